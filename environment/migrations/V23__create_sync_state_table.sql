@@ -0,0 +1,10 @@
+CREATE table sync_state
+(
+    token_address  varchar(255),
+    endpoint       varchar(50),
+    cursor         text,
+    last_timestamp timestamp,
+    run_id         uuid,
+    updated_on     timestamp DEFAULT now(),
+    PRIMARY KEY (token_address, endpoint)
+);
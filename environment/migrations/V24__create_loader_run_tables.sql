@@ -0,0 +1,26 @@
+CREATE table loader_run
+(
+    id          uuid PRIMARY KEY,
+    command     varchar(50),
+    started_on  timestamp,
+    finished_on timestamp,
+    succeeded   boolean
+);
+
+CREATE table loader_run_step
+(
+    id            uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id        uuid REFERENCES loader_run (id),
+    step          varchar(50),
+    token_address varchar(255),
+    started_on    timestamp,
+    finished_on   timestamp,
+    pages_fetched integer,
+    rows_inserted bigint,
+    rows_updated  bigint,
+    error         text,
+    watermark     timestamp
+);
+
+CREATE INDEX idx_loader_run_step_step_token_address_finished_on
+    ON loader_run_step (step, token_address, finished_on DESC);
@@ -0,0 +1,20 @@
+CREATE table reconciliation_issue
+(
+    id            uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id        uuid,
+    token_address varchar(255) NOT NULL,
+    entity        varchar(50)  NOT NULL,
+    day           date         NOT NULL,
+    -- empty for count issues, which are about the whole day
+    record_id     varchar(255) NOT NULL,
+    field         varchar(50)  NOT NULL,
+    api_value     text,
+    db_value      text,
+    -- open, repaired or resolved, the last one when a later run no longer finds it
+    status        varchar(20)  NOT NULL,
+    detected_on   timestamp    NOT NULL,
+    resolved_on   timestamp,
+    UNIQUE (token_address, entity, day, record_id, field)
+);
+
+CREATE INDEX idx_reconciliation_issue_status ON reconciliation_issue (status);
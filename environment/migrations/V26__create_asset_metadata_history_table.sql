@@ -0,0 +1,20 @@
+-- every distinct metadata version of an asset, valid_to is null for the current one
+CREATE table asset_metadata_history
+(
+    id            bigserial PRIMARY KEY,
+    token_address varchar(255) NOT NULL,
+    token_id      integer      NOT NULL,
+    metadata      jsonb        NOT NULL,
+    attribute     varchar,
+    valid_from    timestamp    NOT NULL,
+    valid_to      timestamp
+);
+
+CREATE INDEX idx_asset_metadata_history_token ON asset_metadata_history (token_address, token_id, valid_from);
+CREATE UNIQUE INDEX idx_asset_metadata_history_current ON asset_metadata_history (token_address, token_id) WHERE valid_to IS NULL;
+
+-- the metadata known so far is the first version, there is no earlier state to go by
+INSERT INTO asset_metadata_history (token_address, token_id, metadata, attribute, valid_from)
+SELECT token_address, token_id, metadata, attribute, created_on
+FROM asset
+WHERE metadata <> '{}';
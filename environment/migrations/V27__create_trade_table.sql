@@ -0,0 +1,17 @@
+-- party a of a trade is the buyer, party b sells the NFT
+CREATE table trade
+(
+    transaction_id  integer PRIMARY KEY,
+    status          varchar(50),
+    token_id        integer,
+    token_address   varchar(255),
+    buyer_order_id  integer,
+    seller_order_id integer,
+    -- the seller is taken from order_data, the buyer from the buyer order
+    wallet_from     varchar(255),
+    wallet_to       varchar(255),
+    traded_on       timestamp
+);
+
+CREATE INDEX idx_trade_seller_order_id ON trade (seller_order_id);
+CREATE INDEX idx_trade_token_address_traded_on ON trade (token_address, traded_on);
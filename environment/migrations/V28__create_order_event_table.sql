@@ -0,0 +1,28 @@
+-- every observed state and price of an order, order_data only keeps the latest one
+CREATE table order_event
+(
+    id            bigserial PRIMARY KEY,
+    order_id      integer      NOT NULL,
+    token_id      integer      NOT NULL,
+    token_address varchar(255) NOT NULL,
+    wallet_from   varchar(255),
+    status        varchar(50)  NOT NULL,
+    buy_currency  varchar(50),
+    buy_price     decimal,
+    sell_price    decimal,
+    -- updated_timestamp of the API at the time the state was observed
+    updated_on    timestamp    NOT NULL
+);
+
+-- the same state is read again on every overlapping page, backfill and reconciliation
+CREATE UNIQUE INDEX idx_order_event_state ON order_event (order_id, status, updated_on, buy_price);
+CREATE INDEX idx_order_event_token ON order_event (token_address, token_id, updated_on);
+
+-- orders start as active, the state known so far is the last one
+INSERT INTO order_event (order_id, token_id, token_address, wallet_from, status, buy_currency, buy_price, sell_price, updated_on)
+SELECT order_id, token_id, token_address, wallet_from, 'active', buy_currency, buy_price, sell_price, created_on
+FROM order_data
+WHERE status <> 'active'
+UNION ALL
+SELECT order_id, token_id, token_address, wallet_from, status, buy_currency, buy_price, sell_price, updated_on
+FROM order_data;
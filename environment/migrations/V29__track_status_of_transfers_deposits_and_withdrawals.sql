@@ -0,0 +1,69 @@
+ALTER TABLE withdrawal ADD COLUMN rollup_status varchar(50);
+
+-- every status a transfer, deposit or withdrawal was observed in, the tables only keep the latest one
+CREATE table status_history
+(
+    id             bigserial PRIMARY KEY,
+    entity         varchar(50) NOT NULL,
+    transaction_id integer     NOT NULL,
+    status         varchar(50) NOT NULL,
+    rollup_status  varchar(50),
+    observed_on    timestamp   NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX idx_status_history_state ON status_history (entity, transaction_id, status, coalesce(rollup_status, ''));
+
+-- the status known so far is the first one, there is no earlier state to go by
+INSERT INTO status_history (entity, transaction_id, status, observed_on)
+SELECT 'transfers', transaction_id, status, created_on
+FROM transfer
+UNION ALL
+SELECT 'deposits', transaction_id, status, created_on
+FROM deposit
+UNION ALL
+SELECT 'withdrawals', transaction_id, status, created_on
+FROM withdrawal;
+
+-- items that are not final yet are polled again by the loader
+CREATE INDEX idx_transfer_not_final ON transfer (token_address) WHERE status NOT IN ('success', 'failed');
+CREATE INDEX idx_deposit_not_final ON deposit (token_address) WHERE status NOT IN ('success', 'failed');
+CREATE INDEX idx_withdrawal_not_final ON withdrawal (token_address) WHERE status NOT IN ('success', 'failed');
+
+-- failed transfers, deposits and withdrawals never happened, the ones that are not final yet are labeled as pending
+create or replace view events_view as
+with max_date as (
+    select max(datestamp) as max_datestamp from coin_history)
+select *
+from (
+         select transaction_id,
+                case
+                    when wallet_to='0x0000000000000000000000000000000000000000'
+                        then 'Burned'
+                    else 'Transfer' end || case when status = 'success' then '' else ' pending' end as event, token_address, token_id, wallet_from,
+                case
+                    when wallet_to='0x0000000000000000000000000000000000000000'
+                        then null
+                    else wallet_to end as wallet_to, created_on as timestamp, null as currency, null as price, null as usd_price
+         from transfer
+         where status <> 'failed'
+         union all
+         select od.transaction_id, concat('Trade ', od.status) as event, od.token_address, od.token_id, od.wallet_from, od.wallet_to, od.updated_on as timestamp,
+                od.buy_currency as currency, od.buy_price as price, round(od.buy_price * ch.usd, 2) as usd_price
+         from order_data od
+                  left join coin_history ch on ch.datestamp = case when od.status = 'active' then
+                                                                       (select max_date.max_datestamp from max_date)
+                                                                   else od.updated_on::date end
+             and od.buy_currency = ch.symbol
+         union all
+         select m.transaction_id, 'Mint' as event, token_address, token_id, null as wallet_from, m.wallet as wallet_to, m.minted_on as timestamp, m.currency, m.price, round((m.price * ch.usd), 2) as usd_price
+         from mint m
+                  left join coin_history ch on ch.datestamp = m.minted_on::date and ch.symbol = m.currency
+         union all
+         select transaction_id, 'Deposit' || case when status = 'success' then '' else ' pending' end as event, token_address, token_id, null as wallet_from, wallet as wallet_to, created_on as timestamp, null as currency, null as price, null as usd_price
+         from deposit
+         where status <> 'failed'
+         union all
+         select transaction_id, 'Withdrawal' || case when status = 'success' then '' else ' pending' end as event, token_address, token_id, wallet as wallet_from, null as wallet_to, created_on as timestamp, null as currency, null as price, null as usd_price
+         from withdrawal
+         where status <> 'failed'
+     ) as combined_events;
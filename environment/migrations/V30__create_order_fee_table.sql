@@ -0,0 +1,13 @@
+-- the fees of a sell order, sell_price and buy_price of order_data include them
+CREATE table order_fee
+(
+    order_id  integer      NOT NULL,
+    -- royalty, protocol or ecosystem, the latter is taken by the marketplace
+    fee_type  varchar(50)  NOT NULL,
+    recipient varchar(255) NOT NULL,
+    currency  varchar(50),
+    amount    decimal      NOT NULL,
+    PRIMARY KEY (order_id, fee_type, recipient)
+);
+
+CREATE INDEX idx_order_fee_recipient ON order_fee (fee_type, recipient);
@@ -0,0 +1,16 @@
+-- API records that could not be deserialized or validated, the rest of their page is persisted
+CREATE table quarantined_record
+(
+    id             bigserial PRIMARY KEY,
+    -- same as in sync_state
+    endpoint       varchar(50)  NOT NULL,
+    token_address  varchar(255) NOT NULL,
+    record_id      varchar(255),
+    raw            jsonb        NOT NULL,
+    reason         text         NOT NULL,
+    quarantined_on timestamp    NOT NULL DEFAULT now(),
+    -- set once the record was persisted by reprocess-quarantined
+    reprocessed_on timestamp
+);
+
+CREATE UNIQUE INDEX idx_quarantined_record_open ON quarantined_record (endpoint, record_id) WHERE reprocessed_on IS NULL;
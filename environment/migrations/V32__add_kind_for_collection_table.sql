@@ -0,0 +1,37 @@
+-- set by the loader from config.toml, the attribute trigger no longer needs the token addresses
+alter table collection add column kind varchar(50);
+
+UPDATE collection
+SET kind = CASE address
+               WHEN '0x9e0d99b864e1ac12565125c5a82b59adea5a09cd' THEN 'land'
+               WHEN '0xc1f1da534e227489d617cd742481fd5a23f6a003' THEN 'd1sk'
+               WHEN '0x8cceea8cfb0f8670f4de3a6cd2152925605d19a8' THEN 'illuvitar'
+               WHEN '0x844a2a2b4c139815c1da4bdd447ab558bb9a7d24' THEN 'accessories'
+               WHEN '0x07fb805d026194d188014fc7303e69f412eb7cb1' THEN 'blueprints'
+               WHEN '0x0d78b8aeddb8d3c8b8903a474f8a91855bfdf6f2' THEN 'events'
+    END;
+
+CREATE OR REPLACE FUNCTION update_asset_attribute()
+    RETURNS TRIGGER AS $$
+BEGIN
+    NEW.attribute = CASE (SELECT kind FROM collection WHERE address = NEW.token_address)
+                        WHEN 'land' THEN concat('Tier ', NEW.metadata->>'tier')
+                        WHEN 'd1sk' THEN concat(NEW.metadata->>'name',
+                                                CASE
+                                                    WHEN (NEW.metadata->>'Alpha')::boolean THEN ' Alpha'
+                                                    END, ' Wave ', NEW.metadata->>'Wave')
+                        WHEN 'illuvitar' THEN concat('Set ', NEW.metadata->>'Set', ' Wave ', NEW.metadata->>'Wave', ' Tier ', NEW.metadata->>'Tier',
+                                                     CASE
+                                                         WHEN (NEW.metadata->>'Finish' = 'Holo') THEN ' Holo'
+                                                         END)
+                        WHEN 'accessories' THEN concat('Set ', NEW.metadata->>'Set', ' Tier ', NEW.metadata->>'Tier',
+                                                       ' Stage ', NEW.metadata->>'Stage')
+                        WHEN 'blueprints' THEN concat('Tier ', NEW.metadata->>'Item Tier', ' Stage ', NEW.metadata->>'Item Stage')
+                        WHEN 'events' THEN concat(NEW.metadata->>'Promotion',
+                                                  CASE
+                                                      WHEN (NEW.metadata->>'Finish' = 'Holo') THEN ' Holo'
+                                                      END)
+        END;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
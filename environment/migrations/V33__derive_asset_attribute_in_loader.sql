@@ -0,0 +1,34 @@
+-- attribute and the columns below are derived by the loader from the typed metadata of the collection kind
+DROP TRIGGER update_attribute_trigger ON asset;
+DROP FUNCTION update_asset_attribute();
+
+ALTER TABLE asset ADD COLUMN tier smallint;
+ALTER TABLE asset ADD COLUMN stage smallint;
+ALTER TABLE asset ADD COLUMN wave smallint;
+
+-- values that are not plain numbers are left empty, the same as the loader does
+UPDATE asset a
+SET tier  = CASE WHEN d.tier ~ '^-?\d+$' THEN d.tier::smallint END,
+    stage = CASE WHEN d.stage ~ '^-?\d+$' THEN d.stage::smallint END,
+    wave  = CASE WHEN d.wave ~ '^-?\d+$' THEN d.wave::smallint END
+FROM (SELECT s.token_address,
+             s.token_id,
+             trim(CASE c.kind
+                      WHEN 'land' THEN s.metadata->>'tier'
+                      WHEN 'illuvitar' THEN s.metadata->>'Tier'
+                      WHEN 'accessories' THEN s.metadata->>'Tier'
+                      WHEN 'blueprints' THEN s.metadata->>'Item Tier'
+                 END) AS tier,
+             trim(CASE c.kind
+                      WHEN 'illuvitar' THEN s.metadata->>'Stage'
+                      WHEN 'accessories' THEN s.metadata->>'Stage'
+                      WHEN 'blueprints' THEN s.metadata->>'Item Stage'
+                 END) AS stage,
+             trim(CASE c.kind
+                      WHEN 'd1sk' THEN s.metadata->>'Wave'
+                      WHEN 'illuvitar' THEN s.metadata->>'Wave'
+                 END) AS wave
+      FROM asset s
+               JOIN collection c ON c.address = s.token_address) d
+WHERE d.token_address = a.token_address
+  AND d.token_id = a.token_id;
@@ -0,0 +1,17 @@
+-- The local mirror of the asset images, the files are stored under images.dir of config.toml by their content hash
+CREATE table asset_image
+(
+    token_address   varchar(255) NOT NULL,
+    token_id        integer      NOT NULL,
+    -- the image_url of the metadata the file was downloaded from, a changed url is mirrored again
+    source_url      text         NOT NULL,
+    -- sha256 of the original file, null until it was mirrored
+    content_hash    varchar(64),
+    content_type    varchar(100),
+    mirrored_on     timestamp,
+    failed_attempts integer      NOT NULL DEFAULT 0,
+    last_error      text,
+    PRIMARY KEY (token_address, token_id)
+);
+
+CREATE INDEX idx_asset_image_source_url ON asset_image (source_url) WHERE content_hash IS NOT NULL;
@@ -0,0 +1,23 @@
+-- The market state of every attribute of a collection per day, written by the loader
+-- a snapshot taken again on the same day replaces the earlier one
+CREATE table market_snapshot
+(
+    snapshot_date  date         NOT NULL,
+    token_address  varchar(255) NOT NULL,
+    attribute      varchar(255) NOT NULL,
+    -- the active listing with the lowest USD price, null if there is none
+    floor_price    decimal,
+    floor_currency varchar(50),
+    floor_usd      decimal,
+    listings       bigint       NOT NULL,
+    -- burnt assets are not held by anyone
+    unique_holders bigint       NOT NULL,
+    minted         bigint       NOT NULL,
+    burnt          bigint       NOT NULL,
+    -- filled orders of the 24 hours before taken_on
+    trades_24h     bigint       NOT NULL,
+    volume_24h_eth decimal      NOT NULL,
+    volume_24h_usd decimal      NOT NULL,
+    taken_on       timestamp    NOT NULL,
+    PRIMARY KEY (token_address, attribute, snapshot_date)
+);
@@ -0,0 +1,8 @@
+-- A saved group of wallets, shared by its id. The id is derived from the sorted wallets,
+-- so saving the same group again gives the same id
+CREATE table portfolio
+(
+    id         varchar(12)    NOT NULL PRIMARY KEY,
+    wallets    varchar(255)[] NOT NULL,
+    created_on timestamp      NOT NULL DEFAULT now()
+);
@@ -0,0 +1,68 @@
+-- events_view written out by the loader, one row per transfer, order, mint, deposit and withdrawal, keyed by the
+-- table and the id of the row it is derived from. Failed transfers, deposits and withdrawals have no event.
+-- Feeds are paged by (timestamp, id), newest first
+CREATE table event
+(
+    id             bigserial    PRIMARY KEY,
+    source         varchar(20)  NOT NULL,
+    source_id      integer      NOT NULL,
+    transaction_id integer,
+    event          varchar(50)  NOT NULL,
+    token_address  varchar(255) NOT NULL,
+    token_id       integer      NOT NULL,
+    wallet_from    varchar(255),
+    wallet_to      varchar(255),
+    timestamp      timestamp    NOT NULL,
+    currency       varchar(50),
+    price          decimal,
+    -- at the coin price of the day, active orders at the latest one, filled in once the coin price is known
+    usd_price      decimal
+);
+CREATE UNIQUE INDEX idx_event_source ON event (source, source_id);
+CREATE INDEX idx_event_wallet_from ON event (wallet_from, timestamp DESC, id DESC);
+CREATE INDEX idx_event_wallet_to ON event (wallet_to, timestamp DESC, id DESC);
+CREATE INDEX idx_event_asset ON event (token_address, token_id, timestamp DESC, id DESC);
+CREATE INDEX idx_event_not_priced ON event (timestamp) WHERE usd_price IS NULL AND price IS NOT NULL;
+CREATE INDEX idx_event_active_order ON event (currency) WHERE event = 'Trade active';
+
+INSERT INTO event (source, source_id, transaction_id, event, token_address, token_id, wallet_from, wallet_to, timestamp,
+                   currency, price)
+SELECT *
+FROM (SELECT 'transfer', transaction_id, transaction_id,
+             CASE WHEN wallet_to = '0x0000000000000000000000000000000000000000' THEN 'Burned' ELSE 'Transfer' END ||
+             CASE WHEN status = 'success' THEN '' ELSE ' pending' END,
+             token_address, token_id, wallet_from,
+             CASE WHEN wallet_to = '0x0000000000000000000000000000000000000000' THEN NULL ELSE wallet_to END,
+             created_on, NULL, NULL::decimal
+      FROM transfer
+      WHERE status <> 'failed'
+      UNION ALL
+      SELECT 'order', order_id, transaction_id, concat('Trade ', status), token_address, token_id, wallet_from,
+             wallet_to, updated_on, buy_currency, buy_price
+      FROM order_data
+      UNION ALL
+      SELECT 'mint', transaction_id, transaction_id, 'Mint', token_address, token_id, NULL, wallet, minted_on,
+             currency, price
+      FROM mint
+      UNION ALL
+      SELECT 'deposit', transaction_id, transaction_id,
+             'Deposit' || CASE WHEN status = 'success' THEN '' ELSE ' pending' END, token_address, token_id, NULL,
+             wallet, created_on, NULL, NULL
+      FROM deposit
+      WHERE status <> 'failed'
+      UNION ALL
+      SELECT 'withdrawal', transaction_id, transaction_id,
+             'Withdrawal' || CASE WHEN status = 'success' THEN '' ELSE ' pending' END, token_address, token_id,
+             wallet, NULL, created_on, NULL, NULL
+      FROM withdrawal
+      WHERE status <> 'failed') events
+ORDER BY 9;
+
+UPDATE event e
+SET usd_price = round(e.price * ch.usd, 2)
+FROM coin_history ch
+WHERE ch.symbol = e.currency
+  AND ch.datestamp = CASE
+                         WHEN e.event = 'Trade active' THEN (SELECT max(datestamp) FROM coin_history)
+                         ELSE e.timestamp::date END
+  AND e.price IS NOT NULL;
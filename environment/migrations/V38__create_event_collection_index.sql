@@ -0,0 +1,2 @@
+-- the event feed of a collection, paged by (timestamp, id) either way
+CREATE INDEX idx_event_collection ON event (token_address, timestamp DESC, id DESC);
@@ -0,0 +1,3 @@
+-- assets use their token id as record id, which repeats across collections
+DROP INDEX idx_quarantined_record_open;
+CREATE UNIQUE INDEX idx_quarantined_record_open ON quarantined_record (endpoint, token_address, record_id) WHERE reprocessed_on IS NULL;
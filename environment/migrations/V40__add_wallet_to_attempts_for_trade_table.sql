@@ -0,0 +1,2 @@
+-- failed lookups of the buyer order, trades are given up on after WALLET_TO_MAX_ATTEMPTS
+ALTER TABLE trade ADD COLUMN wallet_to_attempts smallint NOT NULL DEFAULT 0;
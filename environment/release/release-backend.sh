@@ -9,3 +9,4 @@ cargo build --target aarch64-unknown-linux-gnu --release
 rsync -e "ssh -i ~/.ssh/id_rsa_backend" -avO --whole-file --no-perms target/aarch64-unknown-linux-gnu/release/illuvi-analytics-backend ru@backend:/tmp
 # rsync causes the watcher to reload the service multiple times, so have to copy move it like this
 ssh -i ~/.ssh/id_rsa_backend ru@backend "mv /tmp/illuvi-analytics-backend /opt/backend"
+rsync -e "ssh -i ~/.ssh/id_rsa_backend" -avO --no-perms ../../config.toml ru@backend:/opt/backend
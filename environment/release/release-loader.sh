@@ -6,3 +6,4 @@ cargo clean
 cargo build --target aarch64-unknown-linux-gnu --release
 
 rsync -e "ssh -i ~/.ssh/id_rsa_backend" -avO --delete --no-perms target/aarch64-unknown-linux-gnu/release/illuvi-analytics-loader ru@backend:/opt/loader
+rsync -e "ssh -i ~/.ssh/id_rsa_backend" -avO --no-perms ../config.toml ru@backend:/opt/loader
@@ -1,5 +1,8 @@
 mod api_utils;
 mod coingecko;
+mod daemon;
 mod etherscan;
 mod immutablex;
+mod rate_limiter;
 pub mod reader;
+pub mod run_context;
@@ -1,47 +1,172 @@
-use log::error;
-use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{Client, StatusCode};
+use crate::api_reader::rate_limiter::RateLimiter;
+use crate::config;
+use crate::error::LoaderError;
+use crate::utils::metrics;
+use log::warn;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, Response, StatusCode, Url};
 use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+const MAX_RETRIES: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+// a single client is shared by all readers, so the connection pool is reused
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .expect("HTTP client should be buildable")
+});
+
+// one per source of config.toml, keyed by the host of its base URL
+static RATE_LIMITERS: LazyLock<HashMap<String, RateLimiter>> = LazyLock::new(|| {
+    let sources = &config::get().sources;
+    [&sources.immutablex, &sources.etherscan, &sources.coingecko]
+        .into_iter()
+        .map(|source| {
+            let rate_limit = &source.rate_limit;
+            RateLimiter::new(
+                get_host(&source.base_url),
+                rate_limit.requests,
+                rate_limit.per(),
+                rate_limit.daily_cap,
+            )
+        })
+        .map(|limiter| (limiter.host.clone(), limiter))
+        .collect()
+});
 
 pub async fn fetch_single_api_response<T: DeserializeOwned>(
     endpoint: &str,
     headers: &Vec<(&'static str, String)>,
-) -> Option<T> {
-    let client = Client::new();
-    let mut request_builder = client.get(endpoint);
-
-    if !headers.is_empty() {
-        let mut request_headers = HeaderMap::new();
-        for (name, value) in headers {
-            request_headers.insert(*name, HeaderValue::from_str(value.as_str()).unwrap());
-        }
-        request_builder = request_builder.headers(request_headers);
+) -> Result<T, LoaderError> {
+    send_with_retries(endpoint, headers)
+        .await?
+        .json::<T>()
+        .await
+        .map_err(|source| LoaderError::Decode {
+            endpoint: endpoint.to_owned(),
+            source,
+        })
+}
+
+// returns the body and the content type, used for the asset images
+pub async fn fetch_bytes(endpoint: &str) -> Result<(Vec<u8>, Option<String>), LoaderError> {
+    let response = send_with_retries(endpoint, &vec![]).await?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let body = response
+        .bytes()
+        .await
+        .map_err(|source| LoaderError::Transport {
+            endpoint: endpoint.to_owned(),
+            source,
+        })?;
+    Ok((body.to_vec(), content_type))
+}
+
+// Ok is always a 200 response
+async fn send_with_retries(
+    endpoint: &str,
+    headers: &Vec<(&'static str, String)>,
+) -> Result<Response, LoaderError> {
+    let mut request_headers = HeaderMap::new();
+    for (name, value) in headers {
+        request_headers.insert(*name, HeaderValue::from_str(value.as_str()).unwrap());
     }
+    let host = get_host(endpoint);
+    let rate_limiter = RATE_LIMITERS.get(host.as_str());
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        if let Some(limiter) = rate_limiter {
+            if !limiter.acquire().await {
+                return Err(LoaderError::RateLimit {
+                    endpoint: endpoint.to_owned(),
+                });
+            }
+        }
 
-    match request_builder.send().await {
-        Ok(response) => {
-            if response.status() == StatusCode::OK {
-                let result = response.json::<T>().await;
-                match result {
-                    Ok(res) => {
-                        return Some(res);
-                    }
-                    Err(e) => {
-                        error!("Error {e} parsing json body for {endpoint}")
-                    }
+        let (error, retry_after) = match CLIENT
+            .get(endpoint)
+            .headers(request_headers.clone())
+            .send()
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                metrics::record_api_request(host.as_str(), status.as_str());
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    metrics::record_rate_limit_hit(host.as_str(), "remote");
                 }
-            } else {
-                let status_code = response.status();
+                if status == StatusCode::OK {
+                    return Ok(response);
+                }
+
+                let retry_after = get_retry_after(response.headers());
                 let body = response.text().await.unwrap_or(String::from("No body"));
-                error!(
-                    "Request to {endpoint} failed with status code {status_code} and text '{body}'"
-                );
+                let error = LoaderError::HttpStatus {
+                    endpoint: endpoint.to_owned(),
+                    status,
+                    body,
+                };
+                if !is_retryable(status) {
+                    return Err(error);
+                }
+                (error, retry_after)
             }
+            Err(source) => {
+                metrics::record_api_request(host.as_str(), "error");
+                let retryable = source.is_timeout() || source.is_connect() || source.is_request();
+                let error = LoaderError::Transport {
+                    endpoint: endpoint.to_owned(),
+                    source,
+                };
+                if !retryable {
+                    return Err(error);
+                }
+                (error, None)
+            }
+        };
+
+        if attempt == MAX_RETRIES {
+            return Err(error);
         }
-        Err(e) => {
-            error!("Error {e} requesting {endpoint}")
-        }
+        attempt += 1;
+        let sleep_for = retry_after.unwrap_or(backoff).min(MAX_BACKOFF);
+        warn!("Retrying in {sleep_for:?}, attempt {attempt} of {MAX_RETRIES}: {error}");
+        tokio::time::sleep(sleep_for).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
     }
+}
+
+fn get_host(endpoint: &str) -> String {
+    Url::parse(endpoint)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn is_retryable(status_code: StatusCode) -> bool {
+    status_code == StatusCode::TOO_MANY_REQUESTS || status_code.is_server_error()
+}
 
-    None
+// only the delay-seconds form is sent by the APIs in use
+fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
 }
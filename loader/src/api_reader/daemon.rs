@@ -0,0 +1,165 @@
+use crate::api_reader::coingecko::coins_reader;
+use crate::api_reader::etherscan::transactions_reader;
+use crate::api_reader::immutablex::{collection_reader, enricher};
+use crate::api_reader::reader;
+use crate::api_reader::run_context::RunContext;
+use crate::cli::{Entity, Source};
+use crate::utils::env_utils;
+use log::info;
+use sqlx::{Pool, Postgres};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+// the shutdown flag is checked at least this often while waiting for the next job
+const MAX_IDLE_SLEEP: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy)]
+enum Task {
+    Collections,
+    Entity(Entity),
+    Enricher,
+    Etherscan,
+    Coingecko,
+    MatViews,
+    MarketSnapshot,
+    Reconciliation,
+}
+
+struct Job {
+    task: Task,
+    interval: Duration,
+    next_run: Instant,
+}
+
+impl Task {
+    fn name(&self) -> &'static str {
+        match self {
+            Task::Collections => "collections",
+            Task::Entity(entity) => entity.name(),
+            Task::Enricher => "enricher",
+            Task::Etherscan => "etherscan",
+            Task::Coingecko => "coingecko",
+            Task::MatViews => "mat_views",
+            Task::MarketSnapshot => "market_snapshot",
+            Task::Reconciliation => "reconciliation",
+        }
+    }
+
+    // disabled tasks are not scheduled at all
+    fn is_enabled(&self) -> bool {
+        match self {
+            Task::Collections => {
+                Source::Imx.is_enabled() && env_utils::as_parsed::<bool>("COLLECTIONS_ENABLED")
+            }
+            Task::Entity(entity) => Source::Imx.is_enabled() && entity.is_enabled(),
+            Task::Etherscan => Source::Etherscan.is_enabled(),
+            Task::Coingecko => Source::Coingecko.is_enabled(),
+            Task::Reconciliation => {
+                env_utils::as_parsed_or::<bool>("RECONCILIATION_ENABLED", false)
+            }
+            Task::MarketSnapshot => {
+                env_utils::as_parsed_or::<bool>("MARKET_SNAPSHOT_ENABLED", true)
+            }
+            Task::Enricher | Task::MatViews => true,
+        }
+    }
+
+    fn default_interval(&self) -> Duration {
+        match self {
+            Task::Collections | Task::Reconciliation => Duration::from_secs(24 * 60 * 60),
+            Task::Entity(_) | Task::MatViews => Duration::from_secs(5 * 60),
+            Task::Enricher | Task::Etherscan | Task::Coingecko | Task::MarketSnapshot => {
+                Duration::from_secs(60 * 60)
+            }
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        let key = format!("DAEMON_{}_INTERVAL_SECONDS", self.name().to_uppercase());
+        Duration::from_secs(env_utils::as_parsed_or::<u64>(
+            key.as_str(),
+            self.default_interval().as_secs(),
+        ))
+    }
+}
+
+// Jobs run one at a time, so the sources never compete for the DB pool or the API rate limits
+pub async fn run(dry_run: bool, shutdown: Arc<AtomicBool>, pool: &Pool<Postgres>) -> bool {
+    let mut tasks = vec![Task::Collections];
+    tasks.extend(Entity::ALL.map(Task::Entity));
+    tasks.extend([
+        Task::Enricher,
+        Task::Etherscan,
+        Task::Coingecko,
+        Task::MatViews,
+        Task::MarketSnapshot,
+        Task::Reconciliation,
+    ]);
+    let now = Instant::now();
+    let mut jobs: Vec<Job> = tasks
+        .into_iter()
+        .filter(|task| task.is_enabled())
+        .map(|task| Job {
+            task,
+            interval: task.interval(),
+            next_run: now,
+        })
+        .collect();
+    for job in &jobs {
+        info!("Scheduling {} every {:?}", job.task.name(), job.interval);
+    }
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        // jobs that are due at the same time run in the order they were scheduled
+        let due_job = jobs
+            .iter_mut()
+            .filter(|job| job.next_run <= now)
+            .min_by_key(|job| job.next_run);
+        if let Some(job) = due_job {
+            let context = RunContext::new(dry_run, None, shutdown.clone());
+            let task = run_task(job.task, &context, pool);
+            reader::run_journaled(job.task.name(), &context, pool, task).await;
+            job.next_run = Instant::now() + job.interval;
+            continue;
+        }
+
+        let next_run = jobs.iter().map(|job| job.next_run).min();
+        let sleep_for = next_run.map_or(MAX_IDLE_SLEEP, |next_run| {
+            next_run.saturating_duration_since(now).min(MAX_IDLE_SLEEP)
+        });
+        tokio::time::sleep(sleep_for).await;
+    }
+    info!("Daemon stopped");
+    true
+}
+
+// failures are only logged, the job is retried on its next interval
+async fn run_task(task: Task, context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    match task {
+        Task::Collections => {
+            let reader = collection_reader::read_collections(context, pool);
+            reader::run_step(task.name(), None, context, pool, reader).await
+        }
+        Task::Entity(entity) => {
+            reader::read_entity_for_all_collections(entity, context, pool).await
+        }
+        Task::Enricher => {
+            let reader = enricher::enrich(context, pool);
+            reader::run_step(task.name(), None, context, pool, reader).await
+        }
+        Task::Etherscan => {
+            let reader = transactions_reader::read_land_transactions(context, pool);
+            reader::run_step(task.name(), None, context, pool, reader).await
+        }
+        Task::Coingecko => {
+            let reader = coins_reader::read_coins(context, pool);
+            reader::run_step(task.name(), None, context, pool, reader).await
+        }
+        Task::MatViews => reader::refresh_views(context, pool).await,
+        Task::MarketSnapshot => reader::take_market_snapshot(context, pool).await,
+        Task::Reconciliation => reader::reconcile(None, None, true, context, pool).await,
+    }
+}
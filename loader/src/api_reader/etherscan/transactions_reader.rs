@@ -1,5 +1,8 @@
 use crate::api_reader::api_utils;
+use crate::api_reader::run_context::RunContext;
+use crate::config;
 use crate::db::immutablex::mints_handler;
+use crate::error::LoaderError;
 use crate::model::etherscan::{token, transaction};
 use crate::utils::env_utils;
 use ethabi::{decode, ParamType};
@@ -15,43 +18,51 @@ const LAND_CONTRACT: &str = "0x7a47f7707c4b2f2b1def04a47cd8681d48eadeb8";
 const LAND_CONTRACT_CREATION_BLOCK: &str = "14846665";
 const LAND_FUNCTION_NAME: &str = "buyL2";
 
-pub async fn read_land_transactions(pool: &Pool<Postgres>) {
-    if !env_utils::as_parsed::<bool>("ETHERSCAN_ENABLED") {
-        return;
-    }
+pub async fn read_land_transactions(
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
     let api_key = env_utils::as_string("ETHERSCAN_API_KEY");
-    match mints_handler::fetch_all_lands_with_no_price_or_currency(&pool).await {
-        Some(wallets) => {
-            info!("Fetching price and currency for {} wallets", wallets.len());
-            let mut futures = futures::stream::iter(wallets)
-                .map(|wallet| process_wallet(wallet, &api_key, &pool))
-                .buffer_unordered(3);
-
-            // waiting for all to complete
-            while let Some(_) = futures.next().await {}
-        }
-        _ => {}
+    let wallets = mints_handler::fetch_all_lands_with_no_price_or_currency(&pool).await?;
+    info!("Fetching price and currency for {} wallets", wallets.len());
+    let mut futures = futures::stream::iter(wallets)
+        .map(|wallet| process_wallet(wallet, &api_key, context, &pool))
+        .buffer_unordered(config::get().sources.etherscan.max_concurrent_requests);
+
+    // waiting for all to complete
+    while let Some(result) = futures.next().await {
+        result?;
     }
+    Ok(())
 }
 
-async fn process_wallet(wallet: String, api_key: &String, pool: &Pool<Postgres>) {
+async fn process_wallet(
+    wallet: String,
+    api_key: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
     if ILLUVIUM_TEAM_WALLET == wallet {
+        if context.dry_run {
+            return Ok(());
+        }
         // illuvium owned lands were minted for free on IMX directly
         // so it is quite complicated to to find a corresponding transaction on L1 and makes no sense as well
-        mints_handler::update_price_and_currency_for_wallet(
+        let rows = mints_handler::update_price_and_currency_for_wallet(
             wallet.as_str(),
             0.0,
             String::from("(minted by Illuvium team)"),
             pool,
         )
-        .await;
-        return;
+        .await?;
+        context.record_updated(rows);
+        return Ok(());
     }
 
     let mut page = 1;
     let mut transaction_to_token_id = HashMap::new();
     loop {
-        let transactions = fetch_transactions(wallet.clone(), api_key, page).await;
+        let transactions = fetch_transactions(wallet.clone(), api_key, page).await?;
         if transactions.is_empty() {
             break;
         }
@@ -70,7 +81,7 @@ async fn process_wallet(wallet: String, api_key: &String, pool: &Pool<Postgres>)
                     if res.value == "0" {
                         transaction_to_token_id.insert(res.hash.clone(), token_id);
                     } else {
-                        process_transaction(&wallet, &pool, res, token_id).await;
+                        process_transaction(&wallet, context, &pool, res, token_id).await?;
                     }
                 }
                 Err(e) => {
@@ -83,39 +94,36 @@ async fn process_wallet(wallet: String, api_key: &String, pool: &Pool<Postgres>)
     }
 
     if !transaction_to_token_id.is_empty() {
-        process_tokens(&wallet, transaction_to_token_id, api_key, pool).await;
+        process_tokens(&wallet, transaction_to_token_id, api_key, context, pool).await?;
     }
+    Ok(())
 }
 
 async fn fetch_transactions(
     wallet: String,
     api_key: &String,
     page: i8,
-) -> Vec<transaction::TheResult> {
-    let endpoint = format!("https://api.etherscan.io/api?module=account&action=txlist&address={}&page={}&offset=10000&startblock={}&endblock=99999999&sort=asc&apikey={}",
-                           wallet, page, LAND_CONTRACT_CREATION_BLOCK, api_key);
-    return match api_utils::fetch_single_api_response::<transaction::Transaction>(
+) -> Result<Vec<transaction::TheResult>, LoaderError> {
+    let endpoint = format!("{}/api?module=account&action=txlist&address={}&page={}&offset=10000&startblock={}&endblock=99999999&sort=asc&apikey={}",
+                           config::get().sources.etherscan.base_url, wallet, page, LAND_CONTRACT_CREATION_BLOCK, api_key);
+    let transaction = api_utils::fetch_single_api_response::<transaction::Transaction>(
         endpoint.as_str(),
         &vec![],
     )
-    .await
-    {
-        Some(transaction) => {
-            if transaction.status == "1" {
-                return transaction.result.unwrap();
-            }
-            return vec![];
-        }
-        _ => vec![],
-    };
+    .await?;
+    if transaction.status == "1" {
+        return Ok(transaction.result.unwrap());
+    }
+    Ok(vec![])
 }
 
 async fn process_tokens(
     wallet: &String,
     transaction_to_token_id: HashMap<String, i32>,
     api_key: &String,
+    context: &RunContext,
     pool: &Pool<Postgres>,
-) {
+) -> Result<(), LoaderError> {
     fn convert_into_value(value_str: &str, token_decimal_str: &str) -> Decimal {
         let value = Decimal::from_str(value_str).unwrap();
         let token_decimal = Decimal::from_str(&format!(
@@ -134,65 +142,75 @@ async fn process_tokens(
 
     let mut page = 1;
     loop {
-        let tokens = fetch_tokens(wallet.clone(), api_key, page).await;
+        let tokens = fetch_tokens(wallet.clone(), api_key, page).await?;
         if tokens.is_empty() {
             break;
         }
 
         for res in tokens {
             if let Some(token_id) = transaction_to_token_id.get(&res.hash) {
-                mints_handler::update_price_and_currency_for_wallet_and_token_id(
+                if context.dry_run {
+                    continue;
+                }
+                let rows = mints_handler::update_price_and_currency_for_wallet_and_token_id(
                     wallet.as_str(),
                     convert_into_value(res.value.as_str(), res.token_decimal.as_str()),
                     res.token_symbol.to_uppercase(),
                     token_id,
                     &pool,
                 )
-                .await;
+                .await?;
+                context.record_updated(rows);
             }
         }
 
         page += 1;
     }
+    Ok(())
 }
 
 async fn process_transaction(
     wallet: &String,
+    context: &RunContext,
     pool: &&Pool<Postgres>,
     res: transaction::TheResult,
     token_id: i32,
-) {
+) -> Result<(), LoaderError> {
     // The value returned by the Etherscan API endpoint is in Wei, which is the smallest unit of ether
     let wei_value = Decimal::from_str(res.value.as_str()).unwrap();
     let ether_value = wei_value / Decimal::new(10i64.pow(18), 0);
+    if context.dry_run {
+        return Ok(());
+    }
 
-    mints_handler::update_price_and_currency_for_wallet_and_token_id(
+    let rows = mints_handler::update_price_and_currency_for_wallet_and_token_id(
         wallet.as_str(),
         ether_value,
         String::from("ETH"),
         &token_id,
         &pool,
     )
-    .await;
+    .await?;
+    context.record_updated(rows);
+    Ok(())
 }
 
-async fn fetch_tokens(wallet: String, api_key: &String, page: i8) -> Vec<token::TheResult> {
-    let endpoint = format!("https://api.etherscan.io/api?module=account&action=tokentx&address={}&page={}&offset=10000&startblock={}&endblock=99999999&sort=asc&apikey={}",
-                           wallet, page, LAND_CONTRACT_CREATION_BLOCK, api_key);
-    return match api_utils::fetch_single_api_response::<token::Token>(endpoint.as_str(), &vec![])
-        .await
-    {
-        Some(token) => {
-            if token.status == "1" {
-                return token.result.unwrap();
-            }
-            return vec![];
-        }
-        _ => vec![],
-    };
+async fn fetch_tokens(
+    wallet: String,
+    api_key: &String,
+    page: i8,
+) -> Result<Vec<token::TheResult>, LoaderError> {
+    let endpoint = format!("{}/api?module=account&action=tokentx&address={}&page={}&offset=10000&startblock={}&endblock=99999999&sort=asc&apikey={}",
+                           config::get().sources.etherscan.base_url, wallet, page, LAND_CONTRACT_CREATION_BLOCK, api_key);
+    let token =
+        api_utils::fetch_single_api_response::<token::Token>(endpoint.as_str(), &vec![]).await?;
+    if token.status == "1" {
+        return Ok(token.result.unwrap());
+    }
+    Ok(vec![])
 }
 
-fn decode_input_and_get_token_id(input_to_decode: &str) -> Result<i32, Box<dyn std::error::Error>> {
+fn decode_input_and_get_token_id(input_to_decode: &str) -> Result<i32, LoaderError> {
     fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
         (0..s.len())
             .step_by(2)
@@ -200,7 +218,8 @@ fn decode_input_and_get_token_id(input_to_decode: &str) -> Result<i32, Box<dyn s
             .collect()
     }
 
-    let input_decoded = decode_hex(input_to_decode)?;
+    let input_decoded = decode_hex(input_to_decode)
+        .map_err(|e| LoaderError::Validation(format!("input is not hex, {e}")))?;
     let input_params = vec![
         ParamType::Tuple(vec![
             ParamType::Uint(32),
@@ -213,14 +232,19 @@ fn decode_input_and_get_token_id(input_to_decode: &str) -> Result<i32, Box<dyn s
         ]),
         ParamType::FixedBytes(32),
     ];
-    let decoded_input = decode(&input_params, &input_decoded[..])?;
+    let decoded_input = decode(&input_params, &input_decoded[..])
+        .map_err(|e| LoaderError::Validation(format!("input could not be decoded, {e}")))?;
     let token_id = decoded_input[0]
         .clone()
         .into_tuple()
-        .ok_or("Error: could not convert decoded input into tuple")?[0]
+        .ok_or(LoaderError::Validation(String::from(
+            "could not convert decoded input into tuple",
+        )))?[0]
         .clone()
         .into_uint()
-        .ok_or("Error: could not convert tuple element into uint")?
+        .ok_or(LoaderError::Validation(String::from(
+            "could not convert tuple element into uint",
+        )))?
         .as_u32();
     Ok(token_id as i32)
 }
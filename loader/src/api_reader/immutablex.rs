@@ -2,8 +2,12 @@ pub mod assets_reader;
 pub mod collection_reader;
 pub mod deposits_reader;
 pub mod enricher;
+pub mod images_reader;
 pub mod mints_reader;
 pub mod orders_reader;
+mod reconciler;
+pub mod reprocessor;
+pub mod trades_reader;
 pub mod transfers_reader;
 mod utils;
 pub mod withdrawals_reader;
@@ -1,43 +1,76 @@
 use crate::api_reader::api_utils;
-use crate::api_reader::immutablex::utils;
-use crate::db::immutablex::{assets_handler, assets_handler::AssetSaver};
+use crate::api_reader::immutablex::{reconciler, utils};
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
+use crate::db::immutablex::{
+    asset_metadata_history_handler, assets_handler, assets_handler::AssetSaver,
+};
+use crate::error::LoaderError;
 use crate::model::immutablex::asset::{Asset, TheResult};
-use crate::utils::env_utils;
 use sqlx::{Pool, Postgres};
 
-const ASSETS_URL: &str = "https://api.x.immutable.com/v1/assets?page_size=200&collection=";
-const ASSET_URL: &str = "https://api.x.immutable.com/v1/assets";
+const ASSETS_PATH: &str = "/v1/assets?order_by=updated_at&direction=asc&collection=";
+const ASSET_PATH: &str = "/v1/assets";
 
-pub async fn read_assets(token_address: &String, pool: &Pool<Postgres>) {
-    if env_utils::as_parsed::<bool>("ASSETS_ENABLED") {
-        utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Asset>(
-            pool,
-            format!("{}{}", ASSETS_URL, token_address).as_str(),
-            "updated_min_timestamp",
-            token_address,
-            &AssetSaver,
-        )
-        .await;
-    }
+pub async fn read_assets(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Asset>(
+        pool,
+        format!("{}{}", utils::get_url(ASSETS_PATH), token_address).as_str(),
+        ("updated_min_timestamp", "updated_max_timestamp"),
+        token_address,
+        Entity::Assets.name(),
+        context,
+        &AssetSaver,
+    )
+    .await
 }
 
-pub async fn update_metadata(pool: &Pool<Postgres>) {
-    if env_utils::as_parsed::<bool>("ASSETS_ENABLED") {
-        for pair in assets_handler::fetch_all_assets_with_no_metadata(pool).await {
-            let result = api_utils::fetch_single_api_response::<TheResult>(
-                format!("{}/{}/{}", ASSET_URL, pair.0, pair.1).as_str(),
-                &utils::get_immutable_x_api_header(),
-            )
-            .await;
+pub async fn reconcile_assets(
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    reconciler::reconcile::<Asset>(
+        pool,
+        format!("{}{}", utils::get_url(ASSETS_PATH), token_address).as_str(),
+        ("updated_min_timestamp", "updated_max_timestamp"),
+        token_address,
+        repair,
+        context,
+        &AssetSaver,
+    )
+    .await
+}
+
+pub async fn update_metadata(
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    for pair in assets_handler::fetch_all_assets_with_no_metadata(pool).await? {
+        let asset = api_utils::fetch_single_api_response::<TheResult>(
+            format!("{}/{}/{}", utils::get_url(ASSET_PATH), pair.0, pair.1).as_str(),
+            &utils::get_immutable_x_api_header(),
+        )
+        .await?;
 
-            if result.is_some() {
-                let asset = result.unwrap();
-                let metadata = asset.metadata;
-                if metadata.is_some() {
-                    assets_handler::update_metadata(metadata.unwrap(), &pair.0, &pair.1, pool)
-                        .await;
-                }
+        if let Some(metadata) = asset.metadata {
+            if !context.dry_run {
+                let rows =
+                    assets_handler::update_metadata(metadata, &pair.0, &pair.1, pool).await?;
+                context.record_updated(rows);
+                asset_metadata_history_handler::record_metadata_versions(
+                    &vec![pair.0],
+                    &vec![pair.1],
+                    pool,
+                )
+                .await?;
             }
         }
     }
+    Ok(())
 }
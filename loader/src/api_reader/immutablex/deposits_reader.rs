@@ -1,21 +1,56 @@
-use crate::api_reader::immutablex::utils;
+use crate::api_reader::immutablex::{reconciler, utils};
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
 use crate::db::immutablex::deposits_handler::DepositSaver;
+use crate::error::LoaderError;
 use crate::model::immutablex::deposit::Deposit;
-use crate::utils::env_utils;
 use sqlx::{Pool, Postgres};
 
-const DEPOSITS_URL: &str =
-    "https://api.x.immutable.com/v1/deposits?page_size=200&order_by=created_at&token_address=";
+const DEPOSIT_PATH: &str = "/v1/deposits";
+const DEPOSITS_PATH: &str = "/v1/deposits?order_by=created_at&direction=asc&token_address=";
 
-pub async fn read_deposits(token_address: &String, pool: &Pool<Postgres>) {
-    if env_utils::as_parsed::<bool>("DEPOSITS_ENABLED") {
-        utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Deposit>(
-            pool,
-            format!("{}{}", DEPOSITS_URL, token_address).as_str(),
-            "min_timestamp",
-            token_address,
-            &DepositSaver,
-        )
-        .await;
-    }
+pub async fn read_deposits(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Deposit>(
+        pool,
+        format!("{}{}", utils::get_url(DEPOSITS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        Entity::Deposits.name(),
+        context,
+        &DepositSaver,
+    )
+    .await?;
+
+    utils::fetch_and_persist_not_final_api_responses::<Deposit>(
+        pool,
+        &utils::get_url(DEPOSIT_PATH),
+        "deposit",
+        token_address,
+        Entity::Deposits.name(),
+        context,
+        &DepositSaver,
+    )
+    .await
+}
+
+pub async fn reconcile_deposits(
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    reconciler::reconcile::<Deposit>(
+        pool,
+        format!("{}{}", utils::get_url(DEPOSITS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        repair,
+        context,
+        &DepositSaver,
+    )
+    .await
 }
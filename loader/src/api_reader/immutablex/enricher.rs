@@ -1,9 +1,21 @@
-use crate::api_reader::immutablex::{assets_reader, orders_reader};
+use crate::api_reader::immutablex::{assets_reader, images_reader};
+use crate::api_reader::run_context::RunContext;
 use crate::db::immutablex::mints_handler;
+use crate::error::LoaderError;
+use crate::utils::env_utils;
 use sqlx::{Pool, Postgres};
 
-pub async fn enrich(pool: &Pool<Postgres>) {
-    mints_handler::update_d1sk_price(pool).await;
-    assets_reader::update_metadata(pool).await;
-    orders_reader::check_orders_consistency(pool).await;
+pub async fn enrich(context: &RunContext, pool: &Pool<Postgres>) -> Result<(), LoaderError> {
+    if !context.dry_run {
+        let rows = mints_handler::update_d1sk_price(pool).await?;
+        context.record_updated(rows);
+    }
+    if env_utils::as_parsed::<bool>("ASSETS_ENABLED") {
+        assets_reader::update_metadata(context, pool).await?;
+    }
+    // after the metadata, so the images of the enriched assets are mirrored in the same run
+    if env_utils::as_parsed_or::<bool>("IMAGES_ENABLED", false) {
+        images_reader::mirror_images(context, pool).await?;
+    }
+    Ok(())
 }
@@ -0,0 +1,85 @@
+use crate::api_reader::api_utils;
+use crate::api_reader::run_context::RunContext;
+use crate::config;
+use crate::db::immutablex::asset_images_handler;
+use crate::error::LoaderError;
+use crate::utils::env_utils;
+use crate::utils::image_store;
+use crate::utils::image_store::StoredImage;
+use futures::StreamExt;
+use log::{info, warn};
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+
+// images that still fail after this many runs are left to the upstream host
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+// Downloads the images of the metadata into the local store, see image_store. A failing image is recorded
+// in asset_image and retried on the next run, it does not fail the step
+pub async fn mirror_images(context: &RunContext, pool: &Pool<Postgres>) -> Result<(), LoaderError> {
+    async fn mirror_image(url: &str, dry_run: bool) -> Result<StoredImage, LoaderError> {
+        let (bytes, _) = api_utils::fetch_bytes(url).await?;
+        // decoding and encoding would block the other downloads
+        tokio::task::spawn_blocking(move || image_store::store(&bytes, dry_run))
+            .await
+            .map_err(|e| LoaderError::Validation(format!("storing {url} panicked, {e}")))?
+    }
+
+    if !context.dry_run {
+        let rows = asset_images_handler::reuse_mirrored_images(pool).await?;
+        context.record_inserted(rows);
+    }
+    let batch_size = env_utils::as_parsed_or::<i64>("IMAGE_MIRROR_BATCH_SIZE", 500);
+    let images =
+        asset_images_handler::fetch_images_to_mirror(MAX_FAILED_ATTEMPTS, batch_size, pool).await?;
+    // assets sharing an image are downloaded once
+    let mut assets_by_url: HashMap<String, (Vec<String>, Vec<i32>)> = HashMap::new();
+    for (token_address, token_id, url) in images {
+        let assets = assets_by_url.entry(url).or_default();
+        assets.0.push(token_address);
+        assets.1.push(token_id);
+    }
+    info!("Mirroring {} images", assets_by_url.len());
+
+    let mut futures = futures::stream::iter(assets_by_url)
+        .map(|(url, assets)| async move {
+            let result = mirror_image(&url, context.dry_run).await;
+            (url, assets, result)
+        })
+        .buffer_unordered(config::get().images.max_concurrent_downloads);
+    while let Some((url, (token_addresses, token_ids), result)) = futures.next().await {
+        match result {
+            Ok(_) if context.dry_run => info!("Dry run, {url} is not stored"),
+            Ok(stored_image) => {
+                let rows = asset_images_handler::save_mirrored_image(
+                    &token_addresses,
+                    &token_ids,
+                    &url,
+                    &stored_image,
+                    pool,
+                )
+                .await?;
+                context.record_inserted(rows);
+            }
+            Err(e) => {
+                warn!("Could not mirror {url}: {e}");
+                if !context.dry_run {
+                    let rows = asset_images_handler::record_failed_image(
+                        &token_addresses,
+                        &token_ids,
+                        &url,
+                        &e.to_string(),
+                        pool,
+                    )
+                    .await?;
+                    context.record_updated(rows);
+                }
+            }
+        }
+        if context.is_shutdown_requested() {
+            info!("Shutdown requested, stopped mirroring images");
+            break;
+        }
+    }
+    Ok(())
+}
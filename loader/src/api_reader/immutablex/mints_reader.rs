@@ -1,20 +1,44 @@
-use crate::api_reader::immutablex::utils;
+use crate::api_reader::immutablex::{reconciler, utils};
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
 use crate::db::immutablex::mints_handler::MintSaver;
+use crate::error::LoaderError;
 use crate::model::immutablex::mint::Mint;
-use crate::utils::env_utils;
 use sqlx::{Pool, Postgres};
 
-const MINTS_URL: &str = "https://api.x.immutable.com/v1/mints?page_size=200&token_address=";
+const MINTS_PATH: &str = "/v1/mints?direction=asc&token_address=";
 
-pub async fn read_mints(token_address: &String, pool: &Pool<Postgres>) {
-    if env_utils::as_parsed::<bool>("MINTS_ENABLED") {
-        utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Mint>(
-            pool,
-            format!("{}{}", MINTS_URL, token_address).as_str(),
-            "min_timestamp",
-            token_address,
-            &MintSaver,
-        )
-        .await
-    }
+pub async fn read_mints(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Mint>(
+        pool,
+        format!("{}{}", utils::get_url(MINTS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        Entity::Mints.name(),
+        context,
+        &MintSaver,
+    )
+    .await
+}
+
+pub async fn reconcile_mints(
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    reconciler::reconcile::<Mint>(
+        pool,
+        format!("{}{}", utils::get_url(MINTS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        repair,
+        context,
+        &MintSaver,
+    )
+    .await
 }
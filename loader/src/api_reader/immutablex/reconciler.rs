@@ -0,0 +1,203 @@
+use crate::api_reader::immutablex::utils;
+use crate::api_reader::run_context::RunContext;
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::db::immutablex::reconciliation_handler;
+use crate::db::immutablex::reconciliation_handler::{Issue, STATUS_OPEN, STATUS_REPAIRED};
+use crate::error::LoaderError;
+use crate::model::immutablex::shared::PaginatedApi;
+use crate::utils::env_utils;
+use log::info;
+use serde::de::DeserializeOwned;
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime};
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_SAMPLE_DAYS: i64 = 7;
+
+// Compares whole days of data against the API, the days of the window if one is set,
+// otherwise RECONCILIATION_SAMPLE_DAYS random days, so no run has to scan the entire history.
+// With repair on, days with issues are persisted again and only the issues that remain are left open for review
+pub async fn reconcile<T: DeserializeOwned + PaginatedApi>(
+    pool: &Pool<Postgres>,
+    url: &str,
+    // names of the min and max timestamp params
+    timestamp_url_params: (&str, &str),
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    reconcilable: &dyn Reconcilable<T>,
+) -> Result<(), LoaderError> {
+    let days = match context.window {
+        Some((from, to)) => from
+            .date()
+            .iter_days()
+            .take_while(|day| day.and_hms_opt(0, 0, 0).unwrap() < to)
+            .collect(),
+        None => {
+            let sample_days =
+                env_utils::as_parsed_or("RECONCILIATION_SAMPLE_DAYS", DEFAULT_SAMPLE_DAYS);
+            reconciliation_handler::sample_days(
+                reconcilable.table(),
+                token_address,
+                sample_days,
+                pool,
+            )
+            .await?
+        }
+    };
+
+    for day in days {
+        let from = day.and_hms_opt(0, 0, 0).unwrap();
+        let day_url = format!(
+            "{}&{}={}&{}={}",
+            url,
+            timestamp_url_params.0,
+            utils::format_timestamp(&from),
+            timestamp_url_params.1,
+            utils::format_timestamp(&next_day(&day))
+        );
+        reconcile_day(
+            pool,
+            day_url.as_str(),
+            token_address,
+            &day,
+            repair,
+            context,
+            reconcilable,
+        )
+        .await?;
+        if context.is_shutdown_requested() {
+            info!("Shutdown requested, stopped reconciliation after {day}");
+            break;
+        }
+    }
+    Ok(())
+}
+
+async fn reconcile_day<T: DeserializeOwned + PaginatedApi>(
+    pool: &Pool<Postgres>,
+    day_url: &str,
+    token_address: &String,
+    day: &NaiveDate,
+    repair: bool,
+    context: &RunContext,
+    reconcilable: &dyn Reconcilable<T>,
+) -> Result<(), LoaderError> {
+    let from = day.and_hms_opt(0, 0, 0).unwrap();
+    let to = next_day(day);
+    let pages = utils::fetch_all_api_responses_with_cursor::<T>(day_url).await?;
+    let api_records: Vec<Record> = pages
+        .iter()
+        .flat_map(|page| reconcilable.to_records(page))
+        .collect();
+    let db_records = reconcilable
+        .fetch_records(token_address, from, to, pool)
+        .await?;
+    let mut issues = compare(&api_records, &db_records);
+
+    let entity = reconcilable.entity().name();
+    if !issues.is_empty() && repair && !context.dry_run {
+        for page in pages.iter().filter(|page| page.has_results()) {
+            context.record_page(reconcilable.create_one(page, pool).await?);
+        }
+        let db_records = reconcilable
+            .fetch_records(token_address, from, to, pool)
+            .await?;
+        let remaining = compare(&api_records, &db_records);
+        for issue in issues.iter_mut() {
+            let is_remaining = remaining
+                .iter()
+                .any(|other| other.record_id == issue.record_id && other.field == issue.field);
+            if !is_remaining {
+                issue.status = STATUS_REPAIRED;
+            }
+        }
+    }
+    info!(
+        "Reconciled {entity} for {token_address} on {day}: {} API and {} DB records, {} issues of which {} open",
+        api_records.len(),
+        db_records.len(),
+        issues.len(),
+        issues.iter().filter(|issue| issue.status == STATUS_OPEN).count()
+    );
+
+    if !context.dry_run {
+        reconciliation_handler::save_issues(
+            &context.run_id,
+            token_address,
+            entity,
+            day,
+            &issues,
+            pool,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+// Records missing in the DB, records only in the DB and every differing field are separate issues,
+// a count issue is added on top whenever the numbers of records differ
+fn compare(api_records: &Vec<Record>, db_records: &Vec<Record>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    if api_records.len() != db_records.len() {
+        issues.push(new_issue(
+            String::new(),
+            "count",
+            Some(api_records.len().to_string()),
+            Some(db_records.len().to_string()),
+        ));
+    }
+
+    let db_by_id: HashMap<&String, &Record> = db_records
+        .iter()
+        .map(|record| (&record.id, record))
+        .collect();
+    for api_record in api_records {
+        let Some(db_record) = db_by_id.get(&api_record.id) else {
+            issues.push(new_issue(api_record.id.clone(), "missing", None, None));
+            continue;
+        };
+        for (field, api_value) in &api_record.fields {
+            let db_value = db_record
+                .fields
+                .iter()
+                .find(|(db_field, _)| db_field == field)
+                .map(|(_, value)| value);
+            if db_value != Some(api_value) {
+                issues.push(new_issue(
+                    api_record.id.clone(),
+                    field,
+                    Some(api_value.clone()),
+                    db_value.cloned(),
+                ));
+            }
+        }
+    }
+
+    let api_ids: HashSet<&String> = api_records.iter().map(|record| &record.id).collect();
+    for db_record in db_records {
+        if !api_ids.contains(&db_record.id) {
+            issues.push(new_issue(db_record.id.clone(), "unexpected", None, None));
+        }
+    }
+    issues
+}
+
+fn next_day(day: &NaiveDate) -> NaiveDateTime {
+    day.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap()
+}
+
+fn new_issue(
+    record_id: String,
+    field: &str,
+    api_value: Option<String>,
+    db_value: Option<String>,
+) -> Issue {
+    Issue {
+        record_id,
+        field: String::from(field),
+        api_value,
+        db_value,
+        status: STATUS_OPEN,
+    }
+}
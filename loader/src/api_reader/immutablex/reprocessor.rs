@@ -0,0 +1,74 @@
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
+use crate::config;
+use crate::db::immutablex::persistable::Persistable;
+use crate::db::immutablex::quarantine_handler;
+use crate::error::LoaderError;
+use crate::model::immutablex::shared;
+use crate::model::immutablex::shared::PaginatedApi;
+use log::info;
+use sqlx::{Pool, Postgres};
+
+// Parses the open quarantined records of the entity again, the ones that pass now are persisted as if they were
+// just read from the API and marked reprocessed, the others stay open with their current reason
+pub async fn reprocess<T: PaginatedApi>(
+    entity: Entity,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+    persistable: &dyn Persistable<T>,
+) -> Result<(), LoaderError> {
+    let quarantined_records = quarantine_handler::fetch_open_records(entity.name(), pool).await?;
+    info!(
+        "Reprocessing {} quarantined {} records",
+        quarantined_records.len(),
+        entity.name()
+    );
+
+    // persisted in pages of the same size as the paginated reads
+    let page_size = config::get().sources.immutablex.page_size;
+    for chunk in quarantined_records.chunks(page_size) {
+        let mut records = Vec::with_capacity(chunk.len());
+        let mut reprocessed_ids = Vec::with_capacity(chunk.len());
+        for quarantined_record in chunk {
+            match shared::parse_record::<T::Record>(quarantined_record.raw.0.clone()) {
+                Ok(record) => {
+                    records.push(record);
+                    reprocessed_ids.push(quarantined_record.id);
+                }
+                Err(invalid_record) => {
+                    info!(
+                        "Quarantined record {} is still invalid: {}",
+                        quarantined_record.id, invalid_record.reason
+                    );
+                    if !context.dry_run {
+                        quarantine_handler::update_reason(
+                            quarantined_record.id,
+                            &invalid_record.reason,
+                            pool,
+                        )
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        if context.dry_run {
+            info!(
+                "Dry run, {} quarantined records are valid now and not persisted",
+                reprocessed_ids.len()
+            );
+            continue;
+        }
+        let page = T::from_records(records, String::new());
+        if page.has_results() {
+            context.record_page(persistable.create_one(&page, pool).await?);
+            let rows = quarantine_handler::mark_reprocessed(&reprocessed_ids, pool).await?;
+            context.record_updated(rows);
+        }
+        if context.is_shutdown_requested() {
+            info!("Shutdown requested, stopped reprocessing");
+            break;
+        }
+    }
+    Ok(())
+}
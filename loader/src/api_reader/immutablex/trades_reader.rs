@@ -0,0 +1,118 @@
+use crate::api_reader::api_utils;
+use crate::api_reader::immutablex::orders_reader::ORDER_PATH;
+use crate::api_reader::immutablex::{reconciler, utils};
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
+use crate::db::immutablex::trades_handler::TradeSaver;
+use crate::db::immutablex::{orders_handler, trades_handler};
+use crate::error::LoaderError;
+use crate::model::immutablex::order::SingleOrder;
+use crate::model::immutablex::trade::Trade;
+use crate::utils::env_utils;
+use futures::StreamExt;
+use log::{info, warn};
+use sqlx::{Pool, Postgres};
+
+const TRADES_PATH: &str = "/v3/trades?direction=asc&party_b_token_address=";
+
+pub async fn read_trades(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Trade>(
+        pool,
+        format!("{}{}", utils::get_url(TRADES_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        Entity::Trades.name(),
+        context,
+        &TradeSaver,
+    )
+    .await?;
+
+    enrich_wallets(token_address, context, pool).await
+}
+
+pub async fn reconcile_trades(
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    reconciler::reconcile::<Trade>(
+        pool,
+        format!("{}{}", utils::get_url(TRADES_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        repair,
+        context,
+        &TradeSaver,
+    )
+    .await
+}
+
+// Trades come without wallets, the seller is taken from order_data and the buyer from the buyer order,
+// which is fetched once per trade and given up on after WALLET_TO_MAX_ATTEMPTS failures. wallet_to and transaction_id
+// of the filled orders are then derived from the trades
+pub async fn enrich_wallets(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    async fn process_trade(
+        transaction_id: i32,
+        buyer_order_id: i32,
+        context: &RunContext,
+        pool: &Pool<Postgres>,
+    ) -> Result<(), LoaderError> {
+        let url = format!("{}/{}", utils::get_url(ORDER_PATH), buyer_order_id);
+        let order = match api_utils::fetch_single_api_response::<SingleOrder>(
+            url.as_str(),
+            &utils::get_immutable_x_api_header(),
+        )
+        .await
+        {
+            Ok(order) => order,
+            // it is fetched again on the next sync, until WALLET_TO_MAX_ATTEMPTS
+            Err(e) => {
+                warn!("Skipping the buyer wallet of trade {transaction_id}, {e}");
+                return trades_handler::record_wallet_to_attempt(transaction_id, pool).await;
+            }
+        };
+        let rows =
+            trades_handler::update_wallet_to_for_transaction_id(transaction_id, order.wallet, pool)
+                .await?;
+        context.record_updated(rows);
+        Ok(())
+    }
+
+    if context.dry_run {
+        info!("Dry run, wallets of trades are not enriched");
+        return Ok(());
+    }
+    let rows = trades_handler::update_wallet_from_for_token_address(token_address, pool).await?;
+    context.record_updated(rows);
+
+    let max_attempts = env_utils::as_parsed_or::<i16>("WALLET_TO_MAX_ATTEMPTS", 5);
+    let trades = trades_handler::fetch_all_buyer_order_ids_with_no_wallet_to(
+        token_address,
+        max_attempts,
+        pool,
+    )
+    .await?;
+    info!("Fetching buyer wallets for {} trades", trades.len());
+    let mut futures = futures::stream::iter(trades)
+        .map(|(transaction_id, buyer_order_id)| {
+            process_trade(transaction_id, buyer_order_id, context, pool)
+        })
+        .buffer_unordered(utils::max_concurrent_requests());
+    while let Some(result) = futures.next().await {
+        result?;
+    }
+
+    let rows = orders_handler::update_wallet_to_and_transaction_id_from_trades(token_address, pool)
+        .await?;
+    context.record_updated(rows);
+    Ok(())
+}
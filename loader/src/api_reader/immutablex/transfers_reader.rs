@@ -1,21 +1,56 @@
-use crate::api_reader::immutablex::utils;
+use crate::api_reader::immutablex::{reconciler, utils};
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
 use crate::db::immutablex::transfers_handler::TransferSaver;
+use crate::error::LoaderError;
 use crate::model::immutablex::transfer::Transfer;
-use crate::utils::env_utils;
 use sqlx::{Pool, Postgres};
 
-const TRANSFERS_URL: &str =
-    "https://api.x.immutable.com/v1/transfers?page_size=200&order_by=created_at&token_address=";
+const TRANSFER_PATH: &str = "/v1/transfers";
+const TRANSFERS_PATH: &str = "/v1/transfers?order_by=created_at&direction=asc&token_address=";
 
-pub async fn read_transfers(token_address: &String, pool: &Pool<Postgres>) {
-    if env_utils::as_parsed::<bool>("TRANSFERS_ENABLED") {
-        utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Transfer>(
-            pool,
-            format!("{}{}", TRANSFERS_URL, token_address).as_str(),
-            "min_timestamp",
-            token_address,
-            &TransferSaver,
-        )
-        .await;
-    }
+pub async fn read_transfers(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Transfer>(
+        pool,
+        format!("{}{}", utils::get_url(TRANSFERS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        Entity::Transfers.name(),
+        context,
+        &TransferSaver,
+    )
+    .await?;
+
+    utils::fetch_and_persist_not_final_api_responses::<Transfer>(
+        pool,
+        &utils::get_url(TRANSFER_PATH),
+        "transfer",
+        token_address,
+        Entity::Transfers.name(),
+        context,
+        &TransferSaver,
+    )
+    .await
+}
+
+pub async fn reconcile_transfers(
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    reconciler::reconcile::<Transfer>(
+        pool,
+        format!("{}{}", utils::get_url(TRANSFERS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        repair,
+        context,
+        &TransferSaver,
+    )
+    .await
 }
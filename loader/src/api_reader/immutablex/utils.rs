@@ -1,60 +1,277 @@
 use crate::api_reader::api_utils::fetch_single_api_response;
-use crate::db::immutablex::persistable::Persistable;
-use crate::model::immutablex::shared::PaginatedApi;
+use crate::api_reader::run_context::RunContext;
+use crate::config;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::{quarantine_handler, status_history_handler, sync_state_handler};
+use crate::error::LoaderError;
+use crate::model::immutablex::shared;
+use crate::model::immutablex::shared::{InvalidRecord, PaginatedApi, RawPage};
 use crate::utils::env_utils;
-use log::info;
-use serde::de::DeserializeOwned;
+use futures::StreamExt;
+use log::{info, warn};
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::{Pool, Postgres};
 
 const FALLBACK_LAST_TIMESTAMP: &str = "2000-01-12T02:00:00Z";
 
-pub async fn fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp<
-    T: DeserializeOwned + PaginatedApi,
->(
+// resumes from the checkpoint in sync_state, the cursor and the latest persisted timestamp of every page,
+// the last persisted timestamp is only used when there is none
+// backfills read only their window and leave the checkpoint untouched
+pub async fn fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp<T: PaginatedApi>(
     pool: &Pool<Postgres>,
     url: &str,
-    last_timestamp_url_param: &str,
+    // names of the min and max timestamp params
+    timestamp_url_params: (&str, &str),
     token_address: &String,
+    endpoint: &str,
+    context: &RunContext,
     persistable: &dyn Persistable<T>,
-) {
-    let last_timestamp = match persistable.get_last_timestamp(pool, token_address).await {
-        None => String::from(FALLBACK_LAST_TIMESTAMP),
-        Some(value) => value.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+) -> Result<(), LoaderError> {
+    let (last_timestamp, mut cursor) = match context.window {
+        Some((from, _)) => (Some(from), None),
+        None => match sync_state_handler::fetch_sync_state(token_address, endpoint, pool).await? {
+            Some(sync_state) => {
+                info!("Resuming {endpoint} for {token_address} from the saved cursor");
+                (sync_state.last_timestamp, sync_state.cursor)
+            }
+            None => (
+                persistable.get_last_timestamp(pool, token_address).await?,
+                None,
+            ),
+        },
     };
-    let complete_url = format!("{}&{}={}", url, last_timestamp_url_param, last_timestamp);
+    let mut complete_url = format!(
+        "{}&{}={}",
+        url,
+        timestamp_url_params.0,
+        match last_timestamp {
+            None => String::from(FALLBACK_LAST_TIMESTAMP),
+            Some(value) => format_timestamp(&value),
+        }
+    );
+    if let Some((_, to)) = context.window {
+        complete_url = format!(
+            "{}&{}={}",
+            complete_url,
+            timestamp_url_params.1,
+            format_timestamp(&to)
+        );
+    }
 
-    let mut cursor = None;
+    // advances with every persisted page, so a lost cursor does not start over from where this run started
+    let mut watermark = last_timestamp;
+    let mut page = 1;
     loop {
-        let result = fetch_and_get_result::<T>(complete_url.as_str(), cursor).await;
-        if result.is_none() {
-            break;
-        } else {
-            let res = result.unwrap();
-            if res.has_results() {
-                persistable.create_one(&res, pool).await;
+        let result = fetch_and_get_result::<T>(complete_url.as_str(), cursor.clone())
+            .await
+            .map_err(|e| to_pagination_error(&complete_url, page, e))?;
+        match result {
+            None => {
+                info!(
+                    "Reached end of data for {complete_url} after {} pages",
+                    page - 1
+                );
+                // the next run continues from the last cursor
+                if !context.dry_run && context.window.is_none() {
+                    sync_state_handler::save_sync_state(
+                        token_address,
+                        endpoint,
+                        cursor.as_ref(),
+                        watermark,
+                        &context.run_id,
+                        pool,
+                    )
+                    .await
+                    .map_err(|e| to_pagination_error(&complete_url, page, e))?;
+                }
+                break;
+            }
+            Some((res, invalid_records)) => {
+                let next_cursor = res.get_cursor();
+                if context.dry_run {
+                    info!(
+                        "Dry run, page {page} of {complete_url} has {} invalid records and is not persisted",
+                        invalid_records.len()
+                    );
+                    context.record_page(WriteCount::default());
+                } else {
+                    let count = if res.has_results() {
+                        persistable
+                            .create_one(&res, pool)
+                            .await
+                            .map_err(|e| to_pagination_error(&complete_url, page, e))?
+                    } else {
+                        WriteCount::default()
+                    };
+                    context.record_page(count);
+                    quarantine_handler::quarantine_records(
+                        endpoint,
+                        token_address,
+                        &invalid_records,
+                        pool,
+                    )
+                    .await
+                    .map_err(|e| to_pagination_error(&complete_url, page, e))?;
+                    // the checkpoint is saved only after the page is persisted, so no page is skipped on restart
+                    if context.window.is_none() {
+                        watermark = persistable
+                            .get_last_timestamp(pool, token_address)
+                            .await
+                            .map_err(|e| to_pagination_error(&complete_url, page, e))?
+                            .or(watermark);
+                        sync_state_handler::save_sync_state(
+                            token_address,
+                            endpoint,
+                            Some(&next_cursor),
+                            watermark,
+                            &context.run_id,
+                            pool,
+                        )
+                        .await
+                        .map_err(|e| to_pagination_error(&complete_url, page, e))?;
+                    }
+                }
+                if context.is_shutdown_requested() {
+                    info!("Shutdown requested, stopped {complete_url} after page {page}");
+                    break;
+                }
+                cursor = Some(next_cursor);
+                page += 1;
+            }
+        }
+    }
+    context.record_watermark(persistable.get_last_timestamp(pool, token_address).await?);
+    Ok(())
+}
+
+// The paginated reads only move forward in time, so transfers, deposits and withdrawals that are not final yet
+// are fetched again one by one and persisted in pages, see REPOLL_MAX_AGE_DAYS
+pub async fn fetch_and_persist_not_final_api_responses<T: PaginatedApi>(
+    pool: &Pool<Postgres>,
+    // the single item endpoint, the transaction id is appended
+    url: &str,
+    table: &str,
+    token_address: &String,
+    endpoint: &str,
+    context: &RunContext,
+    persistable: &dyn Persistable<T>,
+) -> Result<(), LoaderError> {
+    // backfills only read their window
+    if context.window.is_some() {
+        return Ok(());
+    }
+    let max_age_days = env_utils::as_parsed_or::<i32>("REPOLL_MAX_AGE_DAYS", 30);
+    let transaction_ids = status_history_handler::fetch_not_final_transaction_ids(
+        table,
+        token_address,
+        max_age_days,
+        pool,
+    )
+    .await?;
+    if transaction_ids.is_empty() {
+        return Ok(());
+    }
+    info!(
+        "Polling {} {table} of {token_address} that are not final yet",
+        transaction_ids.len()
+    );
+
+    // persisted in pages of the same size as the paginated reads
+    let page_size = config::get().sources.immutablex.page_size;
+    for (index, chunk) in transaction_ids.chunks(page_size).enumerate() {
+        let page = index as u32 + 1;
+        let mut futures = futures::stream::iter(chunk)
+            .map(|transaction_id| {
+                let url = format!("{}/{}", url, transaction_id);
+                async move {
+                    let result = fetch_single_api_response::<serde_json::Value>(
+                        url.as_str(),
+                        &get_immutable_x_api_header(),
+                    )
+                    .await;
+                    (transaction_id, result)
+                }
+            })
+            .buffer_unordered(max_concurrent_requests());
+        let mut records = Vec::with_capacity(chunk.len());
+        let mut invalid_records = Vec::new();
+        while let Some((transaction_id, result)) = futures.next().await {
+            // it is polled again on the next sync
+            let raw = match result {
+                Ok(raw) => raw,
+                Err(e) => {
+                    warn!("Skipping {table} {transaction_id} of {token_address}, {e}");
+                    continue;
+                }
+            };
+            match shared::parse_record::<T::Record>(raw) {
+                Ok(record) => records.push(record),
+                Err(invalid_record) => invalid_records.push(invalid_record),
             }
-            cursor = Some(res.get_cursor());
+        }
+
+        if context.dry_run {
+            info!(
+                "Dry run, page {page} of {url} has {} invalid records and is not persisted",
+                invalid_records.len()
+            );
+            context.record_page(WriteCount::default());
+        } else {
+            let res = T::from_records(records, String::new());
+            let count = if res.has_results() {
+                persistable
+                    .create_one(&res, pool)
+                    .await
+                    .map_err(|e| to_pagination_error(url, page, e))?
+            } else {
+                WriteCount::default()
+            };
+            context.record_page(count);
+            quarantine_handler::quarantine_records(endpoint, token_address, &invalid_records, pool)
+                .await
+                .map_err(|e| to_pagination_error(url, page, e))?;
+        }
+        if context.is_shutdown_requested() {
+            info!("Shutdown requested, stopped {url} after page {page}");
+            break;
         }
     }
+    Ok(())
 }
 
-pub async fn fetch_all_api_responses_with_cursor<T: DeserializeOwned + PaginatedApi>(
+// invalid records are left out, the syncs quarantine them
+pub async fn fetch_all_api_responses_with_cursor<T: PaginatedApi>(
     url: &str,
-) -> Vec<T> {
+) -> Result<Vec<T>, LoaderError> {
     let mut cursor = None;
+    let mut page = 1;
     let mut results = Vec::new();
-    loop {
-        let result = fetch_and_get_result::<T>(url, cursor).await;
-        if result.is_none() {
-            break;
-        } else {
-            let res = result.unwrap();
-            cursor = Some(res.get_cursor());
-            results.push(res);
+    while let Some((res, invalid_records)) = fetch_and_get_result::<T>(url, cursor)
+        .await
+        .map_err(|e| to_pagination_error(url, page, e))?
+    {
+        if !invalid_records.is_empty() {
+            warn!(
+                "Skipping {} invalid records of {url}",
+                invalid_records.len()
+            );
         }
+        cursor = Some(res.get_cursor());
+        results.push(res);
+        page += 1;
     }
 
-    results
+    Ok(results)
+}
+
+// the path is relative to the configured base URL, e.g. /v1/mints
+pub fn get_url(path: &str) -> String {
+    format!("{}{}", config::get().sources.immutablex.base_url, path)
+}
+
+// more requests in flight than the rate limit allows would only wait for the rate limiter
+pub fn max_concurrent_requests() -> usize {
+    config::get().sources.immutablex.max_concurrent_requests
 }
 
 pub fn get_immutable_x_api_header() -> Vec<(&'static str, String)> {
@@ -62,25 +279,36 @@ pub fn get_immutable_x_api_header() -> Vec<(&'static str, String)> {
     vec![("x-api-key", api_key)]
 }
 
-async fn fetch_and_get_result<T: DeserializeOwned + PaginatedApi>(
+// Ok(None) means there are no more pages
+async fn fetch_and_get_result<T: PaginatedApi>(
     url: &str,
     cursor: Option<String>,
-) -> Option<T> {
-    let url = if cursor.is_some() {
-        url.to_owned() + "&cursor=" + cursor.unwrap().as_str()
-    } else {
-        String::from(url)
+) -> Result<Option<(T, Vec<InvalidRecord>)>, LoaderError> {
+    let url = format!(
+        "{url}&page_size={}",
+        config::get().sources.immutablex.page_size
+    );
+    let url = match cursor {
+        Some(cursor) => url + "&cursor=" + cursor.as_str(),
+        None => url,
     };
-    let response =
-        fetch_single_api_response::<T>(url.as_str(), &get_immutable_x_api_header()).await;
-    match response {
-        Some(result) => {
-            info!("Processing response for {url}");
-            if !result.get_cursor().is_empty() {
-                return Some(result);
-            }
-            None
-        }
-        _ => None,
+    let result =
+        fetch_single_api_response::<RawPage>(url.as_str(), &get_immutable_x_api_header()).await?;
+    info!("Processing response for {url}");
+    if result.cursor.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(shared::parse_page::<T>(result)))
+}
+
+pub fn format_timestamp(timestamp: &NaiveDateTime) -> String {
+    timestamp.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn to_pagination_error(url: &str, page: u32, e: LoaderError) -> LoaderError {
+    LoaderError::Pagination {
+        url: url.to_owned(),
+        page,
+        source: Box::new(e),
     }
 }
@@ -1,21 +1,56 @@
-use crate::api_reader::immutablex::utils;
+use crate::api_reader::immutablex::{reconciler, utils};
+use crate::api_reader::run_context::RunContext;
+use crate::cli::Entity;
 use crate::db::immutablex::withdrawals_handler::WithdrawalSaver;
+use crate::error::LoaderError;
 use crate::model::immutablex::withdrawal::Withdrawal;
-use crate::utils::env_utils;
 use sqlx::{Pool, Postgres};
 
-const WITHDRAWALS_URL: &str =
-    "https://api.x.immutable.com/v1/withdrawals?page_size=200&order_by=created_at&token_address=";
+const WITHDRAWAL_PATH: &str = "/v1/withdrawals";
+const WITHDRAWALS_PATH: &str = "/v1/withdrawals?order_by=created_at&direction=asc&token_address=";
 
-pub async fn read_withdrawals(token_address: &String, pool: &Pool<Postgres>) {
-    if env_utils::as_parsed::<bool>("WITHDRAWALS_ENABLED") {
-        utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Withdrawal>(
-            pool,
-            format!("{}{}", WITHDRAWALS_URL, token_address).as_str(),
-            "min_timestamp",
-            token_address,
-            &WithdrawalSaver,
-        )
-        .await;
-    }
+pub async fn read_withdrawals(
+    token_address: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    utils::fetch_and_persist_all_api_responses_with_cursor_and_last_timestamp::<Withdrawal>(
+        pool,
+        format!("{}{}", utils::get_url(WITHDRAWALS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        Entity::Withdrawals.name(),
+        context,
+        &WithdrawalSaver,
+    )
+    .await?;
+
+    utils::fetch_and_persist_not_final_api_responses::<Withdrawal>(
+        pool,
+        &utils::get_url(WITHDRAWAL_PATH),
+        "withdrawal",
+        token_address,
+        Entity::Withdrawals.name(),
+        context,
+        &WithdrawalSaver,
+    )
+    .await
+}
+
+pub async fn reconcile_withdrawals(
+    token_address: &String,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    reconciler::reconcile::<Withdrawal>(
+        pool,
+        format!("{}{}", utils::get_url(WITHDRAWALS_PATH), token_address).as_str(),
+        ("min_timestamp", "max_timestamp"),
+        token_address,
+        repair,
+        context,
+        &WithdrawalSaver,
+    )
+    .await
 }
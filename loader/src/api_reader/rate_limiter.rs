@@ -0,0 +1,78 @@
+use crate::utils::metrics;
+use sqlx::types::chrono::{NaiveDate, Utc};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+// Token bucket limiter, one instance per API host
+// tokens are refilled continuously, so short bursts up to `capacity` are allowed
+pub struct RateLimiter {
+    pub host: String,
+    capacity: f64,
+    refill_per_second: f64,
+    daily_cap: Option<u32>,
+    state: Mutex<State>,
+}
+
+struct State {
+    tokens: f64,
+    last_refill: Instant,
+    day: NaiveDate,
+    used_today: u32,
+}
+
+impl RateLimiter {
+    pub fn new(host: String, requests: u32, per: Duration, daily_cap: Option<u32>) -> Self {
+        let capacity = requests as f64;
+        Self {
+            host,
+            capacity,
+            refill_per_second: capacity / per.as_secs_f64(),
+            daily_cap,
+            state: Mutex::new(State {
+                tokens: capacity,
+                last_refill: Instant::now(),
+                day: Utc::now().date_naive(),
+                used_today: 0,
+            }),
+        }
+    }
+
+    // Waits until a request may be sent, returns false if the daily cap is exhausted
+    pub async fn acquire(&self) -> bool {
+        let mut throttled = false;
+        loop {
+            let wait_for = {
+                let mut state = self.state.lock().await;
+                let today = Utc::now().date_naive();
+                if state.day != today {
+                    state.day = today;
+                    state.used_today = 0;
+                }
+                if let Some(daily_cap) = self.daily_cap {
+                    if state.used_today >= daily_cap {
+                        metrics::record_rate_limit_hit(&self.host, "daily_cap");
+                        return false;
+                    }
+                }
+
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.refill_per_second).min(self.capacity);
+                state.last_refill = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    state.used_today += 1;
+                    return true;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_second)
+            };
+            // counted once per request, no matter how many times it has to wait
+            if !throttled {
+                throttled = true;
+                metrics::record_rate_limit_hit(&self.host, "local");
+            }
+            tokio::time::sleep(wait_for).await;
+        }
+    }
+}
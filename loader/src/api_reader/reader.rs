@@ -1,44 +1,536 @@
 use crate::api_reader::coingecko::coins_reader;
+use crate::api_reader::daemon;
 use crate::api_reader::etherscan::transactions_reader;
 use crate::api_reader::immutablex::{
-    assets_reader, collection_reader, deposits_reader, enricher, mints_reader, orders_reader,
-    transfers_reader, withdrawals_reader,
+    assets_reader, collection_reader, deposits_reader, enricher, images_reader, mints_reader,
+    orders_reader, reprocessor, trades_reader, transfers_reader, withdrawals_reader,
 };
-use crate::db::db_handler;
-use log::info;
+use crate::api_reader::run_context::RunContext;
+use crate::cli::{
+    BackfillArgs, Command, EnrichArgs, EnrichStep, Entity, ResetSyncStateArgs, Source, SyncArgs,
+};
+use crate::config;
+use crate::db::immutablex::assets_handler::AssetSaver;
+use crate::db::immutablex::deposits_handler::DepositSaver;
+use crate::db::immutablex::events_handler;
+use crate::db::immutablex::mints_handler::MintSaver;
+use crate::db::immutablex::orders_handler::OrderSaver;
+use crate::db::immutablex::sync_state_handler;
+use crate::db::immutablex::trades_handler::TradeSaver;
+use crate::db::immutablex::transfers_handler::TransferSaver;
+use crate::db::immutablex::withdrawals_handler::WithdrawalSaver;
+use crate::db::{db_handler, loader_run_handler, market_snapshot_handler};
+use crate::error::LoaderError;
+use crate::utils::{env_utils, metrics};
+use log::{error, info};
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime, Utc};
 use sqlx::{Pool, Postgres};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::signal::unix::{signal, SignalKind};
 
+// returns false if any of the steps failed, the remaining steps are still attempted
 #[tokio::main]
-pub async fn read() {
+pub async fn run(command: Command, dry_run: bool) -> bool {
+    let shutdown = listen_for_shutdown();
+    // dry runs do not write anything, so they may run next to another instance
+    let lock = if dry_run {
+        None
+    } else {
+        match db_handler::acquire_loader_lock(shutdown.clone()).await {
+            Ok(Some(lock)) => Some(lock),
+            Ok(None) => {
+                error!("Another loader instance is running, exiting");
+                return false;
+            }
+            Err(e) => {
+                error!("Loader lock could not be acquired: {e}");
+                return false;
+            }
+        }
+    };
+
     let pool = db_handler::open_connection().await;
+    let new_context = |window| RunContext::new(dry_run, window, shutdown.clone());
+    let mut succeeded = match command {
+        Command::Sync(args) => {
+            let context = new_context(None);
+            run_journaled("sync", &context, &pool, sync(&args, &context, &pool)).await
+        }
+        Command::Backfill(args) => match get_window((args.from, args.to)) {
+            Some(window) => {
+                let context = new_context(Some(window));
+                run_journaled(
+                    "backfill",
+                    &context,
+                    &pool,
+                    backfill(&args, &context, &pool),
+                )
+                .await
+            }
+            None => false,
+        },
+        Command::RefreshViews => {
+            let context = new_context(None);
+            let refresh = refresh_views(&context, &pool);
+            run_journaled("refresh-views", &context, &pool, refresh).await
+        }
+        Command::MarketSnapshot => {
+            let context = new_context(None);
+            let snapshot = take_market_snapshot(&context, &pool);
+            run_journaled("market-snapshot", &context, &pool, snapshot).await
+        }
+        Command::Enrich(args) => {
+            let context = new_context(None);
+            run_journaled("enrich", &context, &pool, enrich(&args, &context, &pool)).await
+        }
+        Command::Reconcile(args) => match args.from.zip(args.to).map(get_window) {
+            Some(None) => false,
+            window => {
+                let context = new_context(window.flatten());
+                let reconciliation = reconcile(
+                    args.collection.as_ref(),
+                    args.entity,
+                    !args.review_only,
+                    &context,
+                    &pool,
+                );
+                run_journaled("reconcile", &context, &pool, reconciliation).await
+            }
+        },
+        Command::ReprocessQuarantined(args) => {
+            let context = new_context(None);
+            let reprocessing = reprocess_quarantined(args.entity, &context, &pool);
+            run_journaled("reprocess-quarantined", &context, &pool, reprocessing).await
+        }
+        Command::ResetSyncState(args) => reset_sync_state(&args, dry_run, &pool).await,
+        Command::Daemon => daemon::run(dry_run, shutdown.clone(), &pool).await,
+    };
+    db_handler::close_connection(pool).await;
+    if let Some(lock) = lock {
+        match db_handler::release_loader_lock(lock).await {
+            Ok(true) => {}
+            Ok(false) => {
+                error!(
+                    "Loader lock was lost during the run, its writes may overlap another instance"
+                );
+                succeeded = false;
+            }
+            Err(e) => error!("Loader lock could not be released: {e}"),
+        }
+    }
+    succeeded
+}
 
-    read_immutablex(&pool).await;
-    read_etherscan(&pool).await;
-    read_coingecko(&pool).await;
+// SIGTERM is sent by systemd on stop, Ctrl+C is handled the same way for local runs
+fn listen_for_shutdown() -> Arc<AtomicBool> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let flag = shutdown.clone();
+    tokio::spawn(async move {
+        let mut terminate =
+            signal(SignalKind::terminate()).expect("SIGTERM handler should be installable");
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        info!("Shutdown requested, finishing the current page");
+        flag.store(true, Ordering::Relaxed);
+    });
+    shutdown
+}
 
-    db_handler::refresh_mat_views(&pool).await;
-    db_handler::close_connection(pool).await;
+// Records the run in loader_run, its steps are recorded by run_step
+// dry runs are not recorded, as nothing is written to the DB then
+pub async fn run_journaled(
+    command: &str,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+    run: impl Future<Output = bool>,
+) -> bool {
+    info!("Starting {command} run {}", context.run_id);
+    if !context.dry_run {
+        if let Err(e) = loader_run_handler::create_run(&context.run_id, command, pool).await {
+            error!("Run {} could not be recorded: {e}", context.run_id);
+        }
+    }
+    let succeeded = run.await;
+    if !context.dry_run {
+        if let Err(e) = loader_run_handler::finish_run(&context.run_id, succeeded, pool).await {
+            error!("Run {} could not be recorded: {e}", context.run_id);
+        }
+    }
+    metrics::write_to_file();
+    succeeded
+}
+
+// Runs a single reader step and records it in loader_run_step, returns false if the step failed
+pub async fn run_step(
+    step: &str,
+    token_address: Option<&String>,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+    reader: impl Future<Output = Result<(), LoaderError>>,
+) -> bool {
+    let started_on = Utc::now().naive_utc();
+    context.take_step_stats();
+    let result = reader.await;
+    let stats = context.take_step_stats();
+    if !context.dry_run {
+        let error = result.as_ref().err().map(|e| e.to_string());
+        if let Err(e) = loader_run_handler::create_step(
+            &context.run_id,
+            step,
+            token_address,
+            started_on,
+            &stats,
+            error,
+            pool,
+        )
+        .await
+        {
+            error!(
+                "Step {step} of run {} could not be recorded: {e}",
+                context.run_id
+            );
+        }
+    }
+    let step_name = match token_address {
+        Some(token_address) => format!("{step} for {token_address}"),
+        None => String::from(step),
+    };
+    log_failure(step_name.as_str(), result)
+}
+
+async fn sync(args: &SyncArgs, context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    let mut succeeded = true;
+    if is_source_selected(args, Source::Imx) {
+        succeeded &= read_immutablex(args, context, pool).await;
+    }
+    if is_source_selected(args, Source::Etherscan) {
+        let reader = transactions_reader::read_land_transactions(context, pool);
+        succeeded &= run_step("etherscan", None, context, pool, reader).await;
+    }
+    if is_source_selected(args, Source::Coingecko) {
+        let reader = coins_reader::read_coins(context, pool);
+        succeeded &= run_step("coingecko", None, context, pool, reader).await;
+    }
+    succeeded &= refresh_views(context, pool).await;
+    // the last full sync of a day leaves its snapshot
+    let is_full_run = args.collection.is_none() && args.entity.is_none();
+    if is_full_run && env_utils::as_parsed_or::<bool>("MARKET_SNAPSHOT_ENABLED", true) {
+        succeeded &= take_market_snapshot(context, pool).await;
+    }
+    succeeded
+}
+
+fn get_window(range: (NaiveDate, NaiveDate)) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let window = (
+        range.0.and_hms_opt(0, 0, 0).unwrap(),
+        range.1.and_hms_opt(0, 0, 0).unwrap(),
+    );
+    if window.0 >= window.1 {
+        error!("Range is empty, --from should be before --to");
+        return None;
+    }
+    Some(window)
 }
 
-async fn read_immutablex(pool: &Pool<Postgres>) {
-    let collections = collection_reader::read_collections(pool).await;
+async fn backfill(args: &BackfillArgs, context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    info!("Backfilling from {} to {}", args.from, args.to);
+
+    let collections = get_collections(args.collection.as_ref());
+    let mut succeeded = true;
+    for collection in collections {
+        for entity in Entity::ALL {
+            if context.is_shutdown_requested() {
+                return succeeded;
+            }
+            if args.entity.is_none() || args.entity == Some(entity) {
+                succeeded &= read_entity(entity, &collection, context, pool).await;
+            }
+        }
+    }
+    succeeded & refresh_views(context, pool).await
+}
+
+// event prices are refreshed with the views, both follow the coin prices
+pub async fn refresh_views(context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    if context.dry_run {
+        info!("Dry run, materialized views are not refreshed");
+        return true;
+    }
+    let prices = async {
+        let rows = events_handler::refresh_usd_prices(pool).await?;
+        context.record_updated(rows);
+        Ok(())
+    };
+    let succeeded = run_step("event_prices", None, context, pool, prices).await;
+    let refresh = db_handler::refresh_mat_views(pool);
+    succeeded & run_step("mat_views", None, context, pool, refresh).await
+}
+
+pub async fn take_market_snapshot(context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    if context.dry_run {
+        info!("Dry run, market snapshot is not taken");
+        return true;
+    }
+    let snapshot = async {
+        let rows = market_snapshot_handler::save_snapshot(Utc::now().naive_utc(), pool).await?;
+        context.record_inserted(rows);
+        Ok(())
+    };
+    run_step("market_snapshot", None, context, pool, snapshot).await
+}
+
+async fn enrich(args: &EnrichArgs, context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    match args.step {
+        EnrichStep::Metadata => {
+            let reader = assets_reader::update_metadata(context, pool);
+            run_step("metadata", None, context, pool, reader).await
+        }
+        EnrichStep::Images => {
+            let reader = images_reader::mirror_images(context, pool);
+            run_step("images", None, context, pool, reader).await
+        }
+        EnrichStep::WalletTo => {
+            let collections = get_collections(args.collection.as_ref());
+            let mut succeeded = true;
+            for collection in collections {
+                let reader = trades_reader::enrich_wallets(&collection, context, pool);
+                succeeded &= run_step("wallet_to", Some(&collection), context, pool, reader).await;
+            }
+            succeeded
+        }
+        // the orders consistency check became the orders reconciliation
+        EnrichStep::Consistency => {
+            reconcile(
+                args.collection.as_ref(),
+                Some(Entity::Orders),
+                true,
+                context,
+                pool,
+            )
+            .await
+        }
+    }
+}
+
+// every selected entity of every selected collection is a separate step, a failing one does not stop the others
+pub async fn reconcile(
+    collection: Option<&String>,
+    entity: Option<Entity>,
+    repair: bool,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> bool {
+    let collections = get_collections(collection);
+    let mut succeeded = true;
+    for collection in collections {
+        for selected in Entity::ALL {
+            if context.is_shutdown_requested() {
+                return succeeded;
+            }
+            if entity.is_some() && entity != Some(selected) {
+                continue;
+            }
+            let reconciler = async {
+                match selected {
+                    Entity::Mints => {
+                        mints_reader::reconcile_mints(&collection, repair, context, pool).await
+                    }
+                    Entity::Assets => {
+                        assets_reader::reconcile_assets(&collection, repair, context, pool).await
+                    }
+                    Entity::Orders => {
+                        orders_reader::reconcile_orders(&collection, repair, context, pool).await
+                    }
+                    Entity::Trades => {
+                        trades_reader::reconcile_trades(&collection, repair, context, pool).await
+                    }
+                    Entity::Transfers => {
+                        transfers_reader::reconcile_transfers(&collection, repair, context, pool)
+                            .await
+                    }
+                    Entity::Deposits => {
+                        deposits_reader::reconcile_deposits(&collection, repair, context, pool)
+                            .await
+                    }
+                    Entity::Withdrawals => {
+                        withdrawals_reader::reconcile_withdrawals(
+                            &collection,
+                            repair,
+                            context,
+                            pool,
+                        )
+                        .await
+                    }
+                }
+            };
+            let step = format!("reconcile_{}", selected.name());
+            succeeded &=
+                run_step(step.as_str(), Some(&collection), context, pool, reconciler).await;
+        }
+    }
+    succeeded
+}
+
+async fn reprocess_quarantined(
+    entity: Option<Entity>,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> bool {
+    let mut succeeded = true;
+    for selected in Entity::ALL {
+        if context.is_shutdown_requested() {
+            return succeeded;
+        }
+        if entity.is_some() && entity != Some(selected) {
+            continue;
+        }
+        let reprocessing = async {
+            match selected {
+                Entity::Mints => reprocessor::reprocess(selected, context, pool, &MintSaver).await,
+                Entity::Assets => {
+                    reprocessor::reprocess(selected, context, pool, &AssetSaver).await
+                }
+                Entity::Orders => {
+                    reprocessor::reprocess(selected, context, pool, &OrderSaver).await
+                }
+                Entity::Trades => {
+                    reprocessor::reprocess(selected, context, pool, &TradeSaver).await
+                }
+                Entity::Transfers => {
+                    reprocessor::reprocess(selected, context, pool, &TransferSaver).await
+                }
+                Entity::Deposits => {
+                    reprocessor::reprocess(selected, context, pool, &DepositSaver).await
+                }
+                Entity::Withdrawals => {
+                    reprocessor::reprocess(selected, context, pool, &WithdrawalSaver).await
+                }
+            }
+        };
+        let step = format!("reprocess_{}", selected.name());
+        succeeded &= run_step(step.as_str(), None, context, pool, reprocessing).await;
+    }
+    succeeded
+}
+
+async fn reset_sync_state(args: &ResetSyncStateArgs, dry_run: bool, pool: &Pool<Postgres>) -> bool {
+    if dry_run {
+        info!("Dry run, sync state is not reset");
+        return true;
+    }
+    log_failure(
+        "sync state reset",
+        sync_state_handler::reset_sync_state(
+            args.collection.as_ref(),
+            args.entity.map(|entity| entity.name()),
+            pool,
+        )
+        .await
+        .map(|_| ()),
+    )
+}
+
+async fn read_immutablex(args: &SyncArgs, context: &RunContext, pool: &Pool<Postgres>) -> bool {
+    // a targeted run only touches the selected data, the collections and enrichments are left alone
+    let is_full_run = args.collection.is_none() && args.entity.is_none();
+    if is_full_run && env_utils::as_parsed::<bool>("COLLECTIONS_ENABLED") {
+        let reader = collection_reader::read_collections(context, pool);
+        if !run_step("collections", None, context, pool, reader).await {
+            return false;
+        }
+    }
+    let collections = get_collections(args.collection.as_ref());
+
+    let mut succeeded = true;
     for collection in collections {
         info!("Starting for {collection}");
-        mints_reader::read_mints(&collection, pool).await;
-        assets_reader::read_assets(&collection, pool).await;
-        orders_reader::read_orders(&collection, pool).await;
-        transfers_reader::read_transfers(&collection, pool).await;
-        deposits_reader::read_deposits(&collection, pool).await;
-        withdrawals_reader::read_withdrawals(&collection, pool).await;
+        for entity in Entity::ALL {
+            if context.is_shutdown_requested() {
+                return succeeded;
+            }
+            let is_selected = match args.entity {
+                Some(selected) => selected == entity,
+                None => entity.is_enabled(),
+            };
+            if is_selected {
+                succeeded &= read_entity(entity, &collection, context, pool).await;
+            }
+        }
         info!("Done with {collection}");
     }
-    enricher::enrich(pool).await;
+    if is_full_run {
+        let reader = enricher::enrich(context, pool);
+        succeeded &= run_step("enricher", None, context, pool, reader).await;
+    }
+    succeeded
+}
+
+pub async fn read_entity_for_all_collections(
+    entity: Entity,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> bool {
+    let collections = get_collections(None);
+    let mut succeeded = true;
+    for collection in collections {
+        if context.is_shutdown_requested() {
+            break;
+        }
+        succeeded &= read_entity(entity, &collection, context, pool).await;
+    }
+    succeeded
 }
 
-async fn read_etherscan(pool: &Pool<Postgres>) {
-    transactions_reader::read_land_transactions(pool).await;
+async fn read_entity(
+    entity: Entity,
+    collection: &String,
+    context: &RunContext,
+    pool: &Pool<Postgres>,
+) -> bool {
+    let reader = async {
+        match entity {
+            Entity::Mints => mints_reader::read_mints(collection, context, pool).await,
+            Entity::Assets => assets_reader::read_assets(collection, context, pool).await,
+            Entity::Orders => orders_reader::read_orders(collection, context, pool).await,
+            Entity::Trades => trades_reader::read_trades(collection, context, pool).await,
+            Entity::Transfers => transfers_reader::read_transfers(collection, context, pool).await,
+            Entity::Deposits => deposits_reader::read_deposits(collection, context, pool).await,
+            Entity::Withdrawals => {
+                withdrawals_reader::read_withdrawals(collection, context, pool).await
+            }
+        }
+    };
+    run_step(entity.name(), Some(collection), context, pool, reader).await
 }
 
-async fn read_coingecko(pool: &Pool<Postgres>) {
-    coins_reader::read_coins(pool).await;
+// all collections of config.toml unless one is selected explicitly
+fn get_collections(collection: Option<&String>) -> Vec<String> {
+    match collection {
+        Some(collection) => vec![collection.to_lowercase()],
+        None => config::get().collection_addresses(),
+    }
+}
+
+// sources that are not selected explicitly are gated by config.toml
+// filtering by collection or entity implies ImmutableX
+fn is_source_selected(args: &SyncArgs, source: Source) -> bool {
+    match args.source {
+        Some(selected) => selected == source,
+        None if args.collection.is_some() || args.entity.is_some() => source == Source::Imx,
+        None => source.is_enabled(),
+    }
+}
+
+fn log_failure(step: &str, result: Result<(), LoaderError>) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            error!("Source {step} failed: {e}");
+            false
+        }
+    }
 }
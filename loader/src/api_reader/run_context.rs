@@ -0,0 +1,71 @@
+use crate::db::immutablex::persistable::WriteCount;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::Uuid;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Passed down to every reader of a single loader run
+pub struct RunContext {
+    pub run_id: Uuid,
+    // data is fetched and deserialized, but nothing is written to the DB
+    pub dry_run: bool,
+    // set by backfills, sync_state checkpoints are neither used nor updated then
+    pub window: Option<(NaiveDateTime, NaiveDateTime)>,
+    // set on SIGTERM, paginated reads stop after the current page is committed
+    shutdown: Arc<AtomicBool>,
+    step_stats: Mutex<StepStats>,
+}
+
+// Collected while a single step runs, recorded in loader_run_step
+#[derive(Default)]
+pub struct StepStats {
+    pub pages: u32,
+    pub inserted: u64,
+    pub updated: u64,
+    // the latest persisted timestamp of paginated reads
+    pub watermark: Option<NaiveDateTime>,
+}
+
+impl RunContext {
+    pub fn new(
+        dry_run: bool,
+        window: Option<(NaiveDateTime, NaiveDateTime)>,
+        shutdown: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            run_id: Uuid::new_v4(),
+            dry_run,
+            window,
+            shutdown,
+            step_stats: Mutex::new(StepStats::default()),
+        }
+    }
+
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    pub fn record_page(&self, count: WriteCount) {
+        let mut stats = self.step_stats.lock().unwrap();
+        stats.pages += 1;
+        stats.inserted += count.inserted;
+        stats.updated += count.updated;
+    }
+
+    pub fn record_updated(&self, rows: u64) {
+        self.step_stats.lock().unwrap().updated += rows;
+    }
+
+    pub fn record_inserted(&self, rows: u64) {
+        self.step_stats.lock().unwrap().inserted += rows;
+    }
+
+    pub fn record_watermark(&self, watermark: Option<NaiveDateTime>) {
+        self.step_stats.lock().unwrap().watermark = watermark;
+    }
+
+    // returns the stats of the finished step and starts collecting from scratch
+    pub fn take_step_stats(&self) -> StepStats {
+        std::mem::take(&mut *self.step_stats.lock().unwrap())
+    }
+}
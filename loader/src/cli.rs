@@ -0,0 +1,191 @@
+use crate::config;
+use crate::utils::env_utils;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sqlx::types::chrono::NaiveDate;
+
+// Running without a command is the same as `sync` with no filters, which is what the systemd timer does
+#[derive(Parser)]
+#[command(
+    name = "illuvi-analytics",
+    about = "Loads Illuvium data into the analytics DB"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Fetch and validate the data without writing anything to the DB
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Load new data, sources that are not selected explicitly are gated by config.toml, entities by the env flags
+    Sync(SyncArgs),
+    /// Reload ImmutableX data for the given time range, sync state checkpoints are not used nor updated
+    Backfill(BackfillArgs),
+    /// Refresh all materialized views
+    RefreshViews,
+    /// Write today's market snapshot of every attribute, replacing the one taken earlier today
+    MarketSnapshot,
+    /// Run a single enrichment step
+    Enrich(EnrichArgs),
+    /// Compare the data of whole days against the ImmutableX API and record the issues in reconciliation_issue
+    Reconcile(ReconcileArgs),
+    /// Parse the quarantined API records again and persist the ones that are valid now
+    ReprocessQuarantined(ReprocessQuarantinedArgs),
+    /// Rewind the pagination checkpoints, all of them if no collection or entity is given
+    ResetSyncState(ResetSyncStateArgs),
+    /// Keep running and sync every source on its own interval, see DAEMON_*_INTERVAL_SECONDS
+    Daemon,
+}
+
+#[derive(Args, Default)]
+pub struct SyncArgs {
+    #[arg(long, value_enum)]
+    pub source: Option<Source>,
+    /// Token address of an ImmutableX collection
+    #[arg(long)]
+    pub collection: Option<String>,
+    #[arg(long, value_enum)]
+    pub entity: Option<Entity>,
+}
+
+#[derive(Args)]
+pub struct BackfillArgs {
+    /// Start date, inclusive
+    #[arg(long)]
+    pub from: NaiveDate,
+    /// End date, exclusive
+    #[arg(long)]
+    pub to: NaiveDate,
+    /// Token address of an ImmutableX collection
+    #[arg(long)]
+    pub collection: Option<String>,
+    #[arg(long, value_enum)]
+    pub entity: Option<Entity>,
+}
+
+#[derive(Args)]
+pub struct EnrichArgs {
+    #[arg(value_enum)]
+    pub step: EnrichStep,
+    /// Token address of an ImmutableX collection, only used by wallet-to and consistency
+    #[arg(long)]
+    pub collection: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ReconcileArgs {
+    /// Start date, inclusive, random days are sampled if no range is given, see RECONCILIATION_SAMPLE_DAYS
+    #[arg(long, requires = "to")]
+    pub from: Option<NaiveDate>,
+    /// End date, exclusive
+    #[arg(long, requires = "from")]
+    pub to: Option<NaiveDate>,
+    /// Token address of an ImmutableX collection
+    #[arg(long)]
+    pub collection: Option<String>,
+    #[arg(long, value_enum)]
+    pub entity: Option<Entity>,
+    /// Only record the issues, without persisting the API data again
+    #[arg(long)]
+    pub review_only: bool,
+}
+
+#[derive(Args)]
+pub struct ReprocessQuarantinedArgs {
+    #[arg(long, value_enum)]
+    pub entity: Option<Entity>,
+}
+
+#[derive(Args)]
+pub struct ResetSyncStateArgs {
+    /// Token address of an ImmutableX collection
+    #[arg(long)]
+    pub collection: Option<String>,
+    #[arg(long, value_enum)]
+    pub entity: Option<Entity>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Source {
+    Imx,
+    Etherscan,
+    Coingecko,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Entity {
+    Mints,
+    Assets,
+    Orders,
+    Trades,
+    Transfers,
+    Deposits,
+    Withdrawals,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum EnrichStep {
+    Metadata,
+    WalletTo,
+    Images,
+    /// Same as reconcile --entity orders, kept for existing scripts
+    Consistency,
+}
+
+impl Source {
+    // see config.toml
+    pub fn is_enabled(&self) -> bool {
+        let sources = &config::get().sources;
+        match self {
+            Source::Imx => sources.immutablex.enabled,
+            Source::Etherscan => sources.etherscan.enabled,
+            Source::Coingecko => sources.coingecko.enabled,
+        }
+    }
+}
+
+impl Entity {
+    // the order matters, assets and orders of new mints are read in the same run
+    // and trades are matched with the orders read before them
+    pub const ALL: [Entity; 7] = [
+        Entity::Mints,
+        Entity::Assets,
+        Entity::Orders,
+        Entity::Trades,
+        Entity::Transfers,
+        Entity::Deposits,
+        Entity::Withdrawals,
+    ];
+
+    // also used as the endpoint name in sync_state
+    pub fn name(&self) -> &'static str {
+        match self {
+            Entity::Mints => "mints",
+            Entity::Assets => "assets",
+            Entity::Orders => "orders",
+            Entity::Trades => "trades",
+            Entity::Transfers => "transfers",
+            Entity::Deposits => "deposits",
+            Entity::Withdrawals => "withdrawals",
+        }
+    }
+
+    pub fn env_flag(&self) -> &'static str {
+        match self {
+            Entity::Mints => "MINTS_ENABLED",
+            Entity::Assets => "ASSETS_ENABLED",
+            Entity::Orders => "ORDERS_ENABLED",
+            Entity::Trades => "TRADES_ENABLED",
+            Entity::Transfers => "TRANSFERS_ENABLED",
+            Entity::Deposits => "DEPOSITS_ENABLED",
+            Entity::Withdrawals => "WITHDRAWALS_ENABLED",
+        }
+    }
+
+    // entities added later default to enabled, so existing .env files keep working
+    pub fn is_enabled(&self) -> bool {
+        env_utils::as_parsed_or::<bool>(self.env_flag(), true)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::utils::env_utils;
+use serde::Deserialize;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    let path = env_utils::as_parsed_or("CONFIG_FILE", String::from("config.toml"));
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("config file {path} should be readable: {e}"));
+    toml::from_str(&content).unwrap_or_else(|e| panic!("config file {path} should be valid: {e}"))
+});
+
+// The collections and API settings shared with the backend, see config.toml
+#[derive(Deserialize)]
+pub struct Config {
+    pub sources: Sources,
+    pub collections: Vec<CollectionConfig>,
+    pub images: ImagesConfig,
+}
+
+#[derive(Deserialize)]
+pub struct Sources {
+    pub immutablex: SourceConfig,
+    pub etherscan: SourceConfig,
+    pub coingecko: SourceConfig,
+}
+
+#[derive(Deserialize)]
+pub struct SourceConfig {
+    pub enabled: bool,
+    pub base_url: String,
+    // only used by paginated APIs
+    #[serde(default = "default_page_size")]
+    pub page_size: usize,
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    pub rate_limit: RateLimitConfig,
+}
+
+#[derive(Deserialize)]
+pub struct RateLimitConfig {
+    pub requests: u32,
+    pub per_seconds: u64,
+    pub daily_cap: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct ImagesConfig {
+    // the content addressed store, shared with the backend
+    pub dir: String,
+    pub thumbnail_sizes: Vec<u32>,
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_downloads: usize,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionConfig {
+    pub address: String,
+    pub kind: CollectionKind,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionKind {
+    Land,
+    D1sk,
+    Illuvitar,
+    Accessories,
+    Blueprints,
+    Events,
+}
+
+impl CollectionKind {
+    // also stored in collection.kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollectionKind::Land => "land",
+            CollectionKind::D1sk => "d1sk",
+            CollectionKind::Illuvitar => "illuvitar",
+            CollectionKind::Accessories => "accessories",
+            CollectionKind::Blueprints => "blueprints",
+            CollectionKind::Events => "events",
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn per(&self) -> Duration {
+        Duration::from_secs(self.per_seconds)
+    }
+}
+
+impl Config {
+    pub fn collection_addresses(&self) -> Vec<String> {
+        self.collections
+            .iter()
+            .map(|collection| collection.address.to_lowercase())
+            .collect()
+    }
+
+    pub fn kind_of(&self, token_address: &str) -> Option<CollectionKind> {
+        self.collections
+            .iter()
+            .find(|collection| collection.address.eq_ignore_ascii_case(token_address))
+            .map(|collection| collection.kind)
+    }
+
+    // the configured collections of a kind, none if the kind is not loaded
+    pub fn addresses_of(&self, kind: CollectionKind) -> Vec<String> {
+        self.collections
+            .iter()
+            .filter(|collection| collection.kind == kind)
+            .map(|collection| collection.address.to_lowercase())
+            .collect()
+    }
+}
+
+fn default_page_size() -> usize {
+    200
+}
+
+fn default_max_concurrent_requests() -> usize {
+    1
+}
+
+pub fn get() -> &'static Config {
+    &CONFIG
+}
@@ -1,3 +1,5 @@
 pub mod coingecko;
 pub mod db_handler;
 pub mod immutablex;
+pub mod loader_run_handler;
+pub mod market_snapshot_handler;
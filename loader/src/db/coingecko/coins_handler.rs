@@ -1,28 +1,25 @@
+use crate::error::LoaderError;
 use crate::model::coingecko::coin::Coin;
-use log::{error, info};
+use crate::utils::metrics;
+use log::info;
 use sqlx::{query, query_scalar, Pool, Postgres};
 
-pub async fn create_one(coin: &Coin, pool: &Pool<Postgres>) {
-    match query("insert into coin (id, symbol, name) values ($1, $2, $3)")
+pub async fn create_one(coin: &Coin, pool: &Pool<Postgres>) -> Result<u64, LoaderError> {
+    let result = query("insert into coin (id, symbol, name) values ($1, $2, $3)")
         .bind(&coin.id)
         .bind(&coin.symbol.to_uppercase())
         .bind(&coin.name)
         .execute(pool)
-        .await
-    {
-        Ok(result) => {
-            info!("Inserted {} rows", result.rows_affected())
-        }
-        Err(e) => {
-            error!("Couldn't insert values due to {e}")
-        }
-    }
+        .await?;
+    info!("Inserted {} rows", result.rows_affected());
+    metrics::record_rows_inserted("coin", result.rows_affected());
+    Ok(result.rows_affected())
 }
 
 pub async fn get_all_missing_symbols_for_filled_or_active_orders(
     pool: &Pool<Postgres>,
-) -> Option<Vec<String>> {
-    return match query_scalar(
+) -> Result<Vec<String>, LoaderError> {
+    Ok(query_scalar(
         "select distinct(od.buy_currency)
                                from order_data od
                                where not exists(
@@ -32,12 +29,5 @@ pub async fn get_all_missing_symbols_for_filled_or_active_orders(
                                  and od.status in ('filled', 'active')",
     )
     .fetch_all(pool)
-    .await
-    {
-        Ok(result) => Some(result),
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            None
-        }
-    };
+    .await?)
 }
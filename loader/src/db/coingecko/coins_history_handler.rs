@@ -1,11 +1,17 @@
+use crate::error::LoaderError;
 use crate::model::coingecko::coin_history::CoinHistory;
-use log::{error, info};
+use crate::utils::metrics;
+use log::info;
 use sqlx::types::chrono::NaiveDate;
 use sqlx::{query, query_as, FromRow, Pool, Postgres};
 
-pub async fn create_one(coin_history: CoinHistory, date: &NaiveDate, pool: &Pool<Postgres>) {
+pub async fn create_one(
+    coin_history: CoinHistory,
+    date: &NaiveDate,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
     let current_price = coin_history.market_data.unwrap().current_price;
-    match query("insert into coin_history (symbol, btc, eth, eur, jpy, usd, datestamp) values ($1, $2, $3, $4, $5, $6, $7)
+    let result = query("insert into coin_history (symbol, btc, eth, eur, jpy, usd, datestamp) values ($1, $2, $3, $4, $5, $6, $7)
     ON CONFLICT (symbol, datestamp) DO UPDATE SET btc = EXCLUDED.btc, eth = EXCLUDED.eth, eur = EXCLUDED.eur, jpy = EXCLUDED.jpy, usd = EXCLUDED.usd;
     ")
         .bind(coin_history.symbol.to_uppercase())
@@ -15,20 +21,16 @@ pub async fn create_one(coin_history: CoinHistory, date: &NaiveDate, pool: &Pool
         .bind(current_price.jpy)
         .bind(current_price.usd)
         .bind(date)
-        .execute(pool).await {
-        Ok(result) => {
-            info!("Inserted {} rows", result.rows_affected())
-        }
-        Err(e) => {
-            error!("Couldn't insert values due to {e}")
-        }
-    }
+        .execute(pool).await?;
+    info!("Inserted {} rows", result.rows_affected());
+    metrics::record_rows_inserted("coin_history", result.rows_affected());
+    Ok(result.rows_affected())
 }
 
 pub async fn get_all_missing_distinct_date_to_id_pairs(
     pool: &Pool<Postgres>,
-) -> Vec<(NaiveDate, String)> {
-    return match query_as::<_, CoinHistoryData>("select distinct(dates) as datestamp, id from (
+) -> Result<Vec<(NaiveDate, String)>, LoaderError> {
+    let result = query_as::<_, CoinHistoryData>("select distinct(dates) as datestamp, id from (
                                                                 select distinct m.minted_on::date as dates, c.id
                                                                 from mint m
                                                                     join coin c on c.symbol = m.currency
@@ -62,16 +64,12 @@ pub async fn get_all_missing_distinct_date_to_id_pairs(
                                                                   and od.status = 'active'
                                                             ) res;")
         .fetch_all(pool)
-        .await
-    {
-        Ok(result) => result.into_iter()
-            .map(|data| (data.datestamp, data.id))
-            .collect(),
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            vec![]
-        }
-    };
+        .await?;
+
+    Ok(result
+        .into_iter()
+        .map(|data| (data.datestamp, data.id))
+        .collect())
 }
 
 #[derive(FromRow)]
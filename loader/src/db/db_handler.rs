@@ -1,30 +1,101 @@
-use crate::utils::env_utils;
+use crate::error::LoaderError;
+use crate::utils::{env_utils, metrics};
 use log::{error, info};
-use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
-use sqlx::{ConnectOptions, Pool, Postgres};
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPoolOptions};
+use sqlx::{query_scalar, ConnectOptions, Connection, Pool, Postgres};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+// any constant works, it only has to be the same for every loader instance
+const LOADER_LOCK_ID: i64 = 4_242_001;
+// how often the session holding the lock is checked
+const LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
 pub async fn open_connection() -> Pool<Postgres> {
-    let options = PgConnectOptions::new()
+    PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(get_connect_options())
+        .await
+        .expect("DB is not accessible!")
+}
+
+// The advisory lock is held by a dedicated session, so it is never shared with pooled queries
+// returns None if another loader instance holds the lock
+pub async fn acquire_loader_lock(
+    shutdown: Arc<AtomicBool>,
+) -> Result<Option<LoaderLock>, LoaderError> {
+    let mut connection = get_connect_options().connect().await?;
+    let locked: bool = query_scalar("select pg_try_advisory_lock($1)")
+        .bind(LOADER_LOCK_ID)
+        .fetch_one(&mut connection)
+        .await?;
+    if locked {
+        return Ok(Some(watch_loader_lock(connection, shutdown)));
+    }
+    connection.close().await?;
+    Ok(None)
+}
+
+pub struct LoaderLock {
+    stop: oneshot::Sender<()>,
+    // hands the session back when stopped, none if it was lost
+    watcher: JoinHandle<Option<PgConnection>>,
+}
+
+// The lock goes with its session, so the session is pinged for the whole run. When it is lost, another instance
+// may take the lock, the shutdown is requested so the run stops after the current page
+fn watch_loader_lock(mut connection: PgConnection, shutdown: Arc<AtomicBool>) -> LoaderLock {
+    let (stop, mut stopped) = oneshot::channel();
+    let watcher = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(LOCK_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = &mut stopped => return Some(connection),
+                _ = interval.tick() => {
+                    if let Err(e) = connection.ping().await {
+                        error!("Loader lock was lost with its session, stopping the run: {e}");
+                        shutdown.store(true, Ordering::Relaxed);
+                        return None;
+                    }
+                }
+            }
+        }
+    });
+    LoaderLock { stop, watcher }
+}
+
+// closing the session releases the lock as well, returns false if the lock was lost during the run
+pub async fn release_loader_lock(lock: LoaderLock) -> Result<bool, LoaderError> {
+    // the watcher may have stopped already, the send fails then
+    let _ = lock.stop.send(());
+    match lock.watcher.await {
+        Ok(Some(connection)) => {
+            connection.close().await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+fn get_connect_options() -> PgConnectOptions {
+    PgConnectOptions::new()
         .host(env_utils::as_string("DB_HOST").as_str())
         .port(env_utils::as_parsed::<u16>("DB_PORT"))
         .database(env_utils::as_string("DB_DATABASE").as_str())
         .username(env_utils::as_string("DB_USERNAME").as_str())
         .password(env_utils::as_string("DB_PASSWORD").as_str())
         .disable_statement_logging()
-        .clone();
-
-    PgPoolOptions::new()
-        .max_connections(5)
-        .connect_with(options)
-        .await
-        .expect("DB is not accessible!")
+        .clone()
 }
 
 pub async fn close_connection(pool: Pool<Postgres>) {
     pool.close().await;
 }
 
-pub async fn refresh_mat_views(pool: &Pool<Postgres>) {
+pub async fn refresh_mat_views(pool: &Pool<Postgres>) -> Result<(), LoaderError> {
     let mat_views = vec![
         "asset_current_owner_mat_view",
         "trade_volume_mat_view",
@@ -33,16 +104,12 @@ pub async fn refresh_mat_views(pool: &Pool<Postgres>) {
         "floor_data_mat_by_attribute_view",
     ];
     for view in mat_views {
-        match sqlx::query(&format!("refresh materialized view {}", view))
+        let started = Instant::now();
+        sqlx::query(&format!("refresh materialized view {}", view))
             .execute(pool)
-            .await
-        {
-            Ok(_) => {
-                info!("Successfully refreshed {view}")
-            }
-            Err(e) => {
-                error!("Error {e} refreshing {view}")
-            }
-        };
+            .await?;
+        metrics::record_mat_view_refresh(view, started.elapsed());
+        info!("Successfully refreshed {view}");
     }
+    Ok(())
 }
@@ -1,8 +1,19 @@
+pub mod asset_images_handler;
+pub mod asset_metadata_history_handler;
 pub mod assets_handler;
 pub mod collection_handler;
 pub mod deposits_handler;
+pub mod events_handler;
 pub mod mints_handler;
+pub mod order_events_handler;
+pub mod order_fees_handler;
 pub mod orders_handler;
 pub mod persistable;
+pub mod quarantine_handler;
+pub mod reconcilable;
+pub mod reconciliation_handler;
+pub mod status_history_handler;
+pub mod sync_state_handler;
+pub mod trades_handler;
 pub mod transfers_handler;
 pub mod withdrawals_handler;
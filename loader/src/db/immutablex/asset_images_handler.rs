@@ -0,0 +1,108 @@
+use crate::error::LoaderError;
+use crate::utils::image_store::StoredImage;
+use crate::utils::metrics;
+use log::info;
+use sqlx::{query, query_as, Pool, Postgres};
+
+// Assets whose image_url was mirrored for another asset already get the same file, e.g. all D1sks of a wave
+pub async fn reuse_mirrored_images(pool: &Pool<Postgres>) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into asset_image (token_address, token_id, source_url, content_hash, content_type, mirrored_on)
+         select distinct on (a.token_address, a.token_id) a.token_address, a.token_id, m.source_url, m.content_hash,
+                m.content_type, now()
+         from asset a
+                  join asset_image m on m.source_url = a.metadata->>'image_url' and m.content_hash is not null
+                  left join asset_image i on i.token_address = a.token_address and i.token_id = a.token_id
+         where i.token_id is null or i.source_url <> m.source_url or i.content_hash is null
+         order by a.token_address, a.token_id
+         on conflict (token_address, token_id) do update set source_url = excluded.source_url,
+             content_hash = excluded.content_hash, content_type = excluded.content_type,
+             mirrored_on = excluded.mirrored_on, failed_attempts = 0, last_error = null",
+    )
+    .execute(pool)
+    .await?;
+
+    info!("Reused {} mirrored images", result.rows_affected());
+    metrics::record_rows_inserted("asset_image", result.rows_affected());
+    Ok(result.rows_affected())
+}
+
+// (token_address, token_id, image_url) of the assets that were never mirrored or whose image_url changed since,
+// failed ones are retried until max_failed_attempts
+pub async fn fetch_images_to_mirror(
+    max_failed_attempts: i32,
+    limit: i64,
+    pool: &Pool<Postgres>,
+) -> Result<Vec<(String, i32, String)>, LoaderError> {
+    Ok(query_as(
+        "select a.token_address, a.token_id, a.metadata->>'image_url'
+         from asset a
+                  left join asset_image i on i.token_address = a.token_address and i.token_id = a.token_id
+         where a.metadata->>'image_url' is not null
+           and (i.token_id is null
+                or i.source_url <> a.metadata->>'image_url'
+                or (i.content_hash is null and i.failed_attempts < $1))
+         order by a.token_address, a.token_id
+         limit $2",
+    )
+    .bind(max_failed_attempts)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn save_mirrored_image(
+    token_addresses: &Vec<String>,
+    token_ids: &Vec<i32>,
+    source_url: &str,
+    stored_image: &StoredImage,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into asset_image (token_address, token_id, source_url, content_hash, content_type, mirrored_on)
+         select t.token_address, t.token_id, $3, $4, $5, now()
+         from unnest($1::varchar[], $2::integer[]) as t(token_address, token_id)
+         on conflict (token_address, token_id) do update set source_url = excluded.source_url,
+             content_hash = excluded.content_hash, content_type = excluded.content_type,
+             mirrored_on = excluded.mirrored_on, failed_attempts = 0, last_error = null",
+    )
+    .bind(token_addresses)
+    .bind(token_ids)
+    .bind(source_url)
+    .bind(&stored_image.content_hash)
+    .bind(&stored_image.content_type)
+    .execute(pool)
+    .await?;
+
+    metrics::record_rows_inserted("asset_image", result.rows_affected());
+    Ok(result.rows_affected())
+}
+
+// the attempts start over when the image_url changed
+pub async fn record_failed_image(
+    token_addresses: &Vec<String>,
+    token_ids: &Vec<i32>,
+    source_url: &str,
+    error: &str,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into asset_image (token_address, token_id, source_url, failed_attempts, last_error)
+         select t.token_address, t.token_id, $3, 1, $4
+         from unnest($1::varchar[], $2::integer[]) as t(token_address, token_id)
+         on conflict (token_address, token_id) do update set
+             failed_attempts = case when asset_image.source_url = excluded.source_url
+                                    then asset_image.failed_attempts + 1 else 1 end,
+             source_url = excluded.source_url, content_hash = null, content_type = null, mirrored_on = null,
+             last_error = excluded.last_error",
+    )
+    .bind(token_addresses)
+    .bind(token_ids)
+    .bind(source_url)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    metrics::record_rows_updated("asset_image", result.rows_affected());
+    Ok(result.rows_affected())
+}
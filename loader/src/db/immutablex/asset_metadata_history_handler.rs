@@ -0,0 +1,48 @@
+use crate::error::LoaderError;
+use crate::utils::metrics;
+use log::info;
+use sqlx::{query, Pool, Postgres};
+
+// Adds a version for every given asset whose metadata differs from its current version, closing the latter
+// versions start at the updated_on of the asset and the attribute is the one derived by AssetSaver,
+// so the assets have to be persisted first
+pub async fn record_metadata_versions(
+    token_addresses: &Vec<String>,
+    token_ids: &Vec<i32>,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let mut transaction = pool.begin().await?;
+    query(
+        "update asset_metadata_history h set valid_to = a.updated_on
+         from asset a join unnest($1::varchar[], $2::integer[]) as t(token_address, token_id)
+                           on a.token_address = t.token_address and a.token_id = t.token_id
+         where h.token_address = a.token_address and h.token_id = a.token_id and h.valid_to is null
+           and a.metadata <> '{}' and h.metadata <> a.metadata",
+    )
+    .bind(token_addresses)
+    .bind(token_ids)
+    .execute(&mut *transaction)
+    .await?;
+    // assets with no open version are either new or were closed just now
+    let result = query(
+        "insert into asset_metadata_history (token_address, token_id, metadata, attribute, valid_from)
+         select a.token_address, a.token_id, a.metadata, a.attribute, a.updated_on
+         from asset a join unnest($1::varchar[], $2::integer[]) as t(token_address, token_id)
+                           on a.token_address = t.token_address and a.token_id = t.token_id
+         where a.metadata <> '{}'
+           and not exists(select 1 from asset_metadata_history h
+                          where h.token_address = a.token_address and h.token_id = a.token_id and h.valid_to is null)",
+    )
+    .bind(token_addresses)
+    .bind(token_ids)
+    .execute(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    info!(
+        "Recorded {} asset metadata versions",
+        result.rows_affected()
+    );
+    metrics::record_rows_inserted("asset_metadata_history", result.rows_affected());
+    Ok(result.rows_affected())
+}
@@ -1,89 +1,191 @@
-use crate::db::immutablex::persistable::Persistable;
+use crate::cli::Entity;
+use crate::config;
+use crate::db::immutablex::asset_metadata_history_handler;
+use crate::db::immutablex::persistable;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::error::LoaderError;
 use crate::model::immutablex::asset::Asset;
+use crate::model::immutablex::metadata;
+use crate::model::immutablex::metadata::DerivedMetadata;
+use crate::model::immutablex::shared;
+use crate::utils::metrics;
 use async_trait::async_trait;
-use log::{error, info, warn};
-use sqlx::types::chrono::{DateTime, NaiveDateTime};
+use log::{info, warn};
+use serde_json::Value;
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::types::Json;
 use sqlx::{query, query_as, Pool, Postgres, QueryBuilder, Row};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 pub struct AssetSaver;
 
 #[async_trait]
 impl Persistable<Asset> for AssetSaver {
-    async fn create_one(&self, asset: &Asset, pool: &Pool<Postgres>) {
+    async fn create_one(
+        &self,
+        asset: &Asset,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError> {
         let asset_result = &asset.result;
-        // attribute column is set by Postgres function
+        let parsed = asset_result
+            .iter()
+            .map(|res| {
+                Ok((
+                    shared::parse_integer("token_id", &res.token_id)?,
+                    shared::parse_timestamp("created_at", &res.created_at)?,
+                    shared::parse_timestamp("updated_at", &res.updated_at)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(LoaderError::Validation)?;
+        let metadata_values: Vec<HashMap<String, Value>> = asset_result
+            .iter()
+            .map(|res| match &res.metadata {
+                None => {
+                    warn!("Missing metadata for {}", res.token_id);
+                    HashMap::new()
+                }
+                Some(metadata) => {
+                    let mut metadata_values = metadata.clone();
+                    // one of the collections has a "wrong" field name
+                    // a bug was raised
+                    if let Some(value) = metadata_values.remove("image") {
+                        metadata_values.insert("image_url".to_string(), value);
+                    }
+                    metadata_values
+                }
+            })
+            .collect();
+        let derived = derive_all(
+            asset_result
+                .iter()
+                .zip(&metadata_values)
+                .map(|(res, metadata)| {
+                    (res.token_address.as_str(), res.token_id.as_str(), metadata)
+                }),
+        );
         let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
-            "insert into asset (token_id, token_address, metadata, current_owner, created_on, updated_on) ",
+            "insert into asset (token_id, token_address, metadata, current_owner, created_on, updated_on,
+                                attribute, tier, stage, wave) ",
         );
 
-        query_builder.push_values(asset_result, |mut builder, res| {
-            let metadata = &res.metadata;
-            let mut metadata_values = HashMap::new();
-            if metadata.is_none() {
-                warn!("Missing metadata for {}", res.token_id)
-            } else {
-                metadata_values = metadata.clone().unwrap();
-                // one of the collections has a "wrong" field name
-                // a bug was raised
-                if let Some(value) = metadata_values.remove("image") {
-                    metadata_values.insert("image_url".to_string(), value);
-                }
-            }
-            builder
-                .push_bind(res.token_id.parse::<i32>().unwrap())
-                .push_bind(&res.token_address)
-                .push_bind(Json(metadata_values))
-                .push_bind(&res.current_owner)
-                .push_bind(DateTime::parse_from_rfc3339(&res.created_at).unwrap())
-                .push_bind(DateTime::parse_from_rfc3339(&res.updated_at).unwrap());
-        });
+        query_builder.push_values(
+            asset_result
+                .iter()
+                .zip(&parsed)
+                .zip(metadata_values)
+                .zip(derived),
+            |mut builder,
+             (((res, (token_id, created_on, updated_on)), metadata_values), derived)| {
+                builder
+                    .push_bind(token_id)
+                    .push_bind(&res.token_address)
+                    .push_bind(Json(metadata_values))
+                    .push_bind(&res.current_owner)
+                    .push_bind(created_on)
+                    .push_bind(updated_on)
+                    .push_bind(derived.as_ref().map(|derived| derived.attribute.clone()))
+                    .push_bind(derived.as_ref().and_then(|derived| derived.tier))
+                    .push_bind(derived.as_ref().and_then(|derived| derived.stage))
+                    .push_bind(derived.as_ref().and_then(|derived| derived.wave));
+            },
+        );
 
-        let query = query_builder
-            .push(" ON CONFLICT (token_id, token_address) DO UPDATE SET current_owner = EXCLUDED.current_owner,
-                        updated_on = EXCLUDED.updated_on, metadata = EXCLUDED.metadata;")
-            .build();
-        match query.execute(pool).await {
-            Ok(result) => {
-                info!("Inserted {} rows", result.rows_affected())
-            }
-            Err(e) => {
-                error!("Couldn't insert values due to {e}")
-            }
-        }
+        query_builder.push(" ON CONFLICT (token_id, token_address) DO UPDATE SET current_owner = EXCLUDED.current_owner,
+                        updated_on = EXCLUDED.updated_on, metadata = EXCLUDED.metadata, attribute = EXCLUDED.attribute,
+                        tier = EXCLUDED.tier, stage = EXCLUDED.stage, wave = EXCLUDED.wave");
+        let result = persistable::execute_upsert("asset", query_builder, pool).await?;
+        info!(
+            "Inserted {} and updated {} rows",
+            result.inserted, result.updated
+        );
+
+        let token_addresses = asset_result
+            .iter()
+            .map(|res| res.token_address.clone())
+            .collect();
+        let token_ids = parsed.iter().map(|(token_id, _, _)| *token_id).collect();
+        asset_metadata_history_handler::record_metadata_versions(
+            &token_addresses,
+            &token_ids,
+            pool,
+        )
+        .await?;
+        Ok(result)
     }
 
     async fn get_last_timestamp(
         &self,
         pool: &Pool<Postgres>,
         token_address: &String,
-    ) -> Option<NaiveDateTime> {
+    ) -> Result<Option<NaiveDateTime>, LoaderError> {
         let result: (Option<NaiveDateTime>,) =
             query_as("select max(updated_on) from asset where token_address=$1")
                 .bind(token_address)
                 .fetch_one(pool)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("Couldn't fetch data! {e}");
-                    (None,)
-                });
+                .await?;
 
-        result.0
+        Ok(result.0)
     }
 }
 
-pub async fn fetch_all_assets_with_no_metadata(pool: &Pool<Postgres>) -> Vec<(String, i32)> {
-    return match query("select token_address, token_id from asset where metadata='{}'")
+#[async_trait]
+impl Reconcilable<Asset> for AssetSaver {
+    fn entity(&self) -> Entity {
+        Entity::Assets
+    }
+
+    fn table(&self) -> (&'static str, &'static str) {
+        ("asset", "updated_on")
+    }
+
+    // metadata is left out, it is refreshed by the enricher anyway
+    fn to_records(&self, asset: &Asset) -> Vec<Record> {
+        asset
+            .result
+            .iter()
+            .map(|res| {
+                Record::new(
+                    &res.token_id,
+                    vec![("current_owner", res.current_owner.clone())],
+                )
+            })
+            .collect()
+    }
+
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError> {
+        let rows: Vec<(i32, String)> = query_as(
+            "select token_id, current_owner from asset where token_address=$1 and updated_on >= $2 and updated_on < $3",
+        )
+        .bind(token_address)
+        .bind(from)
+        .bind(to)
         .fetch_all(pool)
-        .await
-    {
-        Ok(result) => result.iter().map(|row| (row.get(0), row.get(1))).collect(),
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            vec![]
-        }
-    };
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(token_id, current_owner)| {
+                Record::new(token_id, vec![("current_owner", current_owner)])
+            })
+            .collect())
+    }
+}
+
+pub async fn fetch_all_assets_with_no_metadata(
+    pool: &Pool<Postgres>,
+) -> Result<Vec<(String, i32)>, LoaderError> {
+    let result = query("select token_address, token_id from asset where metadata='{}'")
+        .fetch_all(pool)
+        .await?;
+    Ok(result.iter().map(|row| (row.get(0), row.get(1))).collect())
 }
 
 pub async fn update_metadata(
@@ -91,22 +193,70 @@ pub async fn update_metadata(
     token_address: &String,
     token_id: &i32,
     pool: &Pool<Postgres>,
-) {
-    match query("UPDATE asset SET metadata = $1 WHERE token_address=$2 and token_id=$3")
-        .bind(Json(metadata))
-        .bind(token_address)
-        .bind(token_id)
-        .execute(pool)
-        .await
-    {
-        Ok(res) => {
-            info!(
-                "Updated {} assets metadata for {token_address} and {token_id}",
-                res.rows_affected()
-            )
-        }
-        Err(e) => {
-            error!("Error updating assets metadata: {e}");
-        }
+) -> Result<u64, LoaderError> {
+    let token_id_text = token_id.to_string();
+    let derived = derive_all([(token_address.as_str(), token_id_text.as_str(), &metadata)])
+        .pop()
+        .flatten();
+    let res = query(
+        "UPDATE asset SET metadata = $1, attribute = $2, tier = $3, stage = $4, wave = $5
+         WHERE token_address=$6 and token_id=$7",
+    )
+    .bind(Json(&metadata))
+    .bind(derived.as_ref().map(|derived| derived.attribute.clone()))
+    .bind(derived.as_ref().and_then(|derived| derived.tier))
+    .bind(derived.as_ref().and_then(|derived| derived.stage))
+    .bind(derived.as_ref().and_then(|derived| derived.wave))
+    .bind(token_address)
+    .bind(token_id)
+    .execute(pool)
+    .await?;
+    info!(
+        "Updated {} assets metadata for {token_address} and {token_id}",
+        res.rows_affected()
+    );
+    metrics::record_rows_updated("asset", res.rows_affected());
+    Ok(res.rows_affected())
+}
+
+// The columns derived from the metadata of every given asset, None if the metadata is missing or invalid
+// or the kind of the collection is not configured. Unknown keys are reported once per call
+fn derive_all<'a>(
+    assets: impl IntoIterator<Item = (&'a str, &'a str, &'a HashMap<String, Value>)>,
+) -> Vec<Option<DerivedMetadata>> {
+    let mut unknown_keys: BTreeMap<&'static str, BTreeSet<String>> = BTreeMap::new();
+    let derived = assets
+        .into_iter()
+        .map(|(token_address, token_id, metadata)| {
+            let kind = config::get().kind_of(token_address)?;
+            // missing metadata is fetched again by the enricher
+            if metadata.is_empty() {
+                return None;
+            }
+            match metadata::derive(kind, metadata) {
+                Ok(derived) => {
+                    if !derived.unknown_keys.is_empty() {
+                        metrics::record_metadata_issue(kind.name(), "unknown_key", 1);
+                        unknown_keys
+                            .entry(kind.name())
+                            .or_default()
+                            .extend(derived.unknown_keys.iter().cloned());
+                    }
+                    Some(derived)
+                }
+                Err(reason) => {
+                    warn!(
+                        "Invalid {} metadata of {token_address} {token_id}, no attribute is set: {reason}",
+                        kind.name()
+                    );
+                    metrics::record_metadata_issue(kind.name(), "invalid", 1);
+                    None
+                }
+            }
+        })
+        .collect();
+    for (kind, keys) in unknown_keys {
+        warn!("Unknown {kind} metadata keys {keys:?}");
     }
+    derived
 }
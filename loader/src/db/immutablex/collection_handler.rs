@@ -1,13 +1,24 @@
+use crate::config::CollectionKind;
+use crate::error::LoaderError;
 use crate::model::immutablex::collection::Collection;
-use log::{error, info};
-use sqlx::types::chrono::DateTime;
-use sqlx::{query, query_scalar, Pool, Postgres};
+use crate::model::immutablex::shared;
+use crate::utils::metrics;
+use log::info;
+use sqlx::{query, Pool, Postgres};
 
-pub async fn create_one(collection: &Collection, pool: &Pool<Postgres>) {
-    match query("insert into collection (address, name, description, icon_url, collection_image_url,
-                                     project_id, project_owner_address, metadata_api_url, created_on, updated_on)
-                values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-                ON CONFLICT (address) DO UPDATE SET updated_on = EXCLUDED.updated_on;")
+pub async fn create_one(
+    collection: &Collection,
+    kind: CollectionKind,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let created_on = shared::parse_timestamp("created_at", &collection.created_at)
+        .map_err(LoaderError::Validation)?;
+    let updated_on = shared::parse_timestamp("updated_at", &collection.updated_at)
+        .map_err(LoaderError::Validation)?;
+    let result = query("insert into collection (address, name, description, icon_url, collection_image_url,
+                                     project_id, project_owner_address, metadata_api_url, created_on, updated_on, kind)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                ON CONFLICT (address) DO UPDATE SET updated_on = EXCLUDED.updated_on, kind = EXCLUDED.kind;")
         .bind(&collection.address)
         .bind(&collection.name)
         .bind(&collection.description)
@@ -16,24 +27,11 @@ pub async fn create_one(collection: &Collection, pool: &Pool<Postgres>) {
         .bind(&collection.project_id)
         .bind(&collection.project_owner_address)
         .bind(&collection.metadata_api_url)
-        .bind(DateTime::parse_from_rfc3339(&collection.created_at).unwrap())
-        .bind(DateTime::parse_from_rfc3339(&collection.updated_at).unwrap())
-        .execute(pool).await {
-        Ok(result) => {
-            info!("Inserted {} rows", result.rows_affected())
-        }
-        Err(e) => {
-            error!("Couldn't insert values due to {e}")
-        }
-    }
-}
-
-pub async fn fetch_all_collections(pool: &Pool<Postgres>) -> Vec<String> {
-    return query_scalar("select address from collection")
-        .fetch_all(pool)
-        .await
-        .unwrap_or_else(|e| {
-            error!("Error fetching data: {e}");
-            vec![]
-        });
+        .bind(created_on)
+        .bind(updated_on)
+        .bind(kind.name())
+        .execute(pool).await?;
+    info!("Inserted {} rows", result.rows_affected());
+    metrics::record_rows_inserted("collection", result.rows_affected());
+    Ok(result.rows_affected())
 }
@@ -0,0 +1,169 @@
+use crate::error::LoaderError;
+use crate::utils::metrics;
+use log::info;
+use sqlx::{query, query_as, Pool, Postgres};
+
+// the tables the event table is derived from
+#[derive(Clone, Copy)]
+pub enum EventSource {
+    Transfer,
+    Order,
+    Mint,
+    Deposit,
+    Withdrawal,
+}
+
+impl EventSource {
+    fn name(&self) -> &'static str {
+        match self {
+            EventSource::Transfer => "transfer",
+            EventSource::Order => "order",
+            EventSource::Mint => "mint",
+            EventSource::Deposit => "deposit",
+            EventSource::Withdrawal => "withdrawal",
+        }
+    }
+
+    // source_id, transaction_id, event, token_address, token_id, wallet_from, wallet_to, timestamp, currency and price
+    // of the rows with the ids in $2 that happened, the same as events_view
+    fn select(&self) -> &'static str {
+        match self {
+            EventSource::Transfer => {
+                "select transaction_id, transaction_id,
+                        case when wallet_to = '0x0000000000000000000000000000000000000000' then 'Burned' else 'Transfer' end ||
+                        case when status = 'success' then '' else ' pending' end,
+                        token_address, token_id, wallet_from,
+                        case when wallet_to = '0x0000000000000000000000000000000000000000' then null else wallet_to end,
+                        created_on, null, null::decimal
+                 from transfer
+                 where transaction_id = any($2) and status <> 'failed'"
+            }
+            EventSource::Order => {
+                "select order_id, transaction_id, concat('Trade ', status), token_address, token_id, wallet_from,
+                        wallet_to, updated_on, buy_currency, buy_price
+                 from order_data
+                 where order_id = any($2)"
+            }
+            EventSource::Mint => {
+                "select transaction_id, transaction_id, 'Mint', token_address, token_id, null, wallet, minted_on,
+                        currency, price
+                 from mint
+                 where transaction_id = any($2)"
+            }
+            EventSource::Deposit => {
+                "select transaction_id, transaction_id, 'Deposit' || case when status = 'success' then '' else ' pending' end,
+                        token_address, token_id, null, wallet, created_on, null, null::decimal
+                 from deposit
+                 where transaction_id = any($2) and status <> 'failed'"
+            }
+            EventSource::Withdrawal => {
+                "select transaction_id, transaction_id, 'Withdrawal' || case when status = 'success' then '' else ' pending' end,
+                        token_address, token_id, wallet, null, created_on, null, null::decimal
+                 from withdrawal
+                 where transaction_id = any($2) and status <> 'failed'"
+            }
+        }
+    }
+
+    // the ids in $2 of the rows that never happened, orders and mints do not fail
+    fn select_failed(&self) -> Option<&'static str> {
+        match self {
+            EventSource::Transfer => {
+                Some("select transaction_id from transfer where transaction_id = any($2) and status = 'failed'")
+            }
+            EventSource::Deposit => {
+                Some("select transaction_id from deposit where transaction_id = any($2) and status = 'failed'")
+            }
+            EventSource::Withdrawal => Some(
+                "select transaction_id from withdrawal where transaction_id = any($2) and status = 'failed'",
+            ),
+            EventSource::Order | EventSource::Mint => None,
+        }
+    }
+}
+
+// Writes the events of the given rows of the source table, the rows have to be persisted first.
+// Unchanged events are left alone, the ones of rows that failed since are removed
+pub async fn sync_events(
+    source: EventSource,
+    source_ids: &Vec<i32>,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    if source_ids.is_empty() {
+        return Ok(0);
+    }
+    let rows: Vec<(bool,)> = query_as(&format!(
+        "insert into event (source, source_id, transaction_id, event, token_address, token_id, wallet_from, wallet_to,
+                            timestamp, currency, price, usd_price)
+         select $1, s.*, round(s.price * ch.usd, 2)
+         from ({}) as s(source_id, transaction_id, event, token_address, token_id, wallet_from, wallet_to, timestamp,
+                        currency, price)
+                  left join coin_history ch on ch.symbol = s.currency and ch.datestamp =
+                      case when s.event = 'Trade active' then (select max(datestamp) from coin_history)
+                           else s.timestamp::date end
+         on conflict (source, source_id) do update set transaction_id = excluded.transaction_id,
+             event = excluded.event, wallet_from = excluded.wallet_from, wallet_to = excluded.wallet_to,
+             timestamp = excluded.timestamp, currency = excluded.currency, price = excluded.price,
+             usd_price = excluded.usd_price
+         where (event.transaction_id, event.event, event.wallet_from, event.wallet_to, event.timestamp,
+                event.currency, event.price, event.usd_price)
+             is distinct from (excluded.transaction_id, excluded.event, excluded.wallet_from, excluded.wallet_to,
+                               excluded.timestamp, excluded.currency, excluded.price, excluded.usd_price)
+         returning (xmax = 0)",
+        source.select()
+    ))
+    .bind(source.name())
+    .bind(source_ids)
+    .fetch_all(pool)
+    .await?;
+    let inserted = rows.iter().filter(|row| row.0).count() as u64;
+    let updated = rows.len() as u64 - inserted;
+
+    let deleted = match source.select_failed() {
+        Some(select_failed) => query(&format!(
+            "delete from event where source = $1 and source_id in ({select_failed})"
+        ))
+        .bind(source.name())
+        .bind(source_ids)
+        .execute(pool)
+        .await?
+        .rows_affected(),
+        None => 0,
+    };
+
+    info!(
+        "Inserted {inserted}, updated {updated} and deleted {deleted} {} events",
+        source.name()
+    );
+    metrics::record_rows_inserted("event", inserted);
+    metrics::record_rows_updated("event", updated + deleted);
+    Ok(inserted + updated + deleted)
+}
+
+// Prices the events whose coin price of the day was not known yet when they were written,
+// and reprices active orders at the latest coin prices like events_view does
+pub async fn refresh_usd_prices(pool: &Pool<Postgres>) -> Result<u64, LoaderError> {
+    let priced = query(
+        "update event e set usd_price = round(e.price * ch.usd, 2)
+         from coin_history ch
+         where e.usd_price is null and e.price is not null and e.event <> 'Trade active'
+           and ch.symbol = e.currency and ch.datestamp = e.timestamp::date",
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+    let repriced = query(
+        "update event e set usd_price = round(e.price * ch.usd, 2)
+         from coin_history ch
+         where e.event = 'Trade active'
+           and ch.symbol = e.currency and ch.datestamp = (select max(datestamp) from coin_history)
+           and e.usd_price is distinct from round(e.price * ch.usd, 2)",
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    info!("Priced {priced} events and repriced {repriced} active orders");
+    metrics::record_rows_updated("event", priced + repriced);
+    Ok(priced + repriced)
+}
@@ -1,61 +1,140 @@
-use crate::db::immutablex::persistable::Persistable;
+use crate::cli::Entity;
+use crate::config::{self, CollectionKind};
+use crate::db::immutablex::events_handler::EventSource;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::db::immutablex::{events_handler, persistable};
+use crate::error::LoaderError;
 use crate::model::immutablex::mint::Mint;
+use crate::model::immutablex::shared;
+use crate::utils::metrics;
 use async_trait::async_trait;
-use log::{error, info};
-use sqlx::types::chrono::{DateTime, NaiveDateTime};
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::types::Decimal;
-use sqlx::{query, query_as, query_scalar, Pool, Postgres, QueryBuilder};
+use sqlx::{query_as, query_scalar, Pool, Postgres, QueryBuilder};
 
 pub struct MintSaver;
 
 #[async_trait]
 impl Persistable<Mint> for MintSaver {
-    async fn create_one(&self, mint: &Mint, pool: &Pool<Postgres>) {
+    async fn create_one(
+        &self,
+        mint: &Mint,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError> {
         let mint_result = &mint.result;
         let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
             "insert into mint (transaction_id, status, wallet, token_id, token_address, minted_on) ",
         );
 
-        query_builder.push_values(mint_result, |mut builder, res| {
+        let rows = mint_result
+            .iter()
+            .map(|res| {
+                Ok((
+                    res,
+                    shared::parse_integer("token_id", &res.token.data.token_id)?,
+                    shared::parse_timestamp("minted_on", &res.minted_on)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(LoaderError::Validation)?;
+        query_builder.push_values(rows, |mut builder, (res, token_id, minted_on)| {
             let token_data = &res.token.data;
             builder
                 .push_bind(res.transaction_id)
                 .push_bind(res.status.clone())
                 .push_bind(res.wallet.clone())
-                .push_bind(token_data.token_id.parse::<i32>().unwrap())
+                .push_bind(token_id)
                 .push_bind(token_data.token_address.clone())
-                .push_bind(DateTime::parse_from_rfc3339(&res.minted_on).unwrap());
+                .push_bind(minted_on);
         });
 
-        let query = query_builder
-            .push(" ON CONFLICT (transaction_id) DO NOTHING")
-            .build();
-        match query.execute(pool).await {
-            Ok(result) => {
-                info!("Inserted {} rows", result.rows_affected())
-            }
-            Err(e) => {
-                error!("Couldn't insert values due to {e}")
-            }
-        }
+        query_builder.push(" ON CONFLICT (transaction_id) DO NOTHING");
+        let result = persistable::execute_upsert("mint", query_builder, pool).await?;
+        info!(
+            "Inserted {} and updated {} rows",
+            result.inserted, result.updated
+        );
+        events_handler::sync_events(
+            EventSource::Mint,
+            &mint_result.iter().map(|res| res.transaction_id).collect(),
+            pool,
+        )
+        .await?;
+        Ok(result)
     }
 
     async fn get_last_timestamp(
         &self,
         pool: &Pool<Postgres>,
         token_address: &String,
-    ) -> Option<NaiveDateTime> {
+    ) -> Result<Option<NaiveDateTime>, LoaderError> {
         let result: (Option<NaiveDateTime>,) =
             query_as("select max(minted_on) from mint where token_address=$1")
                 .bind(token_address)
                 .fetch_one(pool)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("Couldn't fetch data! {e}");
-                    (None,)
-                });
+                .await?;
 
-        result.0
+        Ok(result.0)
+    }
+}
+
+#[async_trait]
+impl Reconcilable<Mint> for MintSaver {
+    fn entity(&self) -> Entity {
+        Entity::Mints
+    }
+
+    fn table(&self) -> (&'static str, &'static str) {
+        ("mint", "minted_on")
+    }
+
+    fn to_records(&self, mint: &Mint) -> Vec<Record> {
+        mint.result
+            .iter()
+            .map(|res| {
+                Record::new(
+                    res.transaction_id,
+                    vec![
+                        ("status", res.status.clone()),
+                        ("wallet", res.wallet.clone()),
+                        ("token_id", res.token.data.token_id.clone()),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError> {
+        let rows: Vec<(i32, String, String, i32)> = query_as(
+            "select transaction_id, status, wallet, token_id from mint where token_address=$1 and minted_on >= $2 and minted_on < $3",
+        )
+        .bind(token_address)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(transaction_id, status, wallet, token_id)| {
+                Record::new(
+                    transaction_id,
+                    vec![
+                        ("status", status),
+                        ("wallet", wallet),
+                        ("token_id", token_id.to_string()),
+                    ],
+                )
+            })
+            .collect())
     }
 }
 
@@ -64,21 +143,19 @@ pub async fn update_price_and_currency_for_wallet(
     price: f32,
     currency: String,
     pool: &Pool<Postgres>,
-) {
-    match query("update mint set price = $1, currency = $2 where wallet = $3")
-        .bind(price)
-        .bind(currency)
-        .bind(wallet)
-        .execute(pool)
-        .await
-    {
-        Ok(_) => {
-            info!("Updated wallet {wallet}")
-        }
-        Err(e) => {
-            error!("Error updating order_id {wallet}: {e}");
-        }
-    }
+) -> Result<u64, LoaderError> {
+    let transaction_ids: Vec<i32> = query_scalar(
+        "update mint set price = $1, currency = $2 where wallet = $3 returning transaction_id",
+    )
+    .bind(price)
+    .bind(currency)
+    .bind(wallet)
+    .fetch_all(pool)
+    .await?;
+    info!("Updated wallet {wallet}");
+    metrics::record_rows_updated("mint", transaction_ids.len() as u64);
+    events_handler::sync_events(EventSource::Mint, &transaction_ids, pool).await?;
+    Ok(transaction_ids.len() as u64)
 }
 
 pub async fn update_price_and_currency_for_wallet_and_token_id(
@@ -87,44 +164,36 @@ pub async fn update_price_and_currency_for_wallet_and_token_id(
     currency: String,
     token_id: &i32,
     pool: &Pool<Postgres>,
-) {
-    match query("update mint set price = $1, currency = $2 where wallet = $3 and token_id = $4")
-        .bind(price)
-        .bind(currency)
-        .bind(wallet)
-        .bind(token_id)
-        .execute(pool)
-        .await
-    {
-        Ok(_) => {
-            info!("Updated wallet {wallet} and token_id {token_id}")
-        }
-        Err(e) => {
-            error!("Error updating order_id {wallet} and token_id {token_id}: {e}");
-        }
-    }
+) -> Result<u64, LoaderError> {
+    let transaction_ids: Vec<i32> = query_scalar(
+        "update mint set price = $1, currency = $2 where wallet = $3 and token_id = $4 returning transaction_id",
+    )
+    .bind(price)
+    .bind(currency)
+    .bind(wallet)
+    .bind(token_id)
+    .fetch_all(pool)
+    .await?;
+    info!("Updated wallet {wallet} and token_id {token_id}");
+    metrics::record_rows_updated("mint", transaction_ids.len() as u64);
+    events_handler::sync_events(EventSource::Mint, &transaction_ids, pool).await?;
+    Ok(transaction_ids.len() as u64)
 }
 
 pub async fn fetch_all_lands_with_no_price_or_currency(
     pool: &Pool<Postgres>,
-) -> Option<Vec<String>> {
-    return match query_scalar(
-        "select distinct(wallet) from mint where (price is null or currency is null) and token_address='0x9e0d99b864e1ac12565125c5a82b59adea5a09cd'"
+) -> Result<Vec<String>, LoaderError> {
+    Ok(query_scalar(
+        "select distinct(wallet) from mint where (price is null or currency is null) and token_address = any($1)"
     )
+        .bind(config::get().addresses_of(CollectionKind::Land))
         .fetch_all(pool)
-        .await {
-        Ok(wallets) => {
-            Some(wallets)
-        }
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            None
-        }
-    };
+        .await?)
 }
 
-pub async fn update_d1sk_price(pool: &Pool<Postgres>) {
-    match query("UPDATE mint SET price =
+pub async fn update_d1sk_price(pool: &Pool<Postgres>) -> Result<u64, LoaderError> {
+    let transaction_ids: Vec<i32> = query_scalar(
+        "UPDATE mint SET price =
         CASE
             WHEN asset.attribute = 'Standard D1sk Alpha Wave 1' THEN 0.025
             WHEN asset.attribute = 'Mega D1sk Alpha Wave 1' THEN 0.124
@@ -144,15 +213,14 @@ pub async fn update_d1sk_price(pool: &Pool<Postgres>) {
         currency='ETH'
     FROM asset
     WHERE mint.token_id = asset.token_id and mint.token_address = asset.token_address
-      and (mint.price is null or mint.currency is null) and mint.token_address = '0xc1f1da534e227489d617cd742481fd5a23f6a003'")
-        .execute(pool)
-        .await
-    {
-        Ok(res) => {
-            info!("Updated {} d1sk prices", res.rows_affected())
-        }
-        Err(e) => {
-            error!("Error updating d1sk price: {e}");
-        }
-    }
+      and (mint.price is null or mint.currency is null) and mint.token_address = any($1)
+    RETURNING mint.transaction_id",
+    )
+    .bind(config::get().addresses_of(CollectionKind::D1sk))
+    .fetch_all(pool)
+    .await?;
+    info!("Updated {} d1sk prices", transaction_ids.len());
+    metrics::record_rows_updated("mint", transaction_ids.len() as u64);
+    events_handler::sync_events(EventSource::Mint, &transaction_ids, pool).await?;
+    Ok(transaction_ids.len() as u64)
 }
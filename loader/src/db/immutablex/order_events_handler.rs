@@ -0,0 +1,25 @@
+use crate::error::LoaderError;
+use crate::utils::metrics;
+use log::info;
+use sqlx::{query, Pool, Postgres};
+
+// Appends the current state of the given orders unless it was already observed,
+// the orders have to be persisted first
+pub async fn record_order_events(
+    order_ids: &Vec<i32>,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into order_event (order_id, token_id, token_address, wallet_from, status, buy_currency, buy_price, sell_price, updated_on)
+         select od.order_id, od.token_id, od.token_address, od.wallet_from, od.status, od.buy_currency, od.buy_price, od.sell_price, od.updated_on
+         from order_data od join unnest($1::integer[]) as t(order_id) on od.order_id = t.order_id
+         on conflict (order_id, status, updated_on, buy_price) do nothing",
+    )
+    .bind(order_ids)
+    .execute(pool)
+    .await?;
+
+    info!("Recorded {} order events", result.rows_affected());
+    metrics::record_rows_inserted("order_event", result.rows_affected());
+    Ok(result.rows_affected())
+}
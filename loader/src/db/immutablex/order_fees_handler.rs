@@ -0,0 +1,57 @@
+use crate::db::immutablex::persistable;
+use crate::db::immutablex::persistable::WriteCount;
+use crate::error::LoaderError;
+use crate::model::immutablex::order::TheResult;
+use crate::utils::price_utils;
+use log::info;
+use sqlx::types::Decimal;
+use sqlx::{Pool, Postgres, QueryBuilder};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+// The fees are in the buy currency of their order, the amounts are corrected in case of a reprice
+pub async fn create_order_fees(
+    orders: &[TheResult],
+    pool: &Pool<Postgres>,
+) -> Result<WriteCount, LoaderError> {
+    // an order can pay the same recipient the same kind of fee twice, those are stored as one
+    let mut fees: Vec<(&TheResult, &String, &String, Decimal)> = vec![];
+    let mut positions: HashMap<(i32, &String, &String), usize> = HashMap::new();
+    for res in orders {
+        for fee in &res.fees {
+            let amount = price_utils::get_price(&fee.amount, fee.token.data.decimals);
+            match positions.entry((res.order_id, &fee.fee_type, &fee.recipient)) {
+                Entry::Occupied(position) => fees[*position.get()].3 += amount,
+                Entry::Vacant(position) => {
+                    position.insert(fees.len());
+                    fees.push((res, &fee.fee_type, &fee.recipient, amount));
+                }
+            }
+        }
+    }
+    if fees.is_empty() {
+        return Ok(WriteCount::default());
+    }
+
+    let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+        "insert into order_fee (order_id, fee_type, recipient, currency, amount) ",
+    );
+    query_builder.push_values(fees, |mut builder, (res, fee_type, recipient, amount)| {
+        builder
+            .push_bind(res.order_id)
+            .push_bind(fee_type)
+            .push_bind(recipient)
+            .push_bind(&res.taker_fees.symbol)
+            .push_bind(amount);
+    });
+    query_builder.push(
+        " ON CONFLICT (order_id, fee_type, recipient) DO UPDATE SET amount = EXCLUDED.amount
+         WHERE order_fee.amount <> EXCLUDED.amount",
+    );
+    let result = persistable::execute_upsert("order_fee", query_builder, pool).await?;
+    info!(
+        "Inserted {} and updated {} order fees",
+        result.inserted, result.updated
+    );
+    Ok(result)
+}
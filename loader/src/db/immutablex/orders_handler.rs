@@ -1,178 +1,206 @@
-use crate::db::immutablex::persistable::Persistable;
+use crate::cli::Entity;
+use crate::db::immutablex::events_handler::EventSource;
+use crate::db::immutablex::persistable;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::db::immutablex::{events_handler, order_events_handler, order_fees_handler};
+use crate::error::LoaderError;
 use crate::model::immutablex::order::Order;
+use crate::model::immutablex::shared;
+use crate::utils::metrics;
 use crate::utils::price_utils;
 use async_trait::async_trait;
-use log::{error, info};
-use sqlx::types::chrono::{DateTime, NaiveDateTime};
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::types::Decimal;
-use sqlx::{query, query_as, query_scalar, FromRow, Pool, Postgres, QueryBuilder};
+use sqlx::{query_as, query_scalar, Pool, Postgres, QueryBuilder};
 
 pub struct OrderSaver;
 
 #[async_trait]
 impl Persistable<Order> for OrderSaver {
-    async fn create_one(&self, order: &Order, pool: &Pool<Postgres>) {
+    async fn create_one(
+        &self,
+        order: &Order,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError> {
         let order_result = &order.result;
         let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
             "insert into order_data (order_id, status, wallet_from, token_id, token_address, buy_currency, sell_price, buy_price, created_on, updated_on) ",
         );
 
-        query_builder.push_values(order_result, |mut builder, res| {
-            let sell_data = &res.sell.data;
-            let maker_fees = &res.maker_fees;
-            let taker_fees = &res.taker_fees;
-            let token_id = sell_data.token_id.clone().unwrap().parse::<i32>().unwrap();
-            builder
-                .push_bind(res.order_id)
-                .push_bind(res.status.clone())
-                .push_bind(res.wallet.clone())
-                .push_bind(token_id)
-                .push_bind(&sell_data.token_address)
-                .push_bind(&taker_fees.symbol)
-                .push_bind(price_utils::get_price(
-                    &maker_fees.quantity_with_fees,
-                    maker_fees.decimals,
+        let rows = order_result
+            .iter()
+            .map(|res| {
+                let token_id = res
+                    .sell
+                    .data
+                    .token_id
+                    .as_ref()
+                    .ok_or_else(|| String::from("sell token_id is missing"))?;
+                Ok((
+                    res,
+                    shared::parse_integer("token_id", token_id)?,
+                    shared::parse_timestamp("timestamp", &res.timestamp)?,
+                    shared::parse_timestamp("updated_timestamp", &res.updated_timestamp)?,
                 ))
-                .push_bind(price_utils::get_price(
-                    &taker_fees.quantity_with_fees,
-                    taker_fees.decimals,
-                ))
-                .push_bind(DateTime::parse_from_rfc3339(&res.timestamp).unwrap())
-                .push_bind(DateTime::parse_from_rfc3339(&res.updated_timestamp).unwrap());
-        });
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(LoaderError::Validation)?;
+        query_builder.push_values(
+            rows,
+            |mut builder, (res, token_id, created_on, updated_on)| {
+                let sell_data = &res.sell.data;
+                let maker_fees = &res.maker_fees;
+                let taker_fees = &res.taker_fees;
+                builder
+                    .push_bind(res.order_id)
+                    .push_bind(res.status.clone())
+                    .push_bind(res.wallet.clone())
+                    .push_bind(token_id)
+                    .push_bind(&sell_data.token_address)
+                    .push_bind(&taker_fees.symbol)
+                    .push_bind(price_utils::get_price(
+                        &maker_fees.quantity_with_fees,
+                        maker_fees.decimals,
+                    ))
+                    .push_bind(price_utils::get_price(
+                        &taker_fees.quantity_with_fees,
+                        taker_fees.decimals,
+                    ))
+                    .push_bind(created_on)
+                    .push_bind(updated_on);
+            },
+        );
 
-        let query = query_builder.push(
-            " ON CONFLICT (order_id) DO UPDATE SET status = EXCLUDED.status,
-             updated_on = EXCLUDED.updated_on, sell_price = EXCLUDED.sell_price, buy_price = EXCLUDED.buy_price").build();
-        match query.execute(pool).await {
-            Ok(result) => {
-                info!("Inserted {} rows", result.rows_affected())
-            }
-            Err(e) => {
-                error!("Couldn't insert values due to {e}")
-            }
-        }
+        query_builder.push(" ON CONFLICT (order_id) DO UPDATE SET status = EXCLUDED.status,
+             updated_on = EXCLUDED.updated_on, sell_price = EXCLUDED.sell_price, buy_price = EXCLUDED.buy_price");
+        let result = persistable::execute_upsert("order_data", query_builder, pool).await?;
+        info!(
+            "Inserted {} and updated {} rows",
+            result.inserted, result.updated
+        );
+
+        let order_ids = order_result.iter().map(|res| res.order_id).collect();
+        order_events_handler::record_order_events(&order_ids, pool).await?;
+        events_handler::sync_events(EventSource::Order, &order_ids, pool).await?;
+        order_fees_handler::create_order_fees(order_result, pool).await?;
+        Ok(result)
     }
 
     async fn get_last_timestamp(
         &self,
         pool: &Pool<Postgres>,
         token_address: &String,
-    ) -> Option<NaiveDateTime> {
+    ) -> Result<Option<NaiveDateTime>, LoaderError> {
         let result: (Option<NaiveDateTime>,) =
             query_as("select max(updated_on) from order_data where token_address=$1")
                 .bind(token_address)
                 .fetch_one(pool)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("Couldn't fetch data! {e}");
-                    (None,)
-                });
+                .await?;
 
-        result.0
+        Ok(result.0)
     }
 }
 
-pub async fn fetch_all_filled_token_ids_with_no_wallet_to_or_transaction_id(
-    token_address: &String,
-    pool: &Pool<Postgres>,
-) -> Option<Vec<i32>> {
-    return match query_scalar(
-        "select distinct(token_id) from order_data where status = 'filled' and (wallet_to is null or transaction_id is null) and token_address=$1"
-    )
-        .bind(token_address)
-        .fetch_all(pool)
-        .await {
-        Ok(token_ids) => {
-            Some(token_ids)
-        }
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            None
-        }
-    };
-}
+#[async_trait]
+impl Reconcilable<Order> for OrderSaver {
+    fn entity(&self) -> Entity {
+        Entity::Orders
+    }
 
-pub async fn fetch_all_filled_order_ids_with_no_wallet_to_or_transaction_id_for_token_id(
-    token_id: i32,
-    pool: &Pool<Postgres>,
-) -> Option<Vec<i32>> {
-    return match query_scalar(
-        "select order_id from order_data where status = 'filled' and (wallet_to is null or transaction_id is null) and token_id = $1"
-    )
-        .bind(token_id)
-        .fetch_all(pool)
-        .await {
-        Ok(token_ids) => {
-            Some(token_ids)
-        }
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            None
-        }
-    };
-}
+    fn table(&self) -> (&'static str, &'static str) {
+        ("order_data", "updated_on")
+    }
+
+    fn to_records(&self, order: &Order) -> Vec<Record> {
+        order
+            .result
+            .iter()
+            .map(|res| {
+                let maker_fees = &res.maker_fees;
+                let taker_fees = &res.taker_fees;
+                let sell_price =
+                    price_utils::get_price(&maker_fees.quantity_with_fees, maker_fees.decimals);
+                let buy_price =
+                    price_utils::get_price(&taker_fees.quantity_with_fees, taker_fees.decimals);
+                Record::new(
+                    res.order_id,
+                    vec![
+                        ("status", res.status.clone()),
+                        ("wallet_from", res.wallet.clone()),
+                        (
+                            "token_id",
+                            res.sell.data.token_id.clone().unwrap_or_default(),
+                        ),
+                        ("buy_currency", taker_fees.symbol.clone()),
+                        ("sell_price", sell_price.normalize().to_string()),
+                        ("buy_price", buy_price.normalize().to_string()),
+                    ],
+                )
+            })
+            .collect()
+    }
 
-pub async fn fetch_all_not_checked_order_ids(pool: &Pool<Postgres>) -> Vec<OrderDb> {
-    return match query_as::<_, OrderDb>("select order_id, buy_price, sell_price from order_data")
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError> {
+        let rows: Vec<(i32, String, String, i32, String, Decimal, Decimal)> = query_as(
+            "select order_id, status, wallet_from, token_id, buy_currency, sell_price, buy_price from order_data
+             where token_address=$1 and updated_on >= $2 and updated_on < $3",
+        )
+        .bind(token_address)
+        .bind(from)
+        .bind(to)
         .fetch_all(pool)
-        .await
-    {
-        Ok(token_ids) => token_ids,
-        Err(e) => {
-            error!("Error fetching data: {e}");
-            vec![]
-        }
-    };
-}
+        .await?;
 
-pub async fn update_buy_price_and_sell_price_for_order_id(
-    order_id: i32,
-    buy_price: Decimal,
-    sell_price: Decimal,
-    pool: &Pool<Postgres>,
-) {
-    match query("update order_data set buy_price = $1, sell_price = $2 where order_id = $3")
-        .bind(buy_price)
-        .bind(sell_price)
-        .bind(order_id)
-        .execute(pool)
-        .await
-    {
-        Ok(_) => {
-            info!("Updated order_id {order_id}")
-        }
-        Err(e) => {
-            error!("Error updating order_id {order_id}: {e}");
-        }
+        Ok(rows
+            .into_iter()
+            .map(
+                |(order_id, status, wallet_from, token_id, buy_currency, sell_price, buy_price)| {
+                    Record::new(
+                        order_id,
+                        vec![
+                            ("status", status),
+                            ("wallet_from", wallet_from),
+                            ("token_id", token_id.to_string()),
+                            ("buy_currency", buy_currency),
+                            ("sell_price", sell_price.normalize().to_string()),
+                            ("buy_price", buy_price.normalize().to_string()),
+                        ],
+                    )
+                },
+            )
+            .collect())
     }
 }
 
-pub async fn update_wallet_to_and_transaction_id_for_order_id(
-    order_id: i32,
-    wallet_to: String,
-    transaction_id: i32,
+// wallet_to and transaction_id of filled orders come from the trade that filled them
+pub async fn update_wallet_to_and_transaction_id_from_trades(
+    token_address: &String,
     pool: &Pool<Postgres>,
-) {
-    match query("update order_data set wallet_to = $1, transaction_id = $2 where order_id = $3")
-        .bind(wallet_to)
-        .bind(transaction_id)
-        .bind(order_id)
-        .execute(pool)
-        .await
-    {
-        Ok(_) => {
-            info!("Updated order_id {order_id}")
-        }
-        Err(e) => {
-            error!("Error updating order_id {order_id}: {e}");
-        }
-    }
-}
-
-#[derive(FromRow)]
-pub struct OrderDb {
-    pub order_id: i32,
-    pub buy_price: Decimal,
-    pub sell_price: Decimal,
+) -> Result<u64, LoaderError> {
+    let order_ids: Vec<i32> = query_scalar(
+        "update order_data od set wallet_to = coalesce(t.wallet_to, od.wallet_to), transaction_id = t.transaction_id
+         from trade t
+         where t.seller_order_id = od.order_id and od.token_address = $1
+           and (od.transaction_id is distinct from t.transaction_id
+                or (t.wallet_to is not null and od.wallet_to is distinct from t.wallet_to))
+         returning od.order_id",
+    )
+    .bind(token_address)
+    .fetch_all(pool)
+    .await?;
+    info!(
+        "Updated wallet_to and transaction_id of {} orders",
+        order_ids.len()
+    );
+    metrics::record_rows_updated("order_data", order_ids.len() as u64);
+    events_handler::sync_events(EventSource::Order, &order_ids, pool).await?;
+    Ok(order_ids.len() as u64)
 }
@@ -1,14 +1,45 @@
+use crate::error::LoaderError;
+use crate::utils::metrics;
 use async_trait::async_trait;
 use sqlx::types::chrono::NaiveDateTime;
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, QueryBuilder};
 
 #[async_trait]
 pub trait Persistable<T> {
-    async fn create_one(&self, result: &T, pool: &Pool<Postgres>);
+    async fn create_one(
+        &self,
+        result: &T,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError>;
 
     async fn get_last_timestamp(
         &self,
         pool: &Pool<Postgres>,
         token_address: &String,
-    ) -> Option<NaiveDateTime>;
+    ) -> Result<Option<NaiveDateTime>, LoaderError>;
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct WriteCount {
+    pub inserted: u64,
+    pub updated: u64,
+}
+
+// Executes a batch `insert ... ON CONFLICT` and tells inserted rows apart from updated ones
+// xmax is 0 only for freshly inserted rows, rows skipped by DO NOTHING are not returned at all
+pub async fn execute_upsert(
+    table: &str,
+    mut query_builder: QueryBuilder<'_, Postgres>,
+    pool: &Pool<Postgres>,
+) -> Result<WriteCount, LoaderError> {
+    let rows: Vec<(bool,)> = query_builder
+        .push(" RETURNING (xmax = 0)")
+        .build_query_as()
+        .fetch_all(pool)
+        .await?;
+    let inserted = rows.iter().filter(|row| row.0).count() as u64;
+    let updated = rows.len() as u64 - inserted;
+    metrics::record_rows_inserted(table, inserted);
+    metrics::record_rows_updated(table, updated);
+    Ok(WriteCount { inserted, updated })
 }
@@ -0,0 +1,98 @@
+use crate::error::LoaderError;
+use crate::model::immutablex::shared::InvalidRecord;
+use crate::utils::metrics;
+use log::warn;
+use sqlx::types::Json;
+use sqlx::{query, query_as, FromRow, Pool, Postgres, QueryBuilder};
+
+#[derive(FromRow)]
+pub struct QuarantinedRecord {
+    pub id: i64,
+    pub raw: Json<serde_json::Value>,
+}
+
+// a record that is quarantined again only gets the latest raw JSON and reason
+pub async fn quarantine_records(
+    endpoint: &str,
+    token_address: &String,
+    invalid_records: &[InvalidRecord],
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    if invalid_records.is_empty() {
+        return Ok(0);
+    }
+    for invalid_record in invalid_records {
+        warn!(
+            "Quarantining {endpoint} record {} of {token_address}: {}",
+            invalid_record.record_id.as_deref().unwrap_or("without id"),
+            invalid_record.reason
+        );
+    }
+
+    // a page can hold the same record twice, an upsert cannot touch a row twice, the last one is kept
+    let mut records: Vec<&InvalidRecord> = vec![];
+    for invalid_record in invalid_records.iter().rev() {
+        if invalid_record.record_id.is_none()
+            || !records
+                .iter()
+                .any(|record| record.record_id == invalid_record.record_id)
+        {
+            records.push(invalid_record);
+        }
+    }
+    records.reverse();
+
+    let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+        "insert into quarantined_record (endpoint, token_address, record_id, raw, reason) ",
+    );
+    query_builder.push_values(records, |mut builder, invalid_record| {
+        builder
+            .push_bind(endpoint)
+            .push_bind(token_address)
+            .push_bind(&invalid_record.record_id)
+            .push_bind(Json(&invalid_record.raw))
+            .push_bind(&invalid_record.reason);
+    });
+    query_builder.push(
+        " ON CONFLICT (endpoint, token_address, record_id) WHERE reprocessed_on IS NULL DO UPDATE SET raw = EXCLUDED.raw,
+         reason = EXCLUDED.reason, quarantined_on = now()",
+    );
+    let result = query_builder.build().execute(pool).await?;
+    metrics::record_rows_inserted("quarantined_record", result.rows_affected());
+    Ok(result.rows_affected())
+}
+
+pub async fn fetch_open_records(
+    endpoint: &str,
+    pool: &Pool<Postgres>,
+) -> Result<Vec<QuarantinedRecord>, LoaderError> {
+    Ok(query_as(
+        "select id, raw from quarantined_record where endpoint = $1 and reprocessed_on is null order by id",
+    )
+    .bind(endpoint)
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn mark_reprocessed(ids: &Vec<i64>, pool: &Pool<Postgres>) -> Result<u64, LoaderError> {
+    let result = query("update quarantined_record set reprocessed_on = now() where id = any($1)")
+        .bind(ids)
+        .execute(pool)
+        .await?;
+    metrics::record_rows_updated("quarantined_record", result.rows_affected());
+    Ok(result.rows_affected())
+}
+
+pub async fn update_reason(
+    id: i64,
+    reason: &String,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query("update quarantined_record set reason = $1 where id = $2")
+        .bind(reason)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    metrics::record_rows_updated("quarantined_record", result.rows_affected());
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,43 @@
+use crate::cli::Entity;
+use crate::db::immutablex::persistable::Persistable;
+use crate::error::LoaderError;
+use async_trait::async_trait;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::{Pool, Postgres};
+
+// Implemented by the savers of the entities that can be compared against the ImmutableX API
+// repairs go thru Persistable::create_one, so they are as complete as the upsert of the entity
+#[async_trait]
+pub trait Reconcilable<T>: Persistable<T> {
+    fn entity(&self) -> Entity;
+
+    // the table and the timestamp column matching the timestamp params of the API url
+    fn table(&self) -> (&'static str, &'static str);
+
+    fn to_records(&self, page: &T) -> Vec<Record>;
+
+    // the records with the timestamp column within [from, to)
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError>;
+}
+
+// The compared values of a single row, as strings, so that API and DB values are compared the same way
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub id: String,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Record {
+    pub fn new(id: impl ToString, fields: Vec<(&'static str, String)>) -> Self {
+        Self {
+            id: id.to_string(),
+            fields,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::error::LoaderError;
+use log::info;
+use sqlx::types::chrono::NaiveDate;
+use sqlx::types::Uuid;
+use sqlx::{query, query_scalar, Pool, Postgres, QueryBuilder};
+use std::collections::HashSet;
+
+pub const STATUS_OPEN: &str = "open";
+pub const STATUS_REPAIRED: &str = "repaired";
+const ISSUES_PER_STATEMENT: usize = 5_000;
+
+pub struct Issue {
+    // empty for count issues
+    pub record_id: String,
+    pub field: String,
+    pub api_value: Option<String>,
+    pub db_value: Option<String>,
+    pub status: &'static str,
+}
+
+// random days that have data, so that every run checks a different part of the history
+pub async fn sample_days(
+    table: (&str, &str),
+    token_address: &String,
+    days: i64,
+    pool: &Pool<Postgres>,
+) -> Result<Vec<NaiveDate>, LoaderError> {
+    let (table, column) = table;
+    Ok(query_scalar(&format!(
+        "select day from (select distinct {column}::date as day from {table} where token_address = $1) days
+         order by random() limit $2"
+    ))
+    .bind(token_address)
+    .bind(days)
+    .fetch_all(pool)
+    .await?)
+}
+
+// Replaces the open issues of the day with the ones found now, issues that are gone are marked resolved
+pub async fn save_issues(
+    run_id: &Uuid,
+    token_address: &String,
+    entity: &str,
+    day: &NaiveDate,
+    issues: &[Issue],
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let mut transaction = pool.begin().await?;
+    query(
+        "update reconciliation_issue set status = 'resolved', resolved_on = now() at time zone 'utc'
+         where token_address = $1 and entity = $2 and day = $3 and status = $4",
+    )
+    .bind(token_address)
+    .bind(entity)
+    .bind(day)
+    .bind(STATUS_OPEN)
+    .execute(&mut *transaction)
+    .await?;
+
+    // the same field of a record can only be upserted once per statement, the last issue found is kept
+    let mut unique_issues: Vec<&Issue> = vec![];
+    let mut seen = HashSet::new();
+    for issue in issues.iter().rev() {
+        if seen.insert((&issue.record_id, &issue.field)) {
+            unique_issues.push(issue);
+        }
+    }
+    unique_issues.reverse();
+
+    let mut rows = 0;
+    // 9 binds per issue, the chunks stay well below the 65535 binds of a statement
+    for chunk in unique_issues.chunks(ISSUES_PER_STATEMENT) {
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "insert into reconciliation_issue (run_id, token_address, entity, day, record_id, field, api_value, db_value, status, detected_on, resolved_on) ",
+        );
+        query_builder.push_values(chunk, |mut builder, issue| {
+            builder
+                .push_bind(run_id)
+                .push_bind(token_address)
+                .push_bind(entity)
+                .push_bind(day)
+                .push_bind(&issue.record_id)
+                .push_bind(&issue.field)
+                .push_bind(&issue.api_value)
+                .push_bind(&issue.db_value)
+                .push_bind(issue.status)
+                .push("now() at time zone 'utc'")
+                .push(if issue.status == STATUS_OPEN {
+                    "null"
+                } else {
+                    "now() at time zone 'utc'"
+                });
+        });
+        query_builder.push(
+            " ON CONFLICT (token_address, entity, day, record_id, field) DO UPDATE SET run_id = EXCLUDED.run_id,
+              api_value = EXCLUDED.api_value, db_value = EXCLUDED.db_value, status = EXCLUDED.status,
+              detected_on = EXCLUDED.detected_on, resolved_on = EXCLUDED.resolved_on",
+        );
+        rows += query_builder
+            .build()
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+    }
+    transaction.commit().await?;
+    info!("Saved {rows} reconciliation issues of {entity} for {token_address} on {day}");
+    Ok(rows)
+}
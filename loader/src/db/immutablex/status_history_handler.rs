@@ -0,0 +1,56 @@
+use crate::cli::Entity;
+use crate::error::LoaderError;
+use crate::utils::metrics;
+use log::info;
+use sqlx::{query, query_scalar, Pool, Postgres};
+
+// Appends the given statuses of transfers, deposits or withdrawals unless they were already observed
+pub async fn record_statuses(
+    entity: Entity,
+    transaction_ids: &Vec<i32>,
+    statuses: &Vec<String>,
+    rollup_statuses: &Vec<Option<String>>,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into status_history (entity, transaction_id, status, rollup_status)
+         select $1, * from unnest($2::integer[], $3::varchar[], $4::varchar[])
+         on conflict do nothing",
+    )
+    .bind(entity.name())
+    .bind(transaction_ids)
+    .bind(statuses)
+    .bind(rollup_statuses)
+    .execute(pool)
+    .await?;
+
+    info!("Recorded {} status changes", result.rows_affected());
+    metrics::record_rows_inserted("status_history", result.rows_affected());
+    Ok(result.rows_affected())
+}
+
+// success and failed are final, anything else may still change, e.g. pending withdrawals.
+// A successful withdrawal is final once its rollup status is confirmed as well.
+// older items are not expected to change anymore and are left alone
+pub async fn fetch_not_final_transaction_ids(
+    table: &str,
+    token_address: &String,
+    max_age_days: i32,
+    pool: &Pool<Postgres>,
+) -> Result<Vec<i32>, LoaderError> {
+    let not_final = if table == "withdrawal" {
+        "status <> 'failed' and (status <> 'success' or rollup_status is distinct from 'confirmed')"
+    } else {
+        "status not in ('success', 'failed')"
+    };
+    Ok(query_scalar(&format!(
+        "select transaction_id from {table}
+         where token_address = $1 and {not_final}
+           and created_on > now() - make_interval(days => $2)
+         order by transaction_id"
+    ))
+    .bind(token_address)
+    .bind(max_age_days)
+    .fetch_all(pool)
+    .await?)
+}
@@ -0,0 +1,68 @@
+use crate::error::LoaderError;
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::Uuid;
+use sqlx::{query, query_as, FromRow, Pool, Postgres};
+
+#[derive(FromRow)]
+pub struct SyncState {
+    pub cursor: Option<String>,
+    pub last_timestamp: Option<NaiveDateTime>,
+}
+
+pub async fn fetch_sync_state(
+    token_address: &String,
+    endpoint: &str,
+    pool: &Pool<Postgres>,
+) -> Result<Option<SyncState>, LoaderError> {
+    let result: Option<SyncState> = query_as(
+        "select cursor, last_timestamp from sync_state where token_address = $1 and endpoint = $2",
+    )
+    .bind(token_address)
+    .bind(endpoint)
+    .fetch_optional(pool)
+    .await?;
+    Ok(result)
+}
+
+pub async fn save_sync_state(
+    token_address: &String,
+    endpoint: &str,
+    // none if the first page was already the last one
+    cursor: Option<&String>,
+    last_timestamp: Option<NaiveDateTime>,
+    run_id: &Uuid,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into sync_state (token_address, endpoint, cursor, last_timestamp, run_id, updated_on)
+        values ($1, $2, $3, $4, $5, now())
+        ON CONFLICT (token_address, endpoint) DO UPDATE
+        set cursor = excluded.cursor, last_timestamp = excluded.last_timestamp, run_id = excluded.run_id, updated_on = excluded.updated_on",
+    )
+    .bind(token_address)
+    .bind(endpoint)
+    .bind(cursor)
+    .bind(last_timestamp)
+    .bind(run_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// removing the checkpoint makes the next run start from the last persisted timestamp again
+pub async fn reset_sync_state(
+    token_address: Option<&String>,
+    endpoint: Option<&str>,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "delete from sync_state where ($1::varchar is null or token_address = $1) and ($2::varchar is null or endpoint = $2)",
+    )
+    .bind(token_address)
+    .bind(endpoint)
+    .execute(pool)
+    .await?;
+    info!("Reset {} sync states", result.rows_affected());
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,195 @@
+use crate::cli::Entity;
+use crate::db::immutablex::persistable;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::error::LoaderError;
+use crate::model::immutablex::shared;
+use crate::model::immutablex::trade::Trade;
+use crate::utils::metrics;
+use async_trait::async_trait;
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::{query, query_as, Pool, Postgres, QueryBuilder};
+
+pub struct TradeSaver;
+
+#[async_trait]
+impl Persistable<Trade> for TradeSaver {
+    async fn create_one(
+        &self,
+        trade: &Trade,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError> {
+        let trade_result = &trade.result;
+        // wallets are filled afterwards, see trades_reader
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "insert into trade (transaction_id, status, token_id, token_address, buyer_order_id, seller_order_id, traded_on) ",
+        );
+        let rows = trade_result
+            .iter()
+            .map(|res| {
+                Ok((
+                    res,
+                    shared::parse_integer("token_id", &res.seller.token_id)?,
+                    shared::parse_timestamp("timestamp", &res.timestamp)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(LoaderError::Validation)?;
+        query_builder.push_values(rows, |mut builder, (res, token_id, traded_on)| {
+            let seller = &res.seller;
+            builder
+                .push_bind(res.transaction_id)
+                .push_bind(res.status.clone())
+                .push_bind(token_id)
+                .push_bind(&seller.token_address)
+                .push_bind(res.buyer.order_id)
+                .push_bind(seller.order_id)
+                .push_bind(traded_on);
+        });
+
+        query_builder.push(" ON CONFLICT (transaction_id) DO UPDATE SET status = EXCLUDED.status");
+        let result = persistable::execute_upsert("trade", query_builder, pool).await?;
+        info!(
+            "Inserted {} and updated {} rows",
+            result.inserted, result.updated
+        );
+        Ok(result)
+    }
+
+    async fn get_last_timestamp(
+        &self,
+        pool: &Pool<Postgres>,
+        token_address: &String,
+    ) -> Result<Option<NaiveDateTime>, LoaderError> {
+        let result: (Option<NaiveDateTime>,) =
+            query_as("select max(traded_on) from trade where token_address=$1")
+                .bind(token_address)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(result.0)
+    }
+}
+
+#[async_trait]
+impl Reconcilable<Trade> for TradeSaver {
+    fn entity(&self) -> Entity {
+        Entity::Trades
+    }
+
+    fn table(&self) -> (&'static str, &'static str) {
+        ("trade", "traded_on")
+    }
+
+    fn to_records(&self, trade: &Trade) -> Vec<Record> {
+        trade
+            .result
+            .iter()
+            .map(|res| {
+                Record::new(
+                    res.transaction_id,
+                    vec![
+                        ("status", res.status.clone()),
+                        ("token_id", res.seller.token_id.clone()),
+                        ("buyer_order_id", res.buyer.order_id.to_string()),
+                        ("seller_order_id", res.seller.order_id.to_string()),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError> {
+        let rows: Vec<(i32, String, i32, i32, i32)> = query_as(
+            "select transaction_id, status, token_id, buyer_order_id, seller_order_id from trade where token_address=$1 and traded_on >= $2 and traded_on < $3",
+        )
+        .bind(token_address)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(transaction_id, status, token_id, buyer_order_id, seller_order_id)| {
+                    Record::new(
+                        transaction_id,
+                        vec![
+                            ("status", status),
+                            ("token_id", token_id.to_string()),
+                            ("buyer_order_id", buyer_order_id.to_string()),
+                            ("seller_order_id", seller_order_id.to_string()),
+                        ],
+                    )
+                },
+            )
+            .collect())
+    }
+}
+
+// the seller wallet is the one of the sell order, which is always in order_data
+pub async fn update_wallet_from_for_token_address(
+    token_address: &String,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "update trade t set wallet_from = od.wallet_from
+         from order_data od
+         where od.order_id = t.seller_order_id and t.token_address = $1 and t.wallet_from is null",
+    )
+    .bind(token_address)
+    .execute(pool)
+    .await?;
+    info!("Updated wallet_from of {} trades", result.rows_affected());
+    metrics::record_rows_updated("trade", result.rows_affected());
+    Ok(result.rows_affected())
+}
+
+// trades whose buyer order failed max_attempts times are left without wallet_to
+pub async fn fetch_all_buyer_order_ids_with_no_wallet_to(
+    token_address: &String,
+    max_attempts: i16,
+    pool: &Pool<Postgres>,
+) -> Result<Vec<(i32, i32)>, LoaderError> {
+    Ok(query_as(
+        "select transaction_id, buyer_order_id from trade
+         where token_address = $1 and wallet_to is null and wallet_to_attempts < $2",
+    )
+    .bind(token_address)
+    .bind(max_attempts)
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn record_wallet_to_attempt(
+    transaction_id: i32,
+    pool: &Pool<Postgres>,
+) -> Result<(), LoaderError> {
+    query("update trade set wallet_to_attempts = wallet_to_attempts + 1 where transaction_id = $1")
+        .bind(transaction_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn update_wallet_to_for_transaction_id(
+    transaction_id: i32,
+    wallet_to: String,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query("update trade set wallet_to = $1 where transaction_id = $2")
+        .bind(wallet_to)
+        .bind(transaction_id)
+        .execute(pool)
+        .await?;
+    metrics::record_rows_updated("trade", result.rows_affected());
+    Ok(result.rows_affected())
+}
@@ -1,59 +1,163 @@
-use crate::db::immutablex::persistable::Persistable;
+use crate::cli::Entity;
+use crate::db::immutablex::events_handler::EventSource;
+use crate::db::immutablex::persistable;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::db::immutablex::{events_handler, status_history_handler};
+use crate::error::LoaderError;
+use crate::model::immutablex::shared;
 use crate::model::immutablex::transfer::Transfer;
 use async_trait::async_trait;
-use log::{error, info};
-use sqlx::types::chrono::{DateTime, NaiveDateTime};
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::{query_as, Pool, Postgres, QueryBuilder};
 
 pub struct TransferSaver;
 
 #[async_trait]
 impl Persistable<Transfer> for TransferSaver {
-    async fn create_one(&self, transfer: &Transfer, pool: &Pool<Postgres>) {
+    async fn create_one(
+        &self,
+        transfer: &Transfer,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError> {
         let transfer_result = &transfer.result;
         let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
             "insert into transfer (transaction_id, status, wallet_from, wallet_to, token_id, token_address, created_on) ",
         );
-        query_builder.push_values(transfer_result, |mut builder, res| {
+        let rows = transfer_result
+            .iter()
+            .map(|res| {
+                Ok((
+                    res,
+                    shared::parse_integer("token_id", &res.token.data.token_id)?,
+                    shared::parse_timestamp("timestamp", &res.timestamp)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(LoaderError::Validation)?;
+        query_builder.push_values(rows, |mut builder, (res, token_id, timestamp)| {
             let token_data = &res.token.data;
             builder
                 .push_bind(res.transaction_id)
                 .push_bind(res.status.clone())
                 .push_bind(res.wallet_from.clone())
                 .push_bind(res.wallet_to.clone())
-                .push_bind(token_data.token_id.clone().parse::<i32>().unwrap())
+                .push_bind(token_id)
                 .push_bind(&token_data.token_address)
-                .push_bind(DateTime::parse_from_rfc3339(&res.timestamp).unwrap());
+                .push_bind(timestamp);
         });
 
-        let query = query_builder
-            .push(" ON CONFLICT (transaction_id) DO NOTHING")
-            .build();
-        match query.execute(pool).await {
-            Ok(result) => {
-                info!("Inserted {} rows", result.rows_affected())
-            }
-            Err(e) => {
-                error!("Couldn't insert values due to {e}")
-            }
-        }
+        // the status changes until the transfer is final, see status_history
+        query_builder.push(
+            " ON CONFLICT (transaction_id) DO UPDATE SET status = EXCLUDED.status WHERE transfer.status <> EXCLUDED.status",
+        );
+        let result = persistable::execute_upsert("transfer", query_builder, pool).await?;
+        info!(
+            "Inserted {} and updated {} rows",
+            result.inserted, result.updated
+        );
+
+        status_history_handler::record_statuses(
+            Entity::Transfers,
+            &transfer_result
+                .iter()
+                .map(|res| res.transaction_id)
+                .collect(),
+            &transfer_result
+                .iter()
+                .map(|res| res.status.clone())
+                .collect(),
+            &vec![None; transfer_result.len()],
+            pool,
+        )
+        .await?;
+        events_handler::sync_events(
+            EventSource::Transfer,
+            &transfer_result
+                .iter()
+                .map(|res| res.transaction_id)
+                .collect(),
+            pool,
+        )
+        .await?;
+        Ok(result)
     }
 
     async fn get_last_timestamp(
         &self,
         pool: &Pool<Postgres>,
         token_address: &String,
-    ) -> Option<NaiveDateTime> {
+    ) -> Result<Option<NaiveDateTime>, LoaderError> {
         let result: (Option<NaiveDateTime>,) =
             query_as("select max(created_on) from transfer where token_address=$1")
                 .bind(token_address)
                 .fetch_one(pool)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("Couldn't fetch data! {e}");
-                    (None,)
-                });
+                .await?;
+
+        Ok(result.0)
+    }
+}
+
+#[async_trait]
+impl Reconcilable<Transfer> for TransferSaver {
+    fn entity(&self) -> Entity {
+        Entity::Transfers
+    }
+
+    fn table(&self) -> (&'static str, &'static str) {
+        ("transfer", "created_on")
+    }
+
+    fn to_records(&self, transfer: &Transfer) -> Vec<Record> {
+        transfer
+            .result
+            .iter()
+            .map(|res| {
+                Record::new(
+                    res.transaction_id,
+                    vec![
+                        ("status", res.status.clone()),
+                        ("wallet_from", res.wallet_from.clone()),
+                        ("wallet_to", res.wallet_to.clone()),
+                        ("token_id", res.token.data.token_id.clone()),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError> {
+        let rows: Vec<(i32, String, String, String, i32)> = query_as(
+            "select transaction_id, status, wallet_from, wallet_to, token_id from transfer where token_address=$1 and created_on >= $2 and created_on < $3",
+        )
+        .bind(token_address)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
 
-        result.0
+        Ok(rows
+            .into_iter()
+            .map(
+                |(transaction_id, status, wallet_from, wallet_to, token_id)| {
+                    Record::new(
+                        transaction_id,
+                        vec![
+                            ("status", status),
+                            ("wallet_from", wallet_from),
+                            ("wallet_to", wallet_to),
+                            ("token_id", token_id.to_string()),
+                        ],
+                    )
+                },
+            )
+            .collect())
     }
 }
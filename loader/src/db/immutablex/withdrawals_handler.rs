@@ -1,58 +1,163 @@
-use crate::db::immutablex::persistable::Persistable;
+use crate::cli::Entity;
+use crate::db::immutablex::events_handler::EventSource;
+use crate::db::immutablex::persistable;
+use crate::db::immutablex::persistable::{Persistable, WriteCount};
+use crate::db::immutablex::reconcilable::{Reconcilable, Record};
+use crate::db::immutablex::{events_handler, status_history_handler};
+use crate::error::LoaderError;
+use crate::model::immutablex::shared;
 use crate::model::immutablex::withdrawal::Withdrawal;
 use async_trait::async_trait;
-use log::{error, info};
-use sqlx::types::chrono::{DateTime, NaiveDateTime};
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
 use sqlx::{query_as, Pool, Postgres, QueryBuilder};
 
 pub struct WithdrawalSaver;
 
 #[async_trait]
 impl Persistable<Withdrawal> for WithdrawalSaver {
-    async fn create_one(&self, withdrawal: &Withdrawal, pool: &Pool<Postgres>) {
-        let result = &withdrawal.result;
+    async fn create_one(
+        &self,
+        withdrawal: &Withdrawal,
+        pool: &Pool<Postgres>,
+    ) -> Result<WriteCount, LoaderError> {
+        let withdrawal_result = &withdrawal.result;
         let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
-            "insert into withdrawal (transaction_id, status, wallet, token_id, token_address, created_on) ",
+            "insert into withdrawal (transaction_id, status, rollup_status, wallet, token_id, token_address, created_on) ",
         );
-        query_builder.push_values(result, |mut builder, res| {
+        let rows = withdrawal_result
+            .iter()
+            .map(|res| {
+                Ok((
+                    res,
+                    shared::parse_integer("token_id", &res.token.data.token_id)?,
+                    shared::parse_timestamp("timestamp", &res.timestamp)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(LoaderError::Validation)?;
+        query_builder.push_values(rows, |mut builder, (res, token_id, timestamp)| {
             let token_data = &res.token.data;
             builder
                 .push_bind(res.transaction_id)
                 .push_bind(res.status.clone())
+                .push_bind(res.rollup_status.clone())
                 .push_bind(res.wallet.clone())
-                .push_bind(token_data.token_id.clone().parse::<i32>().unwrap())
+                .push_bind(token_id)
                 .push_bind(&token_data.token_address)
-                .push_bind(DateTime::parse_from_rfc3339(&res.timestamp).unwrap());
+                .push_bind(timestamp);
         });
 
-        let query = query_builder
-            .push(" ON CONFLICT (transaction_id) DO NOTHING")
-            .build();
-        match query.execute(pool).await {
-            Ok(result) => {
-                info!("Inserted {} rows", result.rows_affected())
-            }
-            Err(e) => {
-                error!("Couldn't insert values due to {e}")
-            }
-        }
+        // the status changes until the withdrawal is final, see status_history
+        query_builder.push(
+            " ON CONFLICT (transaction_id) DO UPDATE SET status = EXCLUDED.status, rollup_status = EXCLUDED.rollup_status
+             WHERE withdrawal.status <> EXCLUDED.status OR withdrawal.rollup_status IS DISTINCT FROM EXCLUDED.rollup_status",
+        );
+        let result = persistable::execute_upsert("withdrawal", query_builder, pool).await?;
+        info!(
+            "Inserted {} and updated {} rows",
+            result.inserted, result.updated
+        );
+
+        status_history_handler::record_statuses(
+            Entity::Withdrawals,
+            &withdrawal_result
+                .iter()
+                .map(|res| res.transaction_id)
+                .collect(),
+            &withdrawal_result
+                .iter()
+                .map(|res| res.status.clone())
+                .collect(),
+            &withdrawal_result
+                .iter()
+                .map(|res| res.rollup_status.clone())
+                .collect(),
+            pool,
+        )
+        .await?;
+        events_handler::sync_events(
+            EventSource::Withdrawal,
+            &withdrawal_result
+                .iter()
+                .map(|res| res.transaction_id)
+                .collect(),
+            pool,
+        )
+        .await?;
+        Ok(result)
     }
 
     async fn get_last_timestamp(
         &self,
         pool: &Pool<Postgres>,
         token_address: &String,
-    ) -> Option<NaiveDateTime> {
+    ) -> Result<Option<NaiveDateTime>, LoaderError> {
         let result: (Option<NaiveDateTime>,) =
             query_as("select max(created_on) from withdrawal where token_address=$1")
                 .bind(token_address)
                 .fetch_one(pool)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("Couldn't fetch data! {e}");
-                    (None,)
-                });
+                .await?;
+
+        Ok(result.0)
+    }
+}
+
+#[async_trait]
+impl Reconcilable<Withdrawal> for WithdrawalSaver {
+    fn entity(&self) -> Entity {
+        Entity::Withdrawals
+    }
+
+    fn table(&self) -> (&'static str, &'static str) {
+        ("withdrawal", "created_on")
+    }
+
+    fn to_records(&self, withdrawal: &Withdrawal) -> Vec<Record> {
+        withdrawal
+            .result
+            .iter()
+            .map(|res| {
+                Record::new(
+                    res.transaction_id,
+                    vec![
+                        ("status", res.status.clone()),
+                        ("wallet", res.wallet.clone()),
+                        ("token_id", res.token.data.token_id.clone()),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    async fn fetch_records(
+        &self,
+        token_address: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Record>, LoaderError> {
+        let rows: Vec<(i32, String, String, i32)> = query_as(
+            "select transaction_id, status, wallet, token_id from withdrawal where token_address=$1 and created_on >= $2 and created_on < $3",
+        )
+        .bind(token_address)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
 
-        result.0
+        Ok(rows
+            .into_iter()
+            .map(|(transaction_id, status, wallet, token_id)| {
+                Record::new(
+                    transaction_id,
+                    vec![
+                        ("status", status),
+                        ("wallet", wallet),
+                        ("token_id", token_id.to_string()),
+                    ],
+                )
+            })
+            .collect())
     }
 }
@@ -0,0 +1,63 @@
+use crate::api_reader::run_context::StepStats;
+use crate::error::LoaderError;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::Uuid;
+use sqlx::{query, Pool, Postgres};
+
+pub async fn create_run(
+    run_id: &Uuid,
+    command: &str,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into loader_run (id, command, started_on) values ($1, $2, now() at time zone 'utc')",
+    )
+    .bind(run_id)
+    .bind(command)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn finish_run(
+    run_id: &Uuid,
+    succeeded: bool,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "update loader_run set finished_on = now() at time zone 'utc', succeeded = $2 where id = $1",
+    )
+    .bind(run_id)
+    .bind(succeeded)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn create_step(
+    run_id: &Uuid,
+    step: &str,
+    token_address: Option<&String>,
+    started_on: NaiveDateTime,
+    stats: &StepStats,
+    error: Option<String>,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into loader_run_step (run_id, step, token_address, started_on, finished_on, pages_fetched,
+                                      rows_inserted, rows_updated, error, watermark)
+        values ($1, $2, $3, $4, now() at time zone 'utc', $5, $6, $7, $8, $9)",
+    )
+    .bind(run_id)
+    .bind(step)
+    .bind(token_address)
+    .bind(started_on)
+    .bind(stats.pages as i32)
+    .bind(stats.inserted as i64)
+    .bind(stats.updated as i64)
+    .bind(error)
+    .bind(stats.watermark)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
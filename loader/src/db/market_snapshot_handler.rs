@@ -0,0 +1,80 @@
+use crate::error::LoaderError;
+use crate::utils::metrics;
+use log::info;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::{query, Pool, Postgres};
+
+// Writes the snapshot of taken_on's date for every attribute of every collection, taking it again on the same day
+// replaces it. Listings are valued with the latest coin prices, the volume with the prices of the day of the trade,
+// or the latest ones as long as that day's are not loaded yet
+pub async fn save_snapshot(
+    taken_on: NaiveDateTime,
+    pool: &Pool<Postgres>,
+) -> Result<u64, LoaderError> {
+    let result = query(
+        "insert into market_snapshot (snapshot_date, token_address, attribute, floor_price, floor_currency, floor_usd,
+                                      listings, unique_holders, minted, burnt, trades_24h, volume_24h_eth,
+                                      volume_24h_usd, taken_on)
+         with latest_coin as (select distinct on (symbol) symbol, eth, usd
+                              from coin_history
+                              where datestamp <= $1::date
+                              order by symbol, datestamp desc),
+              supply as (select token_address,
+                                attribute,
+                                count(*) as minted,
+                                count(*) filter (where current_owner = '0x0000000000000000000000000000000000000000') as burnt,
+                                count(distinct current_owner)
+                                filter (where current_owner <> '0x0000000000000000000000000000000000000000') as unique_holders
+                         from asset
+                         where attribute is not null
+                         group by token_address, attribute),
+              listing as (select a.token_address, a.attribute, od.buy_price, od.buy_currency, od.buy_price * lc.usd as usd
+                          from order_data od
+                                   join asset a on a.token_id = od.token_id and a.token_address = od.token_address
+                                   left join latest_coin lc on lc.symbol = od.buy_currency
+                          where od.status = 'active' and a.attribute is not null),
+              listings as (select token_address, attribute, count(*) as listings
+                           from listing
+                           group by token_address, attribute),
+              floor as (select distinct on (token_address, attribute) token_address, attribute, buy_price, buy_currency,
+                               round(usd, 2) as usd
+                        from listing
+                        where usd is not null
+                        order by token_address, attribute, usd),
+              volume as (select a.token_address,
+                                a.attribute,
+                                count(*) as trades,
+                                round(sum(od.buy_price * coalesce(ch.eth, lc.eth)), 6) as eth,
+                                round(sum(od.buy_price * coalesce(ch.usd, lc.usd)), 2) as usd
+                         from order_data od
+                                  join asset a on a.token_id = od.token_id and a.token_address = od.token_address
+                                  left join coin_history ch on ch.datestamp = od.updated_on::date and od.buy_currency = ch.symbol
+                                  left join latest_coin lc on lc.symbol = od.buy_currency
+                         where od.status = 'filled'
+                           and od.updated_on >= $1 - interval '24 hours'
+                           and od.updated_on < $1
+                         group by a.token_address, a.attribute)
+         select $1::date, s.token_address, s.attribute, f.buy_price, f.buy_currency, f.usd, coalesce(l.listings, 0),
+                s.unique_holders, s.minted, s.burnt, coalesce(v.trades, 0), coalesce(v.eth, 0), coalesce(v.usd, 0), $1
+         from supply s
+                  left join listings l on l.token_address = s.token_address and l.attribute = s.attribute
+                  left join floor f on f.token_address = s.token_address and f.attribute = s.attribute
+                  left join volume v on v.token_address = s.token_address and v.attribute = s.attribute
+         on conflict (token_address, attribute, snapshot_date) do update set floor_price = excluded.floor_price,
+             floor_currency = excluded.floor_currency, floor_usd = excluded.floor_usd, listings = excluded.listings,
+             unique_holders = excluded.unique_holders, minted = excluded.minted, burnt = excluded.burnt,
+             trades_24h = excluded.trades_24h, volume_24h_eth = excluded.volume_24h_eth,
+             volume_24h_usd = excluded.volume_24h_usd, taken_on = excluded.taken_on",
+    )
+    .bind(taken_on)
+    .execute(pool)
+    .await?;
+
+    info!(
+        "Saved the market snapshot of {} attributes for {}",
+        result.rows_affected(),
+        taken_on.date()
+    );
+    metrics::record_rows_inserted("market_snapshot", result.rows_affected());
+    Ok(result.rows_affected())
+}
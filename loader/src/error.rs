@@ -0,0 +1,93 @@
+use reqwest::StatusCode;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum LoaderError {
+    Transport {
+        endpoint: String,
+        source: reqwest::Error,
+    },
+    HttpStatus {
+        endpoint: String,
+        status: StatusCode,
+        body: String,
+    },
+    RateLimit {
+        endpoint: String,
+    },
+    Decode {
+        endpoint: String,
+        source: reqwest::Error,
+    },
+    Db(sqlx::Error),
+    Validation(String),
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    Image(image::ImageError),
+    // wraps the error that stopped a paginated read, so the run can tell it apart from the end of data
+    Pagination {
+        url: String,
+        page: u32,
+        source: Box<LoaderError>,
+    },
+}
+
+impl Display for LoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::Transport { endpoint, source } => {
+                write!(f, "error {source} requesting {endpoint}")
+            }
+            LoaderError::HttpStatus {
+                endpoint,
+                status,
+                body,
+            } => write!(
+                f,
+                "request to {endpoint} failed with status code {status} and text '{body}'"
+            ),
+            LoaderError::RateLimit { endpoint } => {
+                write!(f, "daily request limit reached for {endpoint}")
+            }
+            LoaderError::Decode { endpoint, source } => {
+                write!(f, "error {source} parsing json body for {endpoint}")
+            }
+            LoaderError::Db(e) => write!(f, "database error {e}"),
+            LoaderError::Validation(message) => write!(f, "invalid data: {message}"),
+            LoaderError::Io { path, source } => write!(f, "error {source} writing {path}"),
+            LoaderError::Image(e) => write!(f, "image error {e}"),
+            LoaderError::Pagination { url, page, source } => {
+                write!(f, "aborted on page {page} of {url} because of {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoaderError::Transport { source, .. } | LoaderError::Decode { source, .. } => {
+                Some(source)
+            }
+            LoaderError::Db(e) => Some(e),
+            LoaderError::Io { source, .. } => Some(source),
+            LoaderError::Image(e) => Some(e),
+            LoaderError::Pagination { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for LoaderError {
+    fn from(e: sqlx::Error) -> Self {
+        LoaderError::Db(e)
+    }
+}
+
+impl From<image::ImageError> for LoaderError {
+    fn from(e: image::ImageError) -> Self {
+        LoaderError::Image(e)
+    }
+}
@@ -1,16 +1,30 @@
-use api_reader::reader::read;
+use api_reader::reader::run;
+use clap::Parser;
+use cli::{Cli, Command};
 use dotenvy::dotenv;
+use std::process::ExitCode;
 
 mod api_reader;
+mod cli;
+mod config;
 mod db;
+mod error;
 mod model;
 mod utils;
 
-fn main() {
+fn main() -> ExitCode {
+    let cli = Cli::parse();
     env_logger::init_from_env(
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "info"),
     );
     dotenv().expect(".env file should be present");
+    // fails fast instead of in the middle of a run
+    config::get();
 
-    read();
+    let command = cli.command.unwrap_or(Command::Sync(Default::default()));
+    if run(command, cli.dry_run) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }
@@ -1,6 +1,7 @@
 pub mod asset;
 pub mod collection;
 pub mod deposit;
+pub mod metadata;
 pub mod mint;
 pub mod order;
 pub mod shared;
@@ -1,4 +1,6 @@
-use crate::model::immutablex::shared::PaginatedApi;
+use crate::model::immutablex::shared::{
+    validate_integer, validate_timestamp, PaginatedApi, Validatable,
+};
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -9,6 +11,12 @@ pub struct Asset {
 }
 
 impl PaginatedApi for Asset {
+    type Record = TheResult;
+
+    fn from_records(result: Vec<TheResult>, cursor: String) -> Self {
+        Self { result, cursor }
+    }
+
     fn get_cursor(&self) -> String {
         self.cursor.clone()
     }
@@ -28,3 +36,13 @@ pub struct TheResult {
     pub created_at: String,
     pub updated_at: String,
 }
+
+impl Validatable for TheResult {
+    const ID_FIELD: &'static str = "token_id";
+
+    fn validate(&self) -> Result<(), String> {
+        validate_integer("token_id", &self.token_id)?;
+        validate_timestamp("created_at", &self.created_at)?;
+        validate_timestamp("updated_at", &self.updated_at)
+    }
+}
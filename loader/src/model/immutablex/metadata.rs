@@ -0,0 +1,531 @@
+use crate::config::CollectionKind;
+use serde::de::DeserializeOwned;
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+// The columns of asset that are derived from its metadata
+#[derive(Debug, PartialEq)]
+pub struct DerivedMetadata {
+    pub attribute: String,
+    pub tier: Option<i16>,
+    pub stage: Option<i16>,
+    pub wave: Option<i16>,
+    // keys none of the metadata structs know about, they are persisted but not used
+    pub unknown_keys: Vec<String>,
+}
+
+trait AssetMetadata: DeserializeOwned {
+    // keys that are not used here but read by the backend, they are not reported as unknown
+    const KNOWN_KEYS: &'static [&'static str];
+
+    // the keys that are not fields of the struct
+    fn other(&self) -> &HashMap<String, Value>;
+
+    // keys whose name depends on the asset, e.g. the slots of an Illuvitar
+    fn is_dynamic_key(_key: &str) -> bool {
+        false
+    }
+
+    fn derive(&self) -> DerivedMetadata;
+}
+
+#[derive(Deserialize)]
+pub struct LandMetadata {
+    #[serde(deserialize_with = "as_text")]
+    pub tier: String,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct D1skMetadata {
+    #[serde(rename = "name", deserialize_with = "as_text")]
+    pub name: String,
+    #[serde(default, deserialize_with = "as_flag")]
+    pub alpha: bool,
+    #[serde(deserialize_with = "as_text")]
+    pub wave: String,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct IlluvitarMetadata {
+    #[serde(deserialize_with = "as_text")]
+    pub set: String,
+    #[serde(deserialize_with = "as_text")]
+    pub wave: String,
+    #[serde(deserialize_with = "as_text")]
+    pub tier: String,
+    #[serde(default, deserialize_with = "as_optional_text")]
+    pub stage: Option<String>,
+    #[serde(default, deserialize_with = "as_optional_text")]
+    pub finish: Option<String>,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccessoryMetadata {
+    #[serde(deserialize_with = "as_text")]
+    pub set: String,
+    #[serde(deserialize_with = "as_text")]
+    pub tier: String,
+    #[serde(deserialize_with = "as_text")]
+    pub stage: String,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+pub struct BlueprintMetadata {
+    #[serde(rename = "Item Tier", deserialize_with = "as_text")]
+    pub item_tier: String,
+    #[serde(rename = "Item Stage", deserialize_with = "as_text")]
+    pub item_stage: String,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EventMetadata {
+    #[serde(deserialize_with = "as_text")]
+    pub promotion: String,
+    #[serde(default, deserialize_with = "as_optional_text")]
+    pub finish: Option<String>,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl AssetMetadata for LandMetadata {
+    const KNOWN_KEYS: &'static [&'static str] = &[
+        "name",
+        "image_url",
+        "region",
+        "landmark",
+        "solon",
+        "carbon",
+        "crypton",
+        "silicon",
+        "hydrogen",
+        "hyperion",
+    ];
+
+    fn other(&self) -> &HashMap<String, Value> {
+        &self.other
+    }
+
+    fn derive(&self) -> DerivedMetadata {
+        DerivedMetadata {
+            attribute: format!("Tier {}", self.tier),
+            tier: as_number(&self.tier),
+            stage: None,
+            wave: None,
+            unknown_keys: vec![],
+        }
+    }
+}
+
+impl AssetMetadata for D1skMetadata {
+    const KNOWN_KEYS: &'static [&'static str] = &["image_url", "Set"];
+
+    fn other(&self) -> &HashMap<String, Value> {
+        &self.other
+    }
+
+    fn derive(&self) -> DerivedMetadata {
+        let alpha = if self.alpha { " Alpha" } else { "" };
+        DerivedMetadata {
+            attribute: format!("{}{alpha} Wave {}", self.name, self.wave),
+            tier: None,
+            stage: None,
+            wave: as_number(&self.wave),
+            unknown_keys: vec![],
+        }
+    }
+}
+
+impl AssetMetadata for IlluvitarMetadata {
+    const KNOWN_KEYS: &'static [&'static str] = &[
+        "name",
+        "image_url",
+        "Line",
+        "Alpha",
+        "Class",
+        "Affinity",
+        "Expression",
+        "Total Power",
+        "Source Disk Id",
+    ];
+
+    fn other(&self) -> &HashMap<String, Value> {
+        &self.other
+    }
+
+    // the bonded accessories, "<Slot> Token Id", "<Slot> Name" and "<Slot> Image Url"
+    fn is_dynamic_key(key: &str) -> bool {
+        key.ends_with(" Token Id") || key.ends_with(" Name") || key.ends_with(" Image Url")
+    }
+
+    fn derive(&self) -> DerivedMetadata {
+        DerivedMetadata {
+            attribute: format!(
+                "Set {} Wave {} Tier {}{}",
+                self.set,
+                self.wave,
+                self.tier,
+                holo_suffix(&self.finish)
+            ),
+            tier: as_number(&self.tier),
+            stage: self.stage.as_deref().and_then(as_number),
+            wave: as_number(&self.wave),
+            unknown_keys: vec![],
+        }
+    }
+}
+
+impl AssetMetadata for AccessoryMetadata {
+    const KNOWN_KEYS: &'static [&'static str] =
+        &["name", "image_url", "Slot", "Multiplier", "Source Disk Id"];
+
+    fn other(&self) -> &HashMap<String, Value> {
+        &self.other
+    }
+
+    // the Illuvitar an accessory is bonded to
+    fn is_dynamic_key(key: &str) -> bool {
+        key == "Base Illuvitar Token Id"
+    }
+
+    fn derive(&self) -> DerivedMetadata {
+        DerivedMetadata {
+            attribute: format!("Set {} Tier {} Stage {}", self.set, self.tier, self.stage),
+            tier: as_number(&self.tier),
+            stage: as_number(&self.stage),
+            wave: None,
+            unknown_keys: vec![],
+        }
+    }
+}
+
+impl AssetMetadata for BlueprintMetadata {
+    const KNOWN_KEYS: &'static [&'static str] = &[
+        "name",
+        "image_url",
+        "Item Type",
+        "Discovered By",
+        "Discovered Location",
+    ];
+
+    fn other(&self) -> &HashMap<String, Value> {
+        &self.other
+    }
+
+    fn derive(&self) -> DerivedMetadata {
+        DerivedMetadata {
+            attribute: format!("Tier {} Stage {}", self.item_tier, self.item_stage),
+            tier: as_number(&self.item_tier),
+            stage: as_number(&self.item_stage),
+            wave: None,
+            unknown_keys: vec![],
+        }
+    }
+}
+
+impl AssetMetadata for EventMetadata {
+    const KNOWN_KEYS: &'static [&'static str] = &["name", "image_url", "Line"];
+
+    fn other(&self) -> &HashMap<String, Value> {
+        &self.other
+    }
+
+    fn derive(&self) -> DerivedMetadata {
+        DerivedMetadata {
+            attribute: format!("{}{}", self.promotion, holo_suffix(&self.finish)),
+            tier: None,
+            stage: None,
+            wave: None,
+            unknown_keys: vec![],
+        }
+    }
+}
+
+// Err is the reason the metadata does not match the struct of the collection kind, e.g. a missing field
+pub fn derive(
+    kind: CollectionKind,
+    metadata: &HashMap<String, Value>,
+) -> Result<DerivedMetadata, String> {
+    match kind {
+        CollectionKind::Land => derive_as::<LandMetadata>(metadata),
+        CollectionKind::D1sk => derive_as::<D1skMetadata>(metadata),
+        CollectionKind::Illuvitar => derive_as::<IlluvitarMetadata>(metadata),
+        CollectionKind::Accessories => derive_as::<AccessoryMetadata>(metadata),
+        CollectionKind::Blueprints => derive_as::<BlueprintMetadata>(metadata),
+        CollectionKind::Events => derive_as::<EventMetadata>(metadata),
+    }
+}
+
+fn derive_as<T: AssetMetadata>(
+    metadata: &HashMap<String, Value>,
+) -> Result<DerivedMetadata, String> {
+    let value = Value::Object(metadata.clone().into_iter().collect());
+    let typed = serde_json::from_value::<T>(value).map_err(|e| e.to_string())?;
+    let mut derived = typed.derive();
+    derived.unknown_keys = typed
+        .other()
+        .keys()
+        .filter(|key| !T::KNOWN_KEYS.contains(&key.as_str()) && !T::is_dynamic_key(key))
+        .cloned()
+        .collect();
+    derived.unknown_keys.sort();
+    Ok(derived)
+}
+
+fn holo_suffix(finish: &Option<String>) -> &'static str {
+    match finish.as_deref() {
+        Some("Holo") => " Holo",
+        _ => "",
+    }
+}
+
+// the normalized columns are left empty for values like "1a"
+fn as_number(value: &str) -> Option<i16> {
+    value.trim().parse::<i16>().ok()
+}
+
+// values are rendered like Postgres ->> does, so the attribute stays the same as the one the former trigger set
+fn as_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    as_optional_text(deserializer)?.ok_or_else(|| de::Error::custom("value is null"))
+}
+
+fn as_optional_text<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(value) => Ok(Some(value)),
+        value @ (Value::Number(_) | Value::Bool(_)) => Ok(Some(value.to_string())),
+        other => Err(de::Error::custom(format!("expected a scalar, got {other}"))),
+    }
+}
+
+fn as_flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(false),
+        Value::Bool(value) => Ok(value),
+        Value::String(value) => match value.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "1" => Ok(true),
+            "false" | "f" | "no" | "0" => Ok(false),
+            _ => Err(de::Error::custom(format!("'{value}' is not a boolean"))),
+        },
+        other => Err(de::Error::custom(format!(
+            "expected a boolean, got {other}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn metadata(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn derives_land() {
+        let derived = derive(
+            CollectionKind::Land,
+            &metadata(json!({"tier": 3, "name": "Plot", "region": "Crystal Shores"})),
+        )
+        .unwrap();
+        assert_eq!(
+            derived,
+            DerivedMetadata {
+                attribute: String::from("Tier 3"),
+                tier: Some(3),
+                stage: None,
+                wave: None,
+                unknown_keys: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn derives_d1sk_with_and_without_alpha() {
+        let alpha = derive(
+            CollectionKind::D1sk,
+            &metadata(json!({"name": "Genesis Mega D1sk", "Alpha": "true", "Wave": "1"})),
+        )
+        .unwrap();
+        assert_eq!(alpha.attribute, "Genesis Mega D1sk Alpha Wave 1");
+        assert_eq!(alpha.wave, Some(1));
+
+        let plain = derive(
+            CollectionKind::D1sk,
+            &metadata(json!({"name": "Genesis D1sk", "Wave": 2})),
+        )
+        .unwrap();
+        assert_eq!(plain.attribute, "Genesis D1sk Wave 2");
+    }
+
+    #[test]
+    fn derives_illuvitar_with_holo_finish_and_dynamic_keys() {
+        let derived = derive(
+            CollectionKind::Illuvitar,
+            &metadata(json!({
+                "Set": 1, "Wave": 2, "Tier": 3, "Stage": "2", "Finish": "Holo",
+                "Head Token Id": 5, "Head Name": "Hat", "Head Image Url": "url",
+            })),
+        )
+        .unwrap();
+        assert_eq!(
+            derived,
+            DerivedMetadata {
+                attribute: String::from("Set 1 Wave 2 Tier 3 Holo"),
+                tier: Some(3),
+                stage: Some(2),
+                wave: Some(2),
+                unknown_keys: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn derives_accessory() {
+        let derived = derive(
+            CollectionKind::Accessories,
+            &metadata(json!({"Set": 1, "Tier": 2, "Stage": 3, "Base Illuvitar Token Id": 7})),
+        )
+        .unwrap();
+        assert_eq!(derived.attribute, "Set 1 Tier 2 Stage 3");
+        assert_eq!((derived.tier, derived.stage), (Some(2), Some(3)));
+        assert!(derived.unknown_keys.is_empty());
+    }
+
+    #[test]
+    fn derives_blueprint() {
+        let derived = derive(
+            CollectionKind::Blueprints,
+            &metadata(json!({"Item Tier": "4", "Item Stage": "1", "Item Type": "Weapon"})),
+        )
+        .unwrap();
+        assert_eq!(derived.attribute, "Tier 4 Stage 1");
+        assert_eq!((derived.tier, derived.stage), (Some(4), Some(1)));
+    }
+
+    #[test]
+    fn derives_event_with_and_without_finish() {
+        let holo = derive(
+            CollectionKind::Events,
+            &metadata(json!({"Promotion": "Launch", "Finish": "Holo"})),
+        )
+        .unwrap();
+        assert_eq!(holo.attribute, "Launch Holo");
+
+        let plain = derive(
+            CollectionKind::Events,
+            &metadata(json!({"Promotion": "Launch", "Finish": "Matte"})),
+        )
+        .unwrap();
+        assert_eq!(plain.attribute, "Launch");
+    }
+
+    #[test]
+    fn keeps_non_numeric_values_in_the_attribute_only() {
+        let derived = derive(CollectionKind::Land, &metadata(json!({"tier": "1a"}))).unwrap();
+        assert_eq!(derived.attribute, "Tier 1a");
+        assert_eq!(derived.tier, None);
+    }
+
+    // the former trigger concatenated what was there, e.g. "Set  Wave 2 Tier 3", the attribute is null now
+    #[test]
+    fn fails_on_missing_or_null_fields() {
+        assert!(derive(
+            CollectionKind::Illuvitar,
+            &metadata(json!({"Wave": 2, "Tier": 3}))
+        )
+        .is_err());
+        assert!(derive(CollectionKind::Land, &metadata(json!({"tier": null}))).is_err());
+        assert!(derive(CollectionKind::Events, &metadata(json!({}))).is_err());
+    }
+
+    #[test]
+    fn fails_on_non_scalar_values() {
+        assert!(derive(CollectionKind::Land, &metadata(json!({"tier": [1]}))).is_err());
+        assert!(derive(
+            CollectionKind::D1sk,
+            &metadata(json!({"name": "D1sk", "Alpha": "maybe", "Wave": 1}))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reports_unknown_keys_sorted() {
+        let derived = derive(
+            CollectionKind::Land,
+            &metadata(json!({"tier": 1, "name": "Plot", "zeta": 1, "alpha": 2})),
+        )
+        .unwrap();
+        assert_eq!(derived.unknown_keys, vec!["alpha", "zeta"]);
+    }
+
+    #[test]
+    fn renders_text_like_postgres() {
+        #[derive(Deserialize)]
+        struct Text {
+            #[serde(deserialize_with = "as_text")]
+            value: String,
+        }
+        let text = |value: Value| {
+            serde_json::from_value::<Text>(json!({ "value": value }))
+                .map(|text| text.value)
+                .ok()
+        };
+        assert_eq!(text(json!("a")), Some(String::from("a")));
+        assert_eq!(text(json!(1)), Some(String::from("1")));
+        assert_eq!(text(json!(1.5)), Some(String::from("1.5")));
+        assert_eq!(text(json!(true)), Some(String::from("true")));
+        assert_eq!(text(Value::Null), None);
+        assert_eq!(text(json!({"a": 1})), None);
+    }
+
+    #[test]
+    fn reads_flags() {
+        #[derive(Deserialize)]
+        struct Flag {
+            #[serde(default, deserialize_with = "as_flag")]
+            value: bool,
+        }
+        let flag = |value: Value| {
+            serde_json::from_value::<Flag>(json!({ "value": value }))
+                .map(|flag| flag.value)
+                .ok()
+        };
+        assert_eq!(flag(json!(true)), Some(true));
+        assert_eq!(flag(json!("Yes")), Some(true));
+        assert_eq!(flag(json!("0")), Some(false));
+        assert_eq!(flag(Value::Null), Some(false));
+        assert_eq!(flag(json!("maybe")), None);
+        assert_eq!(flag(json!(1)), None);
+        assert_eq!(
+            serde_json::from_value::<Flag>(json!({}))
+                .map(|flag| flag.value)
+                .ok(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn adds_holo_suffix_only_for_holo() {
+        assert_eq!(holo_suffix(&Some(String::from("Holo"))), " Holo");
+        assert_eq!(holo_suffix(&Some(String::from("Dark Holo"))), "");
+        assert_eq!(holo_suffix(&None), "");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::model::immutablex::shared::PaginatedApi;
+use crate::model::immutablex::shared::{validate_timestamp, PaginatedApi, Validatable};
 use crate::model::immutablex::token::Token;
 use serde::Deserialize;
 
@@ -9,6 +9,12 @@ pub struct Mint {
 }
 
 impl PaginatedApi for Mint {
+    type Record = TheResult;
+
+    fn from_records(result: Vec<TheResult>, cursor: String) -> Self {
+        Self { result, cursor }
+    }
+
     fn get_cursor(&self) -> String {
         self.cursor.clone()
     }
@@ -28,3 +34,12 @@ pub struct TheResult {
     pub wallet: String,
     pub token: Token,
 }
+
+impl Validatable for TheResult {
+    const ID_FIELD: &'static str = "transaction_id";
+
+    fn validate(&self) -> Result<(), String> {
+        self.token.validate()?;
+        validate_timestamp("timestamp", &self.minted_on)
+    }
+}
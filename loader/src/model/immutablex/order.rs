@@ -1,4 +1,6 @@
-use crate::model::immutablex::shared::PaginatedApi;
+use crate::model::immutablex::shared::{
+    validate_integer, validate_price, validate_timestamp, PaginatedApi, Validatable,
+};
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -8,6 +10,12 @@ pub struct Order {
 }
 
 impl PaginatedApi for Order {
+    type Record = TheResult;
+
+    fn from_records(result: Vec<TheResult>, cursor: String) -> Self {
+        Self { result, cursor }
+    }
+
     fn get_cursor(&self) -> String {
         self.cursor.clone()
     }
@@ -26,10 +34,39 @@ pub struct TheResult {
     pub sell: Sell,
     pub maker_fees: MakerFees,
     pub taker_fees: TakerFees,
+    // only returned with include_fees=true
+    #[serde(default)]
+    pub fees: Vec<Fee>,
     pub timestamp: String,
     pub updated_timestamp: String,
 }
 
+impl Validatable for TheResult {
+    const ID_FIELD: &'static str = "order_id";
+
+    fn validate(&self) -> Result<(), String> {
+        match &self.sell.data.token_id {
+            None => return Err(String::from("sell token_id is missing")),
+            Some(token_id) => validate_integer("token_id", token_id)?,
+        }
+        validate_price(
+            "maker_fees",
+            &self.maker_fees.quantity_with_fees,
+            self.maker_fees.decimals,
+        )?;
+        validate_price(
+            "taker_fees",
+            &self.taker_fees.quantity_with_fees,
+            self.taker_fees.decimals,
+        )?;
+        for fee in &self.fees {
+            validate_price(&fee.fee_type, &fee.amount, fee.token.data.decimals)?;
+        }
+        validate_timestamp("timestamp", &self.timestamp)?;
+        validate_timestamp("updated_timestamp", &self.updated_timestamp)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Sell {
     pub data: SellData,
@@ -54,10 +91,28 @@ pub struct TakerFees {
     pub decimals: i32,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct Fee {
+    #[serde(rename = "type")]
+    pub fee_type: String,
+    #[serde(rename = "address")]
+    pub recipient: String,
+    pub token: FeeToken,
+    pub amount: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FeeToken {
+    pub data: FeeTokenData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FeeTokenData {
+    pub decimals: i32,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct SingleOrder {
     #[serde(rename = "user")]
     pub wallet: String,
-    pub maker_fees: MakerFees,
-    pub taker_fees: TakerFees,
 }
@@ -1,5 +1,99 @@
-pub trait PaginatedApi {
+use rust_decimal::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::types::chrono::{DateTime, FixedOffset};
+
+pub trait PaginatedApi: Sized {
+    type Record: DeserializeOwned + Validatable;
+
+    fn from_records(result: Vec<Self::Record>, cursor: String) -> Self;
+
     fn get_cursor(&self) -> String;
 
     fn has_results(&self) -> bool;
 }
+
+pub trait Validatable {
+    // also read from the raw JSON of records that could not be deserialized at all
+    const ID_FIELD: &'static str;
+
+    // everything the savers parse has to be checked here, so a single odd record can't fail the whole page
+    fn validate(&self) -> Result<(), String>;
+}
+
+// A page as returned by the API, its records are deserialized and validated one by one
+#[derive(Deserialize, Debug)]
+pub struct RawPage {
+    pub result: Vec<Value>,
+    pub cursor: String,
+}
+
+#[derive(Debug)]
+pub struct InvalidRecord {
+    pub record_id: Option<String>,
+    pub raw: Value,
+    pub reason: String,
+}
+
+// splits the page into the records that can be persisted and the ones to quarantine
+pub fn parse_page<T: PaginatedApi>(page: RawPage) -> (T, Vec<InvalidRecord>) {
+    let mut records = Vec::with_capacity(page.result.len());
+    let mut invalid_records = Vec::new();
+    for raw in page.result {
+        match parse_record::<T::Record>(raw) {
+            Ok(record) => records.push(record),
+            Err(invalid_record) => invalid_records.push(invalid_record),
+        }
+    }
+    (T::from_records(records, page.cursor), invalid_records)
+}
+
+pub fn parse_record<R: DeserializeOwned + Validatable>(raw: Value) -> Result<R, InvalidRecord> {
+    let reason = match serde_json::from_value::<R>(raw.clone()) {
+        Ok(record) => match record.validate() {
+            Ok(()) => return Ok(record),
+            Err(reason) => reason,
+        },
+        Err(e) => format!("could not be deserialized, {e}"),
+    };
+    let record_id = raw.get(R::ID_FIELD).map(|id| match id {
+        Value::String(id) => id.clone(),
+        other => other.to_string(),
+    });
+    Err(InvalidRecord {
+        record_id,
+        raw,
+        reason,
+    })
+}
+
+pub fn validate_integer(field: &str, value: &str) -> Result<(), String> {
+    parse_integer(field, value).map(|_| ())
+}
+
+pub fn validate_timestamp(field: &str, value: &str) -> Result<(), String> {
+    parse_timestamp(field, value).map(|_| ())
+}
+
+// the savers parse what validate checked, so a record that reaches them unchecked is an error, not a panic
+pub fn parse_integer(field: &str, value: &str) -> Result<i32, String> {
+    value
+        .parse::<i32>()
+        .map_err(|e| format!("{field} '{value}' is not an integer, {e}"))
+}
+
+pub fn parse_timestamp(field: &str, value: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map_err(|e| format!("{field} '{value}' is not an RFC 3339 timestamp, {e}"))
+}
+
+// see price_utils::get_price
+pub fn validate_price(field: &str, quantity: &str, decimals: i32) -> Result<(), String> {
+    if !(0..=18).contains(&decimals) {
+        return Err(format!("{field} has unsupported decimals {decimals}"));
+    }
+    Decimal::from_str(quantity)
+        .map(|_| ())
+        .map_err(|e| format!("{field} '{quantity}' is not a decimal, {e}"))
+}
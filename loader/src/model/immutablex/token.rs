@@ -1,3 +1,4 @@
+use crate::model::immutablex::shared::validate_integer;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -10,3 +11,9 @@ pub struct TokenData {
     pub token_id: String,
     pub token_address: String,
 }
+
+impl Token {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_integer("token_id", &self.data.token_id)
+    }
+}
@@ -1,4 +1,6 @@
-use crate::model::immutablex::shared::PaginatedApi;
+use crate::model::immutablex::shared::{
+    validate_integer, validate_timestamp, PaginatedApi, Validatable,
+};
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -8,6 +10,12 @@ pub struct Trade {
 }
 
 impl PaginatedApi for Trade {
+    type Record = TheResult;
+
+    fn from_records(result: Vec<TheResult>, cursor: String) -> Self {
+        Self { result, cursor }
+    }
+
     fn get_cursor(&self) -> String {
         self.cursor.clone()
     }
@@ -20,10 +28,21 @@ impl PaginatedApi for Trade {
 #[derive(Deserialize, Debug)]
 pub struct TheResult {
     pub transaction_id: i32,
+    pub status: String,
     #[serde(rename = "a")]
     pub buyer: Buyer,
     #[serde(rename = "b")]
     pub seller: Seller,
+    pub timestamp: String,
+}
+
+impl Validatable for TheResult {
+    const ID_FIELD: &'static str = "transaction_id";
+
+    fn validate(&self) -> Result<(), String> {
+        validate_integer("token_id", &self.seller.token_id)?;
+        validate_timestamp("timestamp", &self.timestamp)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -34,4 +53,6 @@ pub struct Buyer {
 #[derive(Deserialize, Debug)]
 pub struct Seller {
     pub order_id: i32,
+    pub token_id: String,
+    pub token_address: String,
 }
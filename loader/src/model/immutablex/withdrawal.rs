@@ -1,4 +1,4 @@
-use crate::model::immutablex::shared::PaginatedApi;
+use crate::model::immutablex::shared::{validate_timestamp, PaginatedApi, Validatable};
 use crate::model::immutablex::token::Token;
 use serde::Deserialize;
 
@@ -9,6 +9,12 @@ pub struct Withdrawal {
 }
 
 impl PaginatedApi for Withdrawal {
+    type Record = TheResult;
+
+    fn from_records(result: Vec<TheResult>, cursor: String) -> Self {
+        Self { result, cursor }
+    }
+
     fn get_cursor(&self) -> String {
         self.cursor.clone()
     }
@@ -22,8 +28,19 @@ impl PaginatedApi for Withdrawal {
 pub struct TheResult {
     pub transaction_id: i32,
     pub status: String,
+    // included once the withdrawal is in a batch, confirmed once the batch is on L1
+    pub rollup_status: Option<String>,
     #[serde(rename = "sender")]
     pub wallet: String,
     pub timestamp: String,
     pub token: Token,
 }
+
+impl Validatable for TheResult {
+    const ID_FIELD: &'static str = "transaction_id";
+
+    fn validate(&self) -> Result<(), String> {
+        self.token.validate()?;
+        validate_timestamp("timestamp", &self.timestamp)
+    }
+}
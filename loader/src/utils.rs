@@ -1,2 +1,4 @@
 pub mod env_utils;
+pub mod image_store;
+pub mod metrics;
 pub mod price_utils;
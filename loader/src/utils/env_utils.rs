@@ -11,6 +11,18 @@ where
         .expect(format!("{} should be a valid u16", key).as_str())
 }
 
+pub fn as_parsed_or<T: FromStr>(key: &str, default: T) -> T
+where
+    <T as FromStr>::Err: Debug,
+{
+    match env::var(key) {
+        Ok(value) => value
+            .parse::<T>()
+            .unwrap_or_else(|_| panic!("{key} should be a valid value")),
+        Err(_) => default,
+    }
+}
+
 pub fn as_string(key: &str) -> String {
     String::from(
         env::var(key)
@@ -0,0 +1,86 @@
+use crate::config;
+use crate::error::LoaderError;
+use image::codecs::webp::WebPEncoder;
+use image::DynamicImage;
+use sha2::{Digest, Sha256};
+use sqlx::types::Uuid;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ORIGINAL_FILE: &str = "original";
+
+pub struct StoredImage {
+    pub content_hash: String,
+    pub content_type: String,
+}
+
+// Files are content addressed, <dir>/<first 2 chars of the hash>/<hash>/original and <hash>/<size>.webp,
+// the backend resolves them the same way. Images that are stored already are not written again
+pub fn store(bytes: &[u8], dry_run: bool) -> Result<StoredImage, LoaderError> {
+    let format = image::guess_format(bytes)?;
+    let content_hash = format!("{:x}", Sha256::digest(bytes));
+    let stored_image = StoredImage {
+        content_hash,
+        content_type: format.to_mime_type().to_owned(),
+    };
+    let images = &config::get().images;
+    let dir = get_dir(&images.dir, &stored_image.content_hash);
+    let missing_sizes: Vec<u32> = images
+        .thumbnail_sizes
+        .iter()
+        .copied()
+        .filter(|size| !dir.join(thumbnail_file(*size)).exists())
+        .collect();
+    // decoding is the expensive part, so it is also skipped on dry runs
+    if dry_run || (missing_sizes.is_empty() && dir.join(ORIGINAL_FILE).exists()) {
+        return Ok(stored_image);
+    }
+
+    let image = image::load_from_memory_with_format(bytes, format)?;
+    fs::create_dir_all(&dir).map_err(|source| to_io_error(&dir, source))?;
+    write_atomically(&dir.join(ORIGINAL_FILE), bytes)?;
+    for size in missing_sizes {
+        write_atomically(
+            &dir.join(thumbnail_file(size)),
+            &encode_thumbnail(&image, size)?,
+        )?;
+    }
+    Ok(stored_image)
+}
+
+fn get_dir(root: &str, content_hash: &str) -> PathBuf {
+    Path::new(root).join(&content_hash[..2]).join(content_hash)
+}
+
+fn thumbnail_file(size: u32) -> String {
+    format!("{size}.webp")
+}
+
+// keeps the aspect ratio, smaller images are not scaled up
+fn encode_thumbnail(image: &DynamicImage, size: u32) -> Result<Vec<u8>, LoaderError> {
+    let thumbnail = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image.clone()
+    };
+    // the encoder only supports 8 bit images
+    let thumbnail = DynamicImage::ImageRgba8(thumbnail.to_rgba8());
+    let mut bytes = Vec::new();
+    thumbnail.write_with_encoder(WebPEncoder::new_lossless(&mut bytes))?;
+    Ok(bytes)
+}
+
+// the backend never sees a half written file. Images with the same content share their path,
+// every write gets its own temporary file so concurrent ones do not collide
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), LoaderError> {
+    let tmp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    fs::write(&tmp_path, bytes).map_err(|source| to_io_error(&tmp_path, source))?;
+    fs::rename(&tmp_path, path).map_err(|source| to_io_error(path, source))
+}
+
+fn to_io_error(path: &Path, source: std::io::Error) -> LoaderError {
+    LoaderError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
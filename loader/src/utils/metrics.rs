@@ -0,0 +1,106 @@
+use log::error;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec, TextEncoder,
+};
+use std::env;
+use std::fs;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+static API_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "loader_api_requests_total",
+        "API requests sent per host and response status",
+        &["host", "status"]
+    )
+    .unwrap()
+});
+
+static RATE_LIMIT_HITS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "loader_rate_limit_hits_total",
+        "Requests delayed by the local rate limiter or answered with 429",
+        &["host", "limiter"]
+    )
+    .unwrap()
+});
+
+static ROWS_WRITTEN: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "loader_rows_written_total",
+        "Rows inserted or updated per table",
+        &["table", "operation"]
+    )
+    .unwrap()
+});
+
+static METADATA_ISSUES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "loader_metadata_issues_total",
+        "Asset metadata that is invalid or has unknown keys, per collection kind",
+        &["kind", "issue"]
+    )
+    .unwrap()
+});
+
+static MAT_VIEW_REFRESH: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "loader_mat_view_refresh_duration_seconds",
+        "Duration of materialized view refreshes",
+        &["view"],
+        vec![0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0]
+    )
+    .unwrap()
+});
+
+// status is "error" if no response was received at all
+pub fn record_api_request(host: &str, status: &str) {
+    API_REQUESTS.with_label_values(&[host, status]).inc();
+}
+
+// limiter is "local" for the token bucket and "remote" for 429 responses
+pub fn record_rate_limit_hit(host: &str, limiter: &str) {
+    RATE_LIMIT_HITS.with_label_values(&[host, limiter]).inc();
+}
+
+pub fn record_rows_inserted(table: &str, rows: u64) {
+    ROWS_WRITTEN
+        .with_label_values(&[table, "inserted"])
+        .inc_by(rows);
+}
+
+pub fn record_rows_updated(table: &str, rows: u64) {
+    ROWS_WRITTEN
+        .with_label_values(&[table, "updated"])
+        .inc_by(rows);
+}
+
+// issue is "invalid" or "unknown_key"
+pub fn record_metadata_issue(kind: &str, issue: &str, count: u64) {
+    METADATA_ISSUES
+        .with_label_values(&[kind, issue])
+        .inc_by(count);
+}
+
+pub fn record_mat_view_refresh(view: &str, duration: Duration) {
+    MAT_VIEW_REFRESH
+        .with_label_values(&[view])
+        .observe(duration.as_secs_f64());
+}
+
+// Writes all metrics in the Prometheus text format to METRICS_FILE, if set
+// meant for the node_exporter textfile collector, the file is replaced atomically so it is never read half-written
+pub fn write_to_file() {
+    let Ok(path) = env::var("METRICS_FILE") else {
+        return;
+    };
+    let tmp_path = format!("{path}.tmp");
+    let result = TextEncoder::new()
+        .encode_to_string(&prometheus::gather())
+        .map_err(|e| e.to_string())
+        .and_then(|metrics| fs::write(&tmp_path, metrics).map_err(|e| e.to_string()))
+        .and_then(|_| fs::rename(&tmp_path, &path).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        error!("Metrics could not be written to {path}: {e}");
+    }
+}
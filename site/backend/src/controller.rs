@@ -1,7 +1,10 @@
 pub mod assets_controller;
 pub mod collection_controller;
+pub mod image_controller;
+pub mod metrics_controller;
 pub mod mints_controller;
 pub mod search_controller;
 pub mod stats_controller;
+pub mod status_controller;
 pub mod vitals_controller;
 pub mod wallet_controller;
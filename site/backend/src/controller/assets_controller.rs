@@ -1,5 +1,7 @@
-use crate::db::{assets_events_handler, assets_handler};
+use crate::db::{asset_history_handler, assets_events_handler, assets_handler};
+use crate::utils::cursor_utils;
 use actix_web::{get, web, HttpResponse, Responder};
+use model::model::transaction::EventFilter;
 use serde::Deserialize;
 use sqlx::{Pool, Postgres};
 
@@ -13,8 +15,7 @@ pub struct Params {
 pub struct EventsParams {
     token_address: String,
     token_id: i32,
-    page: i32,
-    event: String,
+    cursor: Option<String>,
 }
 
 #[get("/api/asset/asset")]
@@ -38,13 +39,15 @@ pub async fn get_asset(
 pub async fn get_events(
     pool: web::Data<Pool<Postgres>>,
     params: web::Query<EventsParams>,
+    filter: web::Query<EventFilter>,
 ) -> actix_web::Result<impl Responder> {
+    let cursor = cursor_utils::from_param(&params.cursor)?;
     return match assets_events_handler::get_events_for_token_address_and_token_id(
         &pool,
         &params.token_address,
         &params.token_id,
-        &params.page,
-        &params.event,
+        cursor.as_ref(),
+        &filter,
     )
     .await
     {
@@ -52,3 +55,20 @@ pub async fn get_events(
         Some(asset) => Ok(HttpResponse::Ok().json(asset)),
     };
 }
+
+#[get("/api/asset/history")]
+pub async fn get_history(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<Params>,
+) -> actix_web::Result<impl Responder> {
+    return match asset_history_handler::get_history_for_token_address_and_token_id(
+        &pool,
+        &params.token_address,
+        &params.token_id,
+    )
+    .await
+    {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(history) => Ok(HttpResponse::Ok().json(history)),
+    };
+}
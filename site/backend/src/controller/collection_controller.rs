@@ -1,5 +1,7 @@
-use crate::db::collection_handler;
+use crate::db::{collection_events_handler, collection_handler};
+use crate::utils::cursor_utils;
 use actix_web::{get, web, HttpResponse, Responder};
+use model::model::transaction::EventFilter;
 use serde::Deserialize;
 use sqlx::{Pool, Postgres};
 
@@ -8,6 +10,12 @@ pub struct Params {
     token_address: String,
 }
 
+#[derive(Deserialize)]
+pub struct EventsParams {
+    token_address: String,
+    cursor: Option<String>,
+}
+
 #[get("/api/collection/collections")]
 pub async fn get_collections(pool: web::Data<Pool<Postgres>>) -> actix_web::Result<impl Responder> {
     return match collection_handler::get_all_collections(&pool).await {
@@ -27,3 +35,23 @@ pub async fn get_collection(
         Some(result) => Ok(HttpResponse::Ok().json(result)),
     };
 }
+
+#[get("/api/collection/events")]
+pub async fn get_collection_events(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<EventsParams>,
+    filter: web::Query<EventFilter>,
+) -> actix_web::Result<impl Responder> {
+    let cursor = cursor_utils::from_param(&params.cursor)?;
+    return match collection_events_handler::get_collection_events(
+        &pool,
+        &params.token_address,
+        cursor.as_ref(),
+        &filter,
+    )
+    .await
+    {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
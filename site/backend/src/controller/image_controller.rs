@@ -0,0 +1,57 @@
+use crate::db::asset_images_handler;
+use crate::utils::image_utils;
+use actix_web::http::header::{CacheControl, CacheDirective, LOCATION};
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::{Pool, Postgres};
+
+// mirrored files only change with the image_url of the asset
+const MAX_AGE_SECONDS: u32 = 24 * 60 * 60;
+
+#[derive(Deserialize)]
+pub struct ImageParams {
+    size: Option<u32>,
+}
+
+// Serves the WebP thumbnail of the given size, or the original image if no size is given.
+// Images that are not mirrored are redirected to the upstream host
+#[get("/img/{token_address}/{token_id}")]
+pub async fn get_image(
+    pool: web::Data<Pool<Postgres>>,
+    path: web::Path<(String, i32)>,
+    params: web::Query<ImageParams>,
+) -> actix_web::Result<impl Responder> {
+    let (token_address, token_id) = path.into_inner();
+    if let Some(size) = params.size {
+        if !image_utils::is_thumbnail_size(size) {
+            return Ok(HttpResponse::BadRequest().body(format!("unsupported size {size}")));
+        }
+    }
+    let Some(image) = asset_images_handler::get_asset_image(&pool, &token_address, &token_id).await
+    else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    if let Some(content_hash) = image.content_hash {
+        let file = image_utils::get_path(&content_hash, params.size);
+        if let Ok(bytes) = web::block(move || std::fs::read(file)).await? {
+            let content_type = match params.size {
+                Some(_) => String::from("image/webp"),
+                None => image.content_type.unwrap_or_default(),
+            };
+            return Ok(HttpResponse::Ok()
+                .content_type(content_type)
+                .insert_header(CacheControl(vec![
+                    CacheDirective::Public,
+                    CacheDirective::MaxAge(MAX_AGE_SECONDS),
+                ]))
+                .body(bytes));
+        }
+    }
+    Ok(match image.image_url {
+        Some(image_url) => HttpResponse::Found()
+            .insert_header((LOCATION, image_url))
+            .finish(),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
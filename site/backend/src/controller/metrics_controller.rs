@@ -0,0 +1,10 @@
+use crate::utils::metrics;
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::{Pool, Postgres};
+
+#[get("/metrics")]
+pub async fn get_metrics(pool: web::Data<Pool<Postgres>>) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics::gather(&pool)))
+}
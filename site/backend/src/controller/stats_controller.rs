@@ -1,6 +1,11 @@
-use crate::db::stats_handler;
+use crate::db::{
+    candles_handler, market_snapshot_handler, order_events_handler, order_fees_handler,
+    stats_handler,
+};
 use actix_web::{get, web, HttpResponse, Responder};
+use model::model::stats::{CandleCurrency, CandleInterval};
 use serde::Deserialize;
+use sqlx::types::chrono::NaiveDate;
 use sqlx::{Pool, Postgres};
 
 #[derive(Deserialize)]
@@ -8,6 +13,24 @@ pub struct Params {
     token_address: String,
 }
 
+#[derive(Deserialize)]
+pub struct HistoryParams {
+    token_address: String,
+    attribute: Option<String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+pub struct CandlesParams {
+    token_address: String,
+    attribute: Option<String>,
+    interval: CandleInterval,
+    currency: Option<CandleCurrency>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
 #[get("/api/stat/stats")]
 pub async fn get_stats(
     pool: web::Data<Pool<Postgres>>,
@@ -19,3 +42,98 @@ pub async fn get_stats(
         Some(result) => Ok(HttpResponse::Ok().json(result)),
     };
 }
+
+#[get("/api/stat/listing-duration")]
+pub async fn get_listing_durations(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<Params>,
+) -> actix_web::Result<impl Responder> {
+    return match order_events_handler::get_listing_durations(&params.token_address, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
+
+#[get("/api/stat/reprices")]
+pub async fn get_reprices(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<Params>,
+) -> actix_web::Result<impl Responder> {
+    return match order_events_handler::get_reprices(&params.token_address, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
+
+#[get("/api/stat/time-to-sell")]
+pub async fn get_time_to_sell(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<Params>,
+) -> actix_web::Result<impl Responder> {
+    return match order_events_handler::get_time_to_sell(&params.token_address, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
+
+#[get("/api/stat/royalties")]
+pub async fn get_royalties(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<Params>,
+) -> actix_web::Result<impl Responder> {
+    return match order_fees_handler::get_royalties(&params.token_address, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
+
+#[get("/api/stat/marketplaces")]
+pub async fn get_marketplace_shares(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<Params>,
+) -> actix_web::Result<impl Responder> {
+    return match order_fees_handler::get_marketplace_shares(&params.token_address, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
+
+#[get("/api/stat/history")]
+pub async fn get_market_history(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<HistoryParams>,
+) -> actix_web::Result<impl Responder> {
+    return match market_snapshot_handler::get_market_history(
+        &params.token_address,
+        params.attribute.as_ref(),
+        params.from,
+        params.to,
+        &pool,
+    )
+    .await
+    {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
+
+#[get("/api/stat/candles")]
+pub async fn get_candles(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<CandlesParams>,
+) -> actix_web::Result<impl Responder> {
+    return match candles_handler::get_candles(
+        &params.token_address,
+        params.attribute.as_ref(),
+        params.interval,
+        params.currency.unwrap_or(CandleCurrency::Native),
+        params.from,
+        params.to,
+        &pool,
+    )
+    .await
+    {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
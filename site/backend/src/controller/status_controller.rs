@@ -0,0 +1,11 @@
+use crate::db::status_handler;
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::{Pool, Postgres};
+
+#[get("/api/status/freshness")]
+pub async fn get_freshness(pool: web::Data<Pool<Postgres>>) -> actix_web::Result<impl Responder> {
+    return match status_handler::get_freshness(&pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(result) => Ok(HttpResponse::Ok().json(result)),
+    };
+}
@@ -1,18 +1,64 @@
-use crate::db::{wallet_events_handler, wallet_handler};
-use actix_web::{get, web, HttpResponse, Responder};
+use crate::db::{
+    portfolio_handler, wallet_assets_handler, wallet_events_handler, wallet_handler,
+    wallet_pnl_handler, wallet_portfolio_handler,
+};
+use crate::utils::cursor_utils;
+use actix_web::error::{ErrorBadRequest, ErrorNotFound};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use model::model::transaction::EventFilter;
+use model::model::wallet::{PnlMethod, TransferBasis, WalletAssetSort};
 use serde::Deserialize;
 use sqlx::{Pool, Postgres};
 
+// the most wallets a group can have, every query of a group matches each of them
+const MAX_WALLETS: usize = 20;
+
+// A request is about a single wallet, a comma separated list of wallets or the wallets of a saved portfolio,
+// the first one given is taken
 #[derive(Deserialize)]
 pub struct WalletParams {
-    wallet: String,
+    wallet: Option<String>,
+    wallets: Option<String>,
+    portfolio: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PnlParams {
+    wallet: Option<String>,
+    wallets: Option<String>,
+    portfolio: Option<String>,
+    method: Option<PnlMethod>,
+    transfer_basis: Option<TransferBasis>,
 }
 
 #[derive(Deserialize)]
 pub struct EventsParams {
-    wallet: String,
+    wallet: Option<String>,
+    wallets: Option<String>,
+    portfolio: Option<String>,
+    cursor: Option<String>,
+}
+
+// collection is the token address
+#[derive(Deserialize)]
+pub struct AssetsParams {
+    wallet: Option<String>,
+    wallets: Option<String>,
+    portfolio: Option<String>,
+    collection: Option<String>,
+    attribute: Option<String>,
+    sort: Option<WalletAssetSort>,
     page: i32,
-    event: String,
+}
+
+#[derive(Deserialize)]
+pub struct PortfolioParams {
+    id: String,
+}
+
+#[derive(Deserialize)]
+pub struct SavePortfolioParams {
+    wallets: Vec<String>,
 }
 
 #[get("/api/wallet/wallet")]
@@ -20,7 +66,8 @@ pub async fn get_wallet(
     pool: web::Data<Pool<Postgres>>,
     params: web::Query<WalletParams>,
 ) -> actix_web::Result<impl Responder> {
-    return match wallet_handler::get_wallet(&pool, &params.wallet).await {
+    let wallets = get_wallets(&params.wallet, &params.wallets, &params.portfolio, &pool).await?;
+    return match wallet_handler::get_wallet(&pool, &wallets).await {
         None => Ok(HttpResponse::NotFound().finish()),
         Some(asset) => Ok(HttpResponse::Ok().json(asset)),
     };
@@ -30,16 +77,138 @@ pub async fn get_wallet(
 pub async fn get_wallet_events(
     pool: web::Data<Pool<Postgres>>,
     params: web::Query<EventsParams>,
+    filter: web::Query<EventFilter>,
+) -> actix_web::Result<impl Responder> {
+    let wallets = get_wallets(&params.wallet, &params.wallets, &params.portfolio, &pool).await?;
+    let cursor = cursor_utils::from_param(&params.cursor)?;
+    return match wallet_events_handler::get_wallet_events(&pool, &wallets, cursor.as_ref(), &filter)
+        .await
+    {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(asset) => Ok(HttpResponse::Ok().json(asset)),
+    };
+}
+
+#[get("/api/wallet/pnl")]
+pub async fn get_wallet_pnl(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<PnlParams>,
+) -> actix_web::Result<impl Responder> {
+    let wallets = get_wallets(&params.wallet, &params.wallets, &params.portfolio, &pool).await?;
+    return match wallet_pnl_handler::get_wallet_pnl(
+        &wallets,
+        params.method.unwrap_or(PnlMethod::Fifo),
+        params.transfer_basis.unwrap_or(TransferBasis::Zero),
+        &pool,
+    )
+    .await
+    {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(pnl) => Ok(HttpResponse::Ok().json(pnl)),
+    };
+}
+
+#[get("/api/wallet/portfolio")]
+pub async fn get_wallet_portfolio(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<WalletParams>,
+) -> actix_web::Result<impl Responder> {
+    let wallets = get_wallets(&params.wallet, &params.wallets, &params.portfolio, &pool).await?;
+    return match wallet_portfolio_handler::get_wallet_portfolio(&wallets, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(portfolio) => Ok(HttpResponse::Ok().json(portfolio)),
+    };
+}
+
+#[get("/api/wallet/assets")]
+pub async fn get_wallet_assets(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<AssetsParams>,
 ) -> actix_web::Result<impl Responder> {
-    return match wallet_events_handler::get_wallet_events(
+    let wallets = get_wallets(&params.wallet, &params.wallets, &params.portfolio, &pool).await?;
+    return match wallet_assets_handler::get_wallet_assets(
         &pool,
-        &params.wallet,
+        &wallets,
+        params.collection.as_ref(),
+        params.attribute.as_ref(),
+        params.sort.unwrap_or(WalletAssetSort::Name),
         params.page,
-        &params.event,
     )
     .await
     {
         None => Ok(HttpResponse::NotFound().finish()),
-        Some(asset) => Ok(HttpResponse::Ok().json(asset)),
+        Some(assets) => Ok(HttpResponse::Ok().json(assets)),
+    };
+}
+
+#[get("/api/portfolio")]
+pub async fn get_portfolio(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Query<PortfolioParams>,
+) -> actix_web::Result<impl Responder> {
+    return match portfolio_handler::get_portfolio(&params.id, &pool).await {
+        None => Ok(HttpResponse::NotFound().finish()),
+        Some(portfolio) => Ok(HttpResponse::Ok().json(portfolio)),
+    };
+}
+
+#[post("/api/portfolio")]
+pub async fn save_portfolio(
+    pool: web::Data<Pool<Postgres>>,
+    params: web::Json<SavePortfolioParams>,
+) -> actix_web::Result<impl Responder> {
+    let wallets = parse_wallets(params.wallets.iter().map(String::as_str))?;
+    return match portfolio_handler::save_portfolio(&wallets, &pool).await {
+        None => Ok(HttpResponse::InternalServerError().finish()),
+        Some(portfolio) => Ok(HttpResponse::Ok().json(portfolio)),
     };
 }
+
+async fn get_wallets(
+    wallet: &Option<String>,
+    wallets: &Option<String>,
+    portfolio: &Option<String>,
+    pool: &Pool<Postgres>,
+) -> actix_web::Result<Vec<String>> {
+    if let Some(wallet) = wallet {
+        return parse_wallets(std::iter::once(wallet.as_str()));
+    }
+    if let Some(wallets) = wallets {
+        return parse_wallets(wallets.split(','));
+    }
+    if let Some(id) = portfolio {
+        return match portfolio_handler::get_portfolio(id, pool).await {
+            None => Err(ErrorNotFound(format!("unknown portfolio {id}"))),
+            Some(portfolio) => Ok(portfolio.wallets),
+        };
+    }
+    Err(ErrorBadRequest(
+        "one of wallet, wallets or portfolio is required",
+    ))
+}
+
+// lowercases the wallets and drops duplicates, as the same wallet would be counted twice otherwise
+fn parse_wallets<'a>(wallets: impl Iterator<Item = &'a str>) -> actix_web::Result<Vec<String>> {
+    let mut parsed: Vec<String> = vec![];
+    for wallet in wallets {
+        let wallet = wallet.trim().to_lowercase();
+        if !is_wallet(&wallet) {
+            return Err(ErrorBadRequest(format!("invalid wallet {wallet}")));
+        }
+        if !parsed.contains(&wallet) {
+            parsed.push(wallet);
+        }
+    }
+    if parsed.is_empty() || parsed.len() > MAX_WALLETS {
+        return Err(ErrorBadRequest(format!(
+            "between 1 and {MAX_WALLETS} wallets are required"
+        )));
+    }
+    Ok(parsed)
+}
+
+fn is_wallet(wallet: &str) -> bool {
+    wallet.len() == 42
+        && wallet.starts_with("0x")
+        && wallet[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -1,10 +1,24 @@
+pub mod asset_history_handler;
+pub mod asset_images_handler;
 pub mod assets_events_handler;
 pub mod assets_handler;
+pub mod candles_handler;
+pub mod collection_events_handler;
 pub mod collection_handler;
 pub mod db_handler;
 mod db_model;
+mod event_filter;
+pub mod market_snapshot_handler;
 pub mod mints_handler;
+pub mod order_events_handler;
+pub mod order_fees_handler;
+pub mod portfolio_handler;
 pub mod stats_handler;
+pub mod status_handler;
 pub mod vitals_handler;
+pub mod wallet_assets_handler;
 pub mod wallet_events_handler;
 pub mod wallet_handler;
+pub mod wallet_ledger_handler;
+pub mod wallet_pnl_handler;
+pub mod wallet_portfolio_handler;
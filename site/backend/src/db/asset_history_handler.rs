@@ -0,0 +1,89 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::asset::{AssetHistoryData, MetadataChange};
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+use std::collections::BTreeMap;
+
+// Every metadata version of the asset, oldest first, with the keys that changed compared to the previous one
+pub async fn get_history_for_token_address_and_token_id(
+    pool: &Pool<Postgres>,
+    token_address: &String,
+    token_id: &i32,
+) -> Option<Vec<AssetHistoryData>> {
+    return match query_as::<_, MetadataValueDb>(
+        "select h.id, h.attribute, h.valid_from, h.valid_to, m.key, m.value
+         from asset_metadata_history h
+                  cross join lateral jsonb_each_text(h.metadata) m
+         where h.token_address = $1 and h.token_id = $2
+         order by h.valid_from, h.id, m.key",
+    )
+    .bind(token_address)
+    .bind(token_id)
+    .fetch_all(pool)
+    .timed("get_history_for_token_address_and_token_id")
+    .await
+    {
+        Ok(result) => Some(to_history(result)),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+fn to_history(values: Vec<MetadataValueDb>) -> Vec<AssetHistoryData> {
+    let mut versions: Vec<(AssetHistoryData, BTreeMap<String, Option<String>>)> = Vec::new();
+    let mut last_id = None;
+    for value in values {
+        if last_id != Some(value.id) {
+            last_id = Some(value.id);
+            versions.push((
+                AssetHistoryData {
+                    attribute: value.attribute,
+                    valid_from: value.valid_from,
+                    valid_to: value.valid_to,
+                    changes: vec![],
+                },
+                BTreeMap::new(),
+            ));
+        }
+        versions
+            .last_mut()
+            .unwrap()
+            .1
+            .insert(value.key, value.value);
+    }
+
+    let mut previous_metadata = BTreeMap::new();
+    let mut history = Vec::with_capacity(versions.len());
+    for (mut version, metadata) in versions {
+        let mut keys: Vec<&String> = metadata.keys().chain(previous_metadata.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let previous_value = previous_metadata.get(key).cloned().flatten();
+            let value = metadata.get(key).cloned().flatten();
+            if previous_value != value {
+                version.changes.push(MetadataChange {
+                    key: key.clone(),
+                    previous_value,
+                    value,
+                });
+            }
+        }
+        history.push(version);
+        previous_metadata = metadata;
+    }
+    history
+}
+
+#[derive(FromRow)]
+struct MetadataValueDb {
+    pub id: i64,
+    pub attribute: Option<String>,
+    pub valid_from: NaiveDateTime,
+    pub valid_to: Option<NaiveDateTime>,
+    pub key: String,
+    pub value: Option<String>,
+}
@@ -0,0 +1,37 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// content_hash is None if the image was not mirrored (yet), image_url is the upstream one then
+pub async fn get_asset_image(
+    pool: &Pool<Postgres>,
+    token_address: &String,
+    token_id: &i32,
+) -> Option<AssetImageDb> {
+    return match query_as::<_, AssetImageDb>(
+        "select a.metadata->>'image_url' as image_url, i.content_hash, i.content_type
+         from asset a
+                  left join asset_image i on i.token_address = a.token_address and i.token_id = a.token_id
+                                          and i.source_url = a.metadata->>'image_url'
+         where a.token_address = $1 and a.token_id = $2",
+    )
+    .bind(token_address)
+    .bind(token_id)
+    .fetch_optional(pool)
+    .timed("get_asset_image")
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+pub struct AssetImageDb {
+    pub image_url: Option<String>,
+    pub content_hash: Option<String>,
+    pub content_type: Option<String>,
+}
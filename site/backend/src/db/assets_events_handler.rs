@@ -1,49 +1,67 @@
-use crate::db::db_model::TransactionDataDb;
+use crate::db::db_model::{to_event_data, TransactionDataDb, EVENTS_PER_PAGE, MAX_COUNTED_EVENTS};
+use crate::db::event_filter;
+use crate::utils::cursor_utils::Cursor;
+use crate::utils::metrics::TimedQuery;
 use log::error;
-use model::model::transaction::EventData;
-use sqlx::{query, query_as, Pool, Postgres, Row};
+use model::model::transaction::{EventData, EventFilter};
+use sqlx::{query_as, Pool, Postgres};
 
+// The events of an asset matching the filter, the page after the cursor or the first one
 pub async fn get_events_for_token_address_and_token_id(
     pool: &Pool<Postgres>,
     token_address: &String,
     token_id: &i32,
-    page: &i32,
-    event: &String,
+    cursor: Option<&Cursor>,
+    filter: &EventFilter,
 ) -> Option<EventData> {
-    let total: i64 = match query("select count(timestamp) from events_view where (token_address=$1 and token_id=$2) and ($3 = 'All' or event = $3)")
-        .bind(token_address)
-        .bind(token_id)
-        .bind(event)
-        .fetch_one(pool)
-        .await
+    let (condition, limit) = event_filter::condition(filter, 3, None);
+    let count_sql = format!(
+        "select count(*) from (select 1 from event
+                               where token_address = $1 and token_id = $2 and {condition}
+                               limit ${limit}) e"
+    );
+    let counted: i64 = match event_filter::bind(
+        query_as::<_, (i64,)>(&count_sql)
+            .bind(token_address)
+            .bind(token_id),
+        filter,
+        None,
+    )
+    .bind(MAX_COUNTED_EVENTS + 1)
+    .fetch_one(pool)
+    .timed("get_events_for_token_address_and_token_id")
+    .await
     {
-        Ok(result) => result.get(0),
+        Ok(result) => result.0,
         Err(e) => {
             error!("Error fetching data: {e}");
             0
         }
     };
 
-    return match query_as::<_, TransactionDataDb>(
-        // no need to display the image for asset, so have to select those as nulls
-        "select transaction_id, wallet_from, wallet_to, event, timestamp, currency, price, usd_price,
-         null as token_address, null as token_id, null as name, null as image_url from events_view
-         where (token_address=$1 and token_id=$2) and ($3 = 'All' or event = $3)
-         order by timestamp desc
-         limit 50 offset $4")
-        .bind(token_address)
-        .bind(token_id)
-        .bind(event)
-        .bind((page - 1) * 50)
-        .fetch_all(pool)
-        .await
+    // no need to display the image for asset, so have to select those as nulls
+    let sql = format!(
+        "select id as event_id, transaction_id, wallet_from, wallet_to, event, timestamp, currency, price, usd_price,
+                null as token_address, null as token_id, null as name, null as image_url
+         from event
+         where token_address = $1 and token_id = $2 and {condition}
+         order by timestamp {order}, id {order}
+         limit ${limit}",
+        order = event_filter::order(filter)
+    );
+    return match event_filter::bind(
+        query_as::<_, TransactionDataDb>(&sql)
+            .bind(token_address)
+            .bind(token_id),
+        filter,
+        cursor,
+    )
+    .bind(EVENTS_PER_PAGE + 1)
+    .fetch_all(pool)
+    .timed("get_events_for_token_address_and_token_id")
+    .await
     {
-        Ok(result) => Some(
-            EventData {
-                total,
-                transactions: result.into_iter().map(|t| t.into()).collect(),
-            }
-        ),
+        Ok(result) => Some(to_event_data(result, counted)),
         Err(e) => {
             error!("Error fetching data: {e}");
             None
@@ -1,4 +1,8 @@
 use crate::db::db_model::SingleTransactionDb;
+use crate::utils::config;
+use crate::utils::config::CollectionKind;
+use crate::utils::image_utils;
+use crate::utils::metrics::TimedQuery;
 use log::error;
 use model::model::asset::{
     AccessoriesAssetData, AssetContentData, AssetData, BlueprintAssetData, CommonAssetData,
@@ -10,13 +14,6 @@ use sqlx::{query, query_as, FromRow, Pool, Postgres, Row};
 
 const BURNED_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
 
-const D1SK: &str = "0xc1f1da534e227489d617cd742481fd5a23f6a003";
-const LAND: &str = "0x9e0d99b864e1ac12565125c5a82b59adea5a09cd";
-const ILLUVITAR: &str = "0x8cceea8cfb0f8670f4de3a6cd2152925605d19a8";
-const ACCESSORIES: &str = "0x844a2a2b4c139815c1da4bdd447ab558bb9a7d24";
-const BLUEPRINTS: &str = "0x07fb805d026194d188014fc7303e69f412eb7cb1";
-const EVENTS: &str = "0x0d78b8aeddb8d3c8b8903a474f8a91855bfdf6f2";
-
 pub async fn get_search_results(pool: &Pool<Postgres>, search: &String) -> Option<SearchData> {
     return match query_as::<_, AssetContentDb>(
         "select token_id, token_address, metadata->>'name' as name, metadata->>'image_url' as image_url from asset
@@ -26,6 +23,7 @@ pub async fn get_search_results(pool: &Pool<Postgres>, search: &String) -> Optio
     )
         .bind(search.to_lowercase())
         .fetch_all(pool)
+        .timed("get_search_results")
         .await
     {
         Ok(res) => {
@@ -53,6 +51,7 @@ pub async fn get_asset_for_token_address_and_token_id(
     .bind(token_address)
     .bind(token_id)
     .fetch_one(pool)
+    .timed("get_asset_for_token_address_and_token_id")
     .await
     {
         Ok(result) => {
@@ -77,24 +76,27 @@ pub async fn get_asset_for_token_address_and_token_id(
         return None;
     }
 
-    if token_address == D1SK {
-        return get_d1sk_asset(pool, token_address, token_id, common_asset_data.unwrap()).await;
-    } else if token_address == ACCESSORIES {
-        return get_accessories_asset(pool, token_address, token_id, common_asset_data.unwrap())
-            .await;
-    } else if token_address == ILLUVITAR {
-        return get_illuvitar_asset(pool, token_address, token_id, common_asset_data.unwrap())
-            .await;
-    } else if token_address == LAND {
-        return get_land_asset(pool, token_address, token_id, common_asset_data.unwrap()).await;
-    } else if token_address == BLUEPRINTS {
-        return get_blueprint_asset(pool, token_address, token_id, common_asset_data.unwrap())
-            .await;
-    } else if token_address == EVENTS {
-        return get_events_asset(pool, token_address, token_id, common_asset_data.unwrap()).await;
+    let common_asset_data = common_asset_data.unwrap();
+    match config::kind_of(token_address)? {
+        CollectionKind::D1sk => {
+            get_d1sk_asset(pool, token_address, token_id, common_asset_data).await
+        }
+        CollectionKind::Accessories => {
+            get_accessories_asset(pool, token_address, token_id, common_asset_data).await
+        }
+        CollectionKind::Illuvitar => {
+            get_illuvitar_asset(pool, token_address, token_id, common_asset_data).await
+        }
+        CollectionKind::Land => {
+            get_land_asset(pool, token_address, token_id, common_asset_data).await
+        }
+        CollectionKind::Blueprints => {
+            get_blueprint_asset(pool, token_address, token_id, common_asset_data).await
+        }
+        CollectionKind::Events => {
+            get_events_asset(pool, token_address, token_id, common_asset_data).await
+        }
     }
-
-    return None;
 }
 
 async fn get_d1sk_asset(
@@ -109,6 +111,7 @@ async fn get_d1sk_asset(
             and metadata ->> 'Base Illuvitar Token Id' is null order by token_address, name")
         .bind(token_id)
         .fetch_all(pool)
+        .timed("get_d1sk_asset")
         .await
     {
         Ok(result) => result.into_iter().map(|t| t.into()).collect(),
@@ -129,6 +132,7 @@ async fn get_d1sk_asset(
         .bind(token_address)
         .bind(token_id)
         .fetch_one(pool)
+        .timed("get_d1sk_asset")
         .await
     {
         Ok(result) => {
@@ -167,9 +171,10 @@ async fn get_accessories_asset(
             FROM asset iluv
             WHERE (iluv.metadata ->> (SELECT (metadata->>'Slot' || ' Token Id') FROM
              asset WHERE token_id = $2 and token_address=$1))::int4 = $2")
-            .bind(ACCESSORIES)
+            .bind(config::address_of(CollectionKind::Accessories))
             .bind(token_id)
             .fetch_optional(pool)
+            .timed("get_accessories_asset")
             .await
         {
             Ok(result) => result.map(|value| value.into()),
@@ -196,6 +201,7 @@ async fn get_accessories_asset(
     .bind(token_address)
     .bind(token_id)
     .fetch_one(pool)
+    .timed("get_accessories_asset")
     .await
     {
         Ok(result) => Some(AssetData {
@@ -237,14 +243,17 @@ async fn get_illuvitar_asset(
         .bind(token_address)
         .bind(token_id)
         .fetch_all(pool)
+        .timed("get_illuvitar_asset")
         .await
     {
         Ok(result) => {
+            let token_address = config::address_of(CollectionKind::Accessories).unwrap_or_default();
             result.into_iter().map(|row| AssetContentData {
                 token_id: row.get(0),
-                token_address: ACCESSORIES.to_owned(),
+                token_address: token_address.to_owned(),
                 name: row.get(1),
                 image_url: row.get(2),
+                thumbnail_url: image_utils::thumbnail_url(token_address, row.get(0)),
             }).collect()
         }
         Err(e) => {
@@ -259,6 +268,7 @@ async fn get_illuvitar_asset(
     )
     .bind(token_id)
     .fetch_optional(pool)
+    .timed("get_illuvitar_asset")
     .await
     {
         Ok(result) => result.map(|value| value.into()),
@@ -282,6 +292,7 @@ async fn get_illuvitar_asset(
     .bind(token_address)
     .bind(token_id)
     .fetch_optional(pool)
+    .timed("get_illuvitar_asset")
     .await
     {
         Ok(result) => result.map(|value| value.into()),
@@ -307,6 +318,7 @@ async fn get_illuvitar_asset(
         .bind(token_address)
         .bind(token_id)
         .fetch_one(pool)
+        .timed("get_illuvitar_asset")
         .await
     {
         Ok(result) => {
@@ -359,8 +371,9 @@ async fn get_source_d1sk(
         where a.token_id = (acc.source_disk_id)::int4 and a.token_address=$3")
         .bind(token_address)
         .bind(token_id)
-        .bind(D1SK)
+        .bind(config::address_of(CollectionKind::D1sk))
         .fetch_optional(pool)
+        .timed("get_source_d1sk")
         .await
     {
         Ok(result) => result.map(|value| value.into()),
@@ -384,8 +397,9 @@ async fn get_land_asset(
             token_address=$2",
     )
     .bind(token_id)
-    .bind(BLUEPRINTS)
+    .bind(config::address_of(CollectionKind::Blueprints))
     .fetch_one(pool)
+    .timed("get_land_asset")
     .await
     {
         Ok(result) => result.get(0),
@@ -409,6 +423,7 @@ async fn get_land_asset(
         .bind(token_address)
         .bind(token_id)
         .fetch_one(pool)
+        .timed("get_land_asset")
         .await
     {
         Ok(result) => {
@@ -458,8 +473,9 @@ async fn get_blueprint_asset(
         where a.token_address = $3")
         .bind(token_address)
         .bind(token_id)
-        .bind(LAND)
+        .bind(config::address_of(CollectionKind::Land))
         .fetch_optional(pool)
+        .timed("get_blueprint_asset")
         .await
     {
         Ok(result) => result.map(|value| value.into()),
@@ -481,6 +497,7 @@ async fn get_blueprint_asset(
     .bind(token_address)
     .bind(token_id)
     .fetch_one(pool)
+    .timed("get_blueprint_asset")
     .await
     {
         Ok(result) => Some(AssetData {
@@ -522,6 +539,7 @@ async fn get_events_asset(
         .bind(token_address)
         .bind(token_id)
         .fetch_one(pool)
+        .timed("get_events_asset")
         .await
     {
         Ok(result) => {
@@ -566,6 +584,7 @@ async fn query_common_order_data(
         .bind(token_address)
         .bind(token_id)
         .fetch_all(pool)
+        .timed("query_common_order_data")
         .await
     {
         Ok(result) => result.into_iter().map(|t| t.into()).collect(),
@@ -590,6 +609,7 @@ async fn query_common_order_data(
         .bind(token_address)
         .bind(token_id)
         .fetch_all(pool)
+        .timed("query_common_order_data")
         .await
     {
         Ok(result) => {
@@ -671,6 +691,7 @@ struct AssetContentDb {
 impl From<AssetContentDb> for AssetContentData {
     fn from(data: AssetContentDb) -> Self {
         Self {
+            thumbnail_url: image_utils::thumbnail_url(&data.token_address, data.token_id),
             token_id: data.token_id,
             token_address: data.token_address,
             name: data.name,
@@ -0,0 +1,89 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::stats::{CandleCurrency, CandleInterval, StatsDataCandle};
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime};
+use sqlx::types::Decimal;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// Candles of the filled orders of a collection, or of one attribute of it. Native prices are not converted, so there
+// is a series per buy currency, in USD trades of days without a coin price are left out.
+// from is inclusive and to exclusive, both are optional
+pub async fn get_candles(
+    token_address: &String,
+    attribute: Option<&String>,
+    interval: CandleInterval,
+    currency: CandleCurrency,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataCandle>> {
+    return match query_as::<_, StatsDataCandleDb>(
+        "select date_trunc($3, od.updated_on) as period_start,
+                p.currency,
+                (array_agg(p.price order by od.updated_on, od.order_id))[1] as open,
+                max(p.price) as high,
+                min(p.price) as low,
+                (array_agg(p.price order by od.updated_on desc, od.order_id desc))[1] as close,
+                sum(p.price) as volume,
+                count(*) as trades
+         from order_data od
+                  left join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+                  cross join lateral (select case when $4 then round(od.buy_price * ch.usd, 2) else od.buy_price end as price,
+                                             case when $4 then 'USD' else od.buy_currency end as currency) p
+         where od.token_address = $1
+           and od.status = 'filled'
+           and p.price is not null
+           and ($2::varchar is null or exists (select 1
+                                                from asset a
+                                                where a.token_address = od.token_address
+                                                  and a.token_id = od.token_id
+                                                  and a.attribute = $2))
+           and ($5::date is null or od.updated_on >= $5)
+           and ($6::date is null or od.updated_on < $6)
+         group by 1, 2
+         order by 2, 1",
+    )
+    .bind(token_address)
+    .bind(attribute)
+    .bind(interval.unit())
+    .bind(currency == CandleCurrency::Usd)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .timed("get_candles")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct StatsDataCandleDb {
+    pub period_start: NaiveDateTime,
+    pub currency: String,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    pub trades: i64,
+}
+
+impl From<StatsDataCandleDb> for StatsDataCandle {
+    fn from(data: StatsDataCandleDb) -> Self {
+        Self {
+            period_start: data.period_start,
+            currency: data.currency,
+            open: f64::try_from(data.open).unwrap(),
+            high: f64::try_from(data.high).unwrap(),
+            low: f64::try_from(data.low).unwrap(),
+            close: f64::try_from(data.close).unwrap(),
+            volume: f64::try_from(data.volume).unwrap(),
+            trades: data.trades,
+        }
+    }
+}
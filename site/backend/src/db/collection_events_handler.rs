@@ -0,0 +1,67 @@
+use crate::db::db_model::{to_event_data, TransactionDataDb, EVENTS_PER_PAGE, MAX_COUNTED_EVENTS};
+use crate::db::event_filter;
+use crate::utils::cursor_utils::Cursor;
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::transaction::{EventData, EventFilter};
+use sqlx::{query_as, Pool, Postgres};
+
+// The events of all assets of a collection matching the filter, the page after the cursor or the first one
+pub async fn get_collection_events(
+    pool: &Pool<Postgres>,
+    token_address: &String,
+    cursor: Option<&Cursor>,
+    filter: &EventFilter,
+) -> Option<EventData> {
+    let (condition, limit) = event_filter::condition(filter, 2, None);
+    let count_sql = format!(
+        "select count(*) from (select 1 from event
+                               where token_address = $1 and {condition}
+                               limit ${limit}) e"
+    );
+    let counted: i64 = match event_filter::bind(
+        query_as::<_, (i64,)>(&count_sql).bind(token_address),
+        filter,
+        None,
+    )
+    .bind(MAX_COUNTED_EVENTS + 1)
+    .fetch_one(pool)
+    .timed("get_collection_events")
+    .await
+    {
+        Ok(result) => result.0,
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            0
+        }
+    };
+
+    let sql = format!(
+        "select e.id as event_id, e.transaction_id, e.wallet_from, e.wallet_to, e.event, e.timestamp, e.currency,
+                e.price, e.usd_price, e.token_address, e.token_id, coalesce(a.metadata->>'name', '') as name,
+                coalesce(a.metadata->>'image_url', '') as image_url
+         from (select * from event
+               where token_address = $1 and {condition}
+               order by timestamp {order}, id {order}
+               limit ${limit}) e
+                  left join asset a on a.token_address = e.token_address and a.token_id = e.token_id
+         order by e.timestamp {order}, e.id {order}",
+        order = event_filter::order(filter)
+    );
+    return match event_filter::bind(
+        query_as::<_, TransactionDataDb>(&sql).bind(token_address),
+        filter,
+        cursor,
+    )
+    .bind(EVENTS_PER_PAGE + 1)
+    .fetch_all(pool)
+    .timed("get_collection_events")
+    .await
+    {
+        Ok(result) => Some(to_event_data(result, counted)),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
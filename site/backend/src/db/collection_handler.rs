@@ -1,3 +1,4 @@
+use crate::utils::metrics::TimedQuery;
 use log::error;
 use model::model::collection::CollectionData;
 use sqlx::types::chrono::NaiveDateTime;
@@ -8,6 +9,7 @@ pub async fn get_all_collections(pool: &Pool<Postgres>) -> Option<Vec<Collection
         "select address, name, collection_image_url, description, created_on from collection order by created_on",
     )
         .fetch_all(pool)
+        .timed("get_all_collections")
         .await
     {
         Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
@@ -27,6 +29,7 @@ pub async fn get_collection_for_address(
     )
         .bind(address)
         .fetch_one(pool)
+        .timed("get_collection_for_address")
         .await
     {
         Ok(result) => Some(result.into()),
@@ -1,7 +1,9 @@
+use crate::utils::cursor_utils::Cursor;
+use crate::utils::image_utils;
 use model::model::asset::AssetContentData;
 use model::model::price::Price;
 use model::model::transaction::SingleTransaction;
-use model::model::transaction::TransactionData;
+use model::model::transaction::{EventData, TransactionData};
 use sqlx::types::{chrono::NaiveDateTime, Decimal};
 use sqlx::FromRow;
 
@@ -38,8 +40,14 @@ impl From<SingleTransactionDb> for SingleTransaction {
     }
 }
 
+pub const EVENTS_PER_PAGE: i64 = 50;
+// counting the events of a feed stops here, busy wallets have far more than anyone pages through
+pub const MAX_COUNTED_EVENTS: i64 = 10_000;
+
+// a row of the event table, event_id is the id of the row
 #[derive(FromRow)]
 pub struct TransactionDataDb {
+    event_id: i64,
     transaction_id: Option<i32>,
     wallet_from: Option<String>,
     wallet_to: Option<String>,
@@ -70,6 +78,10 @@ impl From<TransactionDataDb> for TransactionData {
             .token_address
             .map(|token_address| AssetContentData {
                 token_id: event_data.token_id.unwrap(),
+                thumbnail_url: image_utils::thumbnail_url(
+                    &token_address,
+                    event_data.token_id.unwrap(),
+                ),
                 token_address,
                 name: event_data.name.unwrap(),
                 image_url: event_data.image_url.unwrap(),
@@ -87,3 +99,25 @@ impl From<TransactionDataDb> for TransactionData {
         }
     }
 }
+
+// Rows are fetched one beyond the page, which tells there is a next one. counted is the capped count of the feed
+pub fn to_event_data(mut rows: Vec<TransactionDataDb>, counted: i64) -> EventData {
+    let next_cursor = if rows.len() as i64 > EVENTS_PER_PAGE {
+        rows.truncate(EVENTS_PER_PAGE as usize);
+        rows.last().map(|row| {
+            Cursor {
+                timestamp: row.timestamp,
+                id: row.event_id,
+            }
+            .to_string()
+        })
+    } else {
+        None
+    };
+    EventData {
+        total: counted.min(MAX_COUNTED_EVENTS),
+        total_capped: counted > MAX_COUNTED_EVENTS,
+        next_cursor,
+        transactions: rows.into_iter().map(|row| row.into()).collect(),
+    }
+}
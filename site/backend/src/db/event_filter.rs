@@ -0,0 +1,145 @@
+use crate::utils::cursor_utils::Cursor;
+use model::model::transaction::{EventFilter, EventSort};
+use sqlx::postgres::PgArguments;
+use sqlx::query::QueryAs;
+use sqlx::Postgres;
+
+const PARAMETERS: usize = 9;
+
+// The condition of the filter and the cursor on the columns of event and the number of the parameter after it.
+// Its parameters are numbered from first on and bound by bind in the same order. counterparty is the column the
+// counterparty wallet is matched against, either side of the event when none
+pub fn condition(
+    filter: &EventFilter,
+    first: usize,
+    counterparty: Option<&str>,
+) -> (String, usize) {
+    let [events, from, to, min_usd, max_usd, currency, wallet, timestamp, id]: [String;
+        PARAMETERS] = std::array::from_fn(|index| format!("${}", first + index));
+    let counterparty = match counterparty {
+        Some(column) => format!("{column} = {wallet}"),
+        None => format!("(wallet_from = {wallet} or wallet_to = {wallet})"),
+    };
+    let condition = format!(
+        "(cardinality({events}::varchar[]) = 0 or event = any({events}))
+         and ({from}::date is null or timestamp >= {from}::date)
+         and ({to}::date is null or timestamp < {to}::date + 1)
+         and ({min_usd}::float8 is null or usd_price >= {min_usd}::float8)
+         and ({max_usd}::float8 is null or usd_price <= {max_usd}::float8)
+         and ({currency}::varchar is null or currency = {currency})
+         and ({wallet}::varchar is null or {counterparty})
+         and ({timestamp}::timestamp is null or (timestamp, id) {} ({timestamp}, {id}::bigint))",
+        match sort(filter) {
+            EventSort::Newest => "<",
+            EventSort::Oldest => ">",
+        }
+    );
+    (condition, first + PARAMETERS)
+}
+
+pub fn bind<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    filter: &EventFilter,
+    cursor: Option<&Cursor>,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    query
+        .bind(filter.events())
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.min_usd)
+        .bind(filter.max_usd)
+        .bind(filter.currency.as_ref().map(|value| value.to_uppercase()))
+        .bind(
+            filter
+                .counterparty
+                .as_ref()
+                .map(|value| value.to_lowercase()),
+        )
+        .bind(cursor.map(|cursor| cursor.timestamp))
+        .bind(cursor.map(|cursor| cursor.id))
+}
+
+// the direction of the feed, the index of the event table serves both
+pub fn order(filter: &EventFilter) -> &'static str {
+    match sort(filter) {
+        EventSort::Newest => "desc",
+        EventSort::Oldest => "asc",
+    }
+}
+
+fn sort(filter: &EventFilter) -> EventSort {
+    filter.sort.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(sql: &str) -> Vec<usize> {
+        let mut numbers: Vec<usize> = sql
+            .split('$')
+            .skip(1)
+            .map(|rest| {
+                rest.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap()
+            })
+            .collect();
+        numbers.sort();
+        numbers.dedup();
+        numbers
+    }
+
+    #[test]
+    fn condition_numbers_its_parameters_from_first_on() {
+        let (sql, next) = condition(&EventFilter::default(), 3, None);
+        assert_eq!(placeholders(&sql), (3..12).collect::<Vec<_>>());
+        assert_eq!(next, 12);
+    }
+
+    #[test]
+    fn condition_takes_the_same_parameters_for_either_counterparty_column() {
+        let (sent, next_sent) = condition(&EventFilter::default(), 2, Some("wallet_to"));
+        let (received, next_received) = condition(&EventFilter::default(), 2, Some("wallet_from"));
+        assert_eq!(placeholders(&sent), placeholders(&received));
+        assert_eq!(next_sent, next_received);
+        assert!(sent.contains("wallet_to = $8"));
+        assert!(received.contains("wallet_from = $8"));
+    }
+
+    #[test]
+    fn condition_compares_the_cursor_in_the_direction_of_the_feed() {
+        let oldest = EventFilter {
+            sort: Some(EventSort::Oldest),
+            ..EventFilter::default()
+        };
+        assert!(condition(&EventFilter::default(), 2, None)
+            .0
+            .contains("(timestamp, id) < ($9, $10::bigint)"));
+        assert!(condition(&oldest, 2, None)
+            .0
+            .contains("(timestamp, id) > ($9, $10::bigint)"));
+        assert_eq!(order(&oldest), "asc");
+    }
+
+    #[test]
+    fn events_splits_the_list_and_drops_all() {
+        let filter = EventFilter {
+            event: Some(String::from("Sale, Transfer,,All")),
+            ..EventFilter::default()
+        };
+        assert_eq!(filter.events(), vec!["Sale", "Transfer"]);
+    }
+
+    #[test]
+    fn events_are_empty_without_a_filter_or_for_all() {
+        let all = EventFilter {
+            event: Some(String::from("All")),
+            ..EventFilter::default()
+        };
+        assert!(EventFilter::default().events().is_empty());
+        assert!(all.events().is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::price::Price;
+use model::model::stats::StatsDataMarketSnapshot;
+use sqlx::types::chrono::NaiveDate;
+use sqlx::types::Decimal;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// all attributes if none is given, from is inclusive and to exclusive, both are optional
+pub async fn get_market_history(
+    token_address: &String,
+    attribute: Option<&String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataMarketSnapshot>> {
+    return match query_as::<_, StatsDataMarketSnapshotDb>(
+        "select snapshot_date, attribute, floor_price, floor_currency, floor_usd, listings, unique_holders, minted,
+                burnt, trades_24h, volume_24h_eth, volume_24h_usd
+         from market_snapshot
+         where token_address = $1
+           and ($2::varchar is null or attribute = $2)
+           and ($3::date is null or snapshot_date >= $3)
+           and ($4::date is null or snapshot_date < $4)
+         order by attribute, snapshot_date",
+    )
+    .bind(token_address)
+    .bind(attribute)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .timed("get_market_history")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct StatsDataMarketSnapshotDb {
+    pub snapshot_date: NaiveDate,
+    pub attribute: String,
+    pub floor_price: Option<Decimal>,
+    pub floor_currency: Option<String>,
+    pub floor_usd: Option<Decimal>,
+    pub listings: i64,
+    pub unique_holders: i64,
+    pub minted: i64,
+    pub burnt: i64,
+    pub trades_24h: i64,
+    pub volume_24h_eth: Decimal,
+    pub volume_24h_usd: Decimal,
+}
+
+impl From<StatsDataMarketSnapshotDb> for StatsDataMarketSnapshot {
+    fn from(data: StatsDataMarketSnapshotDb) -> Self {
+        Self {
+            snapshot_date: data.snapshot_date,
+            attribute: data.attribute,
+            floor: data
+                .floor_price
+                .zip(data.floor_currency)
+                .map(|(price, currency)| Price {
+                    price: f64::try_from(price).unwrap(),
+                    currency,
+                }),
+            floor_usd: data.floor_usd.map(|floor_usd| Price {
+                price: f64::try_from(floor_usd).unwrap(),
+                currency: String::from("USD"),
+            }),
+            listings: data.listings,
+            unique_holders: data.unique_holders,
+            assets_minted: data.minted,
+            assets_burnt: data.burnt,
+            trades_24h: data.trades_24h,
+            volume_24h: vec![
+                Price {
+                    price: f64::try_from(data.volume_24h_eth).unwrap(),
+                    currency: String::from("ETH"),
+                },
+                Price {
+                    price: f64::try_from(data.volume_24h_usd).unwrap(),
+                    currency: String::from("USD"),
+                },
+            ],
+        }
+    }
+}
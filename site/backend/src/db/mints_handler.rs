@@ -1,3 +1,5 @@
+use crate::utils::image_utils;
+use crate::utils::metrics::TimedQuery;
 use log::error;
 use model::model::mint::{Mint, MintData};
 use sqlx::{query, query_as, FromRow, Pool, Postgres, Row};
@@ -10,6 +12,7 @@ pub async fn get_all_mints_for_token_address(
     let total: i64 = match query("select count(token_id) from mint where token_address=$1")
         .bind(token_address)
         .fetch_one(pool)
+        .timed("get_all_mints_for_token_address")
         .await
     {
         Ok(result) => result.get(0),
@@ -30,6 +33,7 @@ pub async fn get_all_mints_for_token_address(
         .bind(token_address)
         .bind((page - 1) * 50)
         .fetch_all(pool)
+        .timed("get_all_mints_for_token_address")
         .await
     {
         Ok(res) => {
@@ -56,6 +60,7 @@ struct MintDb {
 impl From<MintDb> for Mint {
     fn from(mint: MintDb) -> Self {
         Self {
+            thumbnail_url: image_utils::thumbnail_url(&mint.token_address, mint.token_id),
             token_id: mint.token_id,
             token_address: mint.token_address,
             name: mint.name,
@@ -0,0 +1,161 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::stats::{StatsDataListingDuration, StatsDataReprices, StatsDataTimeToSell};
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// a listing lasts from its creation until the first state it was observed in other than active
+pub async fn get_listing_durations(
+    token_address: &String,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataListingDuration>> {
+    return match query_as::<_, StatsDataListingDurationDb>(
+        "select a.attribute, c.status, count(*) as listings,
+                (avg(extract(epoch from c.closed_on - od.created_on)) / 3600)::float8 as avg_hours,
+                (percentile_cont(0.5) within group (order by extract(epoch from c.closed_on - od.created_on)::float8) / 3600)::float8 as median_hours
+         from (select distinct on (order_id) order_id, status, updated_on as closed_on
+               from order_event
+               where token_address = $1 and status <> 'active'
+               order by order_id, updated_on) c
+                  join order_data od on od.order_id = c.order_id
+                  left join asset a on a.token_address = od.token_address and a.token_id = od.token_id
+         group by a.attribute, c.status
+         order by a.attribute, c.status",
+    )
+    .bind(token_address)
+    .fetch_all(pool)
+    .timed("get_listing_durations")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+// a reprice is an active state with a different price than the previous state of the same seller and token,
+// either of the same order or of a new listing that replaced a cancelled one
+pub async fn get_reprices(
+    token_address: &String,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataReprices>> {
+    return match query_as::<_, StatsDataRepricesDb>(
+        "select a.attribute, count(distinct e.order_id) as listings, count(*) filter (where e.is_reprice) as reprices
+         from (select order_id, token_address, token_id,
+                      status = 'active' and lag(status) over w <> 'filled'
+                          and buy_price <> lag(buy_price) over w as is_reprice
+               from order_event
+               where token_address = $1
+               window w as (partition by token_id, wallet_from order by updated_on, id)) e
+                  left join asset a on a.token_address = e.token_address and a.token_id = e.token_id
+         group by a.attribute
+         order by a.attribute",
+    )
+    .bind(token_address)
+    .fetch_all(pool)
+    .timed("get_reprices")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+// the time to sell starts with the first listing of the seller after their previous sale of the same token,
+// so cancelled and relisted orders count towards it
+pub async fn get_time_to_sell(
+    token_address: &String,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataTimeToSell>> {
+    return match query_as::<_, StatsDataTimeToSellDb>(
+        "with sales as (select order_id, token_id, wallet_from, created_on, updated_on as sold_on,
+                               lag(updated_on) over (partition by token_id, wallet_from order by updated_on) as previous_sale_on
+                        from order_data
+                        where token_address = $1 and status = 'filled')
+         select a.attribute, count(*) as sales,
+                (avg(extract(epoch from t.time_to_sell)) / 3600)::float8 as avg_hours,
+                (percentile_cont(0.5) within group (order by extract(epoch from t.time_to_sell)::float8) / 3600)::float8 as median_hours
+         from (select s.token_id, s.sold_on - min(o.created_on) as time_to_sell
+               from sales s
+                        join order_data o on o.token_address = $1 and o.token_id = s.token_id
+                   and o.wallet_from = s.wallet_from and o.created_on <= s.created_on
+                   and (s.previous_sale_on is null or o.created_on > s.previous_sale_on)
+               group by s.order_id, s.token_id, s.sold_on) t
+                  left join asset a on a.token_address = $1 and a.token_id = t.token_id
+         group by a.attribute
+         order by a.attribute",
+    )
+    .bind(token_address)
+    .fetch_all(pool)
+    .timed("get_time_to_sell")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct StatsDataListingDurationDb {
+    pub attribute: Option<String>,
+    pub status: String,
+    pub listings: i64,
+    pub avg_hours: f64,
+    pub median_hours: f64,
+}
+
+impl From<StatsDataListingDurationDb> for StatsDataListingDuration {
+    fn from(data: StatsDataListingDurationDb) -> Self {
+        Self {
+            attribute: data.attribute,
+            status: data.status,
+            listings: data.listings,
+            avg_hours: data.avg_hours,
+            median_hours: data.median_hours,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct StatsDataRepricesDb {
+    pub attribute: Option<String>,
+    pub listings: i64,
+    pub reprices: i64,
+}
+
+impl From<StatsDataRepricesDb> for StatsDataReprices {
+    fn from(data: StatsDataRepricesDb) -> Self {
+        Self {
+            attribute: data.attribute,
+            listings: data.listings,
+            reprices: data.reprices,
+            avg_reprices_per_listing: data.reprices as f64 / data.listings as f64,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct StatsDataTimeToSellDb {
+    pub attribute: Option<String>,
+    pub sales: i64,
+    pub avg_hours: f64,
+    pub median_hours: f64,
+}
+
+impl From<StatsDataTimeToSellDb> for StatsDataTimeToSell {
+    fn from(data: StatsDataTimeToSellDb) -> Self {
+        Self {
+            attribute: data.attribute,
+            sales: data.sales,
+            avg_hours: data.avg_hours,
+            median_hours: data.median_hours,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::price::Price;
+use model::model::stats::{StatsDataMarketplaceShare, StatsDataRoyalties};
+use sqlx::types::Decimal;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+pub async fn get_royalties(
+    token_address: &String,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataRoyalties>> {
+    return match query_as::<_, StatsDataRoyaltiesDb>(
+        "select count(distinct od.order_id) as orders, f.currency, sum(f.amount) as total,
+                round(sum(f.amount * ch.usd), 2) as total_usd
+         from order_fee f
+                  join order_data od on od.order_id = f.order_id
+                  left join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = f.currency
+         where od.token_address = $1 and od.status = 'filled' and f.fee_type = 'royalty'
+         group by f.currency
+         order by f.currency",
+    )
+    .bind(token_address)
+    .fetch_all(pool)
+    .timed("get_royalties")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+pub async fn get_marketplace_shares(
+    token_address: &String,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<StatsDataMarketplaceShare>> {
+    return match query_as::<_, StatsDataMarketplaceShareDb>(
+        "select f.recipient, count(distinct od.order_id) as orders,
+                (100.0 * count(distinct od.order_id) / sum(count(distinct od.order_id)) over ())::float8 as share
+         from order_data od
+                  left join order_fee f on f.order_id = od.order_id and f.fee_type = 'ecosystem'
+         where od.token_address = $1 and od.status = 'filled'
+         group by f.recipient
+         order by orders desc",
+    )
+    .bind(token_address)
+    .fetch_all(pool)
+    .timed("get_marketplace_shares")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct StatsDataRoyaltiesDb {
+    pub orders: i64,
+    pub currency: String,
+    pub total: Decimal,
+    pub total_usd: Option<Decimal>,
+}
+
+impl From<StatsDataRoyaltiesDb> for StatsDataRoyalties {
+    fn from(data: StatsDataRoyaltiesDb) -> Self {
+        Self {
+            orders: data.orders,
+            total_in_buy_currency: Price {
+                price: f64::try_from(data.total).unwrap(),
+                currency: data.currency,
+            },
+            total_usd: data.total_usd.map(|total_usd| Price {
+                price: f64::try_from(total_usd).unwrap(),
+                currency: String::from("USD"),
+            }),
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct StatsDataMarketplaceShareDb {
+    pub recipient: Option<String>,
+    pub orders: i64,
+    pub share: f64,
+}
+
+impl From<StatsDataMarketplaceShareDb> for StatsDataMarketplaceShare {
+    fn from(data: StatsDataMarketplaceShareDb) -> Self {
+        Self {
+            recipient: data.recipient,
+            orders: data.orders,
+            share: data.share,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::wallet::Portfolio;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// Saves the group of wallets under the first 12 characters of the md5 of the sorted wallets,
+// saving the same group again returns the portfolio that is already there.
+// A portfolio of other wallets under the same id is never replaced, the group is not saved then
+pub async fn save_portfolio(wallets: &[String], pool: &Pool<Postgres>) -> Option<Portfolio> {
+    let mut wallets = wallets.to_vec();
+    wallets.sort();
+    wallets.dedup();
+    // the no-op update returns the stored row on a conflict, also when a concurrent save inserted it
+    return match query_as::<_, PortfolioDb>(
+        "insert into portfolio (id, wallets)
+         values (left(md5(array_to_string($1::varchar[], ',')), 12), $1)
+         on conflict (id) do update set id = excluded.id
+         returning id, wallets",
+    )
+    .bind(&wallets)
+    .fetch_one(pool)
+    .timed("save_portfolio")
+    .await
+    {
+        Ok(result) if result.wallets == wallets => Some(result.into()),
+        Ok(_) => {
+            error!("Error saving data: the portfolio id of {wallets:?} is taken by other wallets");
+            None
+        }
+        Err(e) => {
+            error!("Error saving data: {e}");
+            None
+        }
+    };
+}
+
+pub async fn get_portfolio(id: &String, pool: &Pool<Postgres>) -> Option<Portfolio> {
+    return match query_as::<_, PortfolioDb>("select id, wallets from portfolio where id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .timed("get_portfolio")
+        .await
+    {
+        Ok(result) => result.map(|portfolio| portfolio.into()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct PortfolioDb {
+    id: String,
+    wallets: Vec<String>,
+}
+
+impl From<PortfolioDb> for Portfolio {
+    fn from(portfolio: PortfolioDb) -> Self {
+        Self {
+            id: portfolio.id,
+            wallets: portfolio.wallets,
+        }
+    }
+}
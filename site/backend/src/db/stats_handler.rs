@@ -1,4 +1,5 @@
 use crate::db::db_model::SingleTransactionDb;
+use crate::utils::metrics::TimedQuery;
 use log::error;
 use model::model::price::Price;
 use model::model::stats::{
@@ -46,6 +47,7 @@ async fn fetch_transfers(token_address: &String, pool: &Pool<Postgres>) -> i64 {
     return match query("select count(*) from transfer where token_address=$1")
         .bind(token_address)
         .fetch_one(pool)
+        .timed("fetch_transfers")
         .await
     {
         Ok(result) => result.get(0),
@@ -63,7 +65,7 @@ async fn fetch_total_trades(
     return match query_as::<_, OrderDb>(
         "select count(*), status, buy_currency from order_data where token_address=$1 group by 2, 3 order by 2, 1, 3")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_total_trades").await {
         Ok(result) => {
             let mut total_orders = 0;
             let mut orders_by_status_currency: BTreeMap<String, Vec<StatsDataTotalOrder>> = BTreeMap::new();
@@ -90,7 +92,7 @@ async fn fetch_trades_volume(
         "select total_trades, total_in_buy_currency, buy_currency, total_btc, total_eth, total_usd, total_eur, total_jpy
           from trade_volume_full_mat_view where token_address=$1")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_trades_volume").await {
         Ok(result) => result.into_iter().map(|volume| StatsDataTradesVolume {
             total_trades: volume.total_trades,
             total_in_buy_currency: Price { price: f64::try_from(volume.total_in_buy_currency).unwrap(), currency: volume.buy_currency },
@@ -120,7 +122,7 @@ async fn fetch_most_transferred_tokens(
             where total = (select max(total)
                 from (select count(*) as total from transfer where token_address=$1 group by token_id) as counts);")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_most_transferred_tokens").await {
         Ok(result) => result.into_iter().map(|token| StatsDataMostEventForToken {
             token_id: token.token_id,
             count: token.total,
@@ -146,7 +148,7 @@ async fn fetch_most_traded_tokens(
                         where total = (select max(total)
                                 from (select count(*) as total from order_data where token_address=$1 and status='filled' group by token_id) as counts);")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_most_traded_tokens").await {
         Ok(result) => result.into_iter().map(|token| StatsDataMostEventForToken {
             token_id: token.token_id,
             count: token.total,
@@ -174,6 +176,7 @@ async fn fetch_most_trading_wallets(
     )
     .bind(token_address)
     .fetch_all(pool)
+    .timed("fetch_most_trading_wallets")
     .await
     {
         Ok(result) => result
@@ -199,7 +202,7 @@ async fn fetch_cheapest_and_most_expensive_trades_by_attribute(
             from cheapest_and_most_expensive_trades_by_attribute_mat_view
             where token_address=$1")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_cheapest_and_most_expensive_trades_by_attribute").await {
         Ok(result) => {
             let mut cheapest_and_most_expensive_trades_by_attribute: BTreeMap<String, Vec<SingleTransaction>> = BTreeMap::new();
             for trade in result {
@@ -225,6 +228,7 @@ async fn fetch_minted_and_burnt_assets(
     )
     .bind(token_address)
     .fetch_one(pool)
+    .timed("fetch_minted_and_burnt_assets")
     .await
     {
         Ok(result) => (result.get(0), result.get(1)),
@@ -242,6 +246,7 @@ async fn fetch_total_sales_in_usd(token_address: &String, pool: &Pool<Postgres>)
     )
     .bind(token_address)
     .fetch_one(pool)
+    .timed("fetch_total_sales_in_usd")
     .await
     .unwrap_or_else(|e| {
         error!("Couldn't fetch data! {e}");
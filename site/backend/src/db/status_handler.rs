@@ -0,0 +1,48 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::status::FreshnessData;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// the latest successful loader step per source and collection, failed steps do not count as synced
+pub async fn get_freshness(pool: &Pool<Postgres>) -> Option<Vec<FreshnessData>> {
+    return match query_as::<_, FreshnessDataDb>(
+        "select distinct on (s.step, s.token_address) s.step, s.token_address, c.name as collection_name,
+                s.finished_on as last_synced_on, s.watermark
+         from loader_run_step s
+                  left join collection c on c.address = s.token_address
+         where s.error is null and s.finished_on is not null
+         order by s.step, s.token_address, s.finished_on desc",
+    )
+    .fetch_all(pool)
+    .timed("get_freshness")
+    .await
+    {
+        Ok(result) => Some(result.into_iter().map(|t| t.into()).collect()),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct FreshnessDataDb {
+    pub step: String,
+    pub token_address: Option<String>,
+    pub collection_name: Option<String>,
+    pub last_synced_on: NaiveDateTime,
+    pub watermark: Option<NaiveDateTime>,
+}
+
+impl From<FreshnessDataDb> for FreshnessData {
+    fn from(data: FreshnessDataDb) -> Self {
+        Self {
+            step: data.step,
+            token_address: data.token_address,
+            collection_name: data.collection_name,
+            last_synced_on: data.last_synced_on,
+            watermark: data.watermark,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::db::db_model::SingleTransactionDb;
+use crate::utils::metrics::TimedQuery;
 use log::error;
 use model::model::price::Price;
 use model::model::transaction::SingleTransaction;
@@ -31,6 +32,7 @@ async fn fetch_total_assets(pool: &Pool<Postgres>, token_address: &String) -> (i
     )
     .bind(token_address)
     .fetch_one(pool)
+    .timed("fetch_total_assets")
     .await
     {
         Ok(result) => (result.get(0), result.get(1)),
@@ -85,7 +87,7 @@ async fn fetch_floor_data_by_attribute(
                 from floor_data_mat_by_attribute_view fdmbav join coin_history ch on fdmbav.buy_currency = ch.symbol
                      where fdmbav.token_address=$1 and ch.datestamp = (select max(datestamp) from coin_history where symbol = fdmbav.buy_currency)")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_floor_data_by_attribute").await {
         Ok(result) => {
             let mut floor_data_by_attribute: BTreeMap<String, Vec<VitalsDataFloor>> = BTreeMap::new();
             for data in result {
@@ -107,6 +109,7 @@ async fn fetch_trades_volume(pool: &Pool<Postgres>, token_address: &String) -> V
     )
     .bind(token_address)
     .fetch_one(pool)
+    .timed("fetch_trades_volume")
     .await
     {
         Ok(result) => {
@@ -144,7 +147,7 @@ async fn fetch_last_trades(
                 order by od.updated_on desc
                 limit 3;")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_last_trades").await {
         Ok(result) => result.into_iter().map(|trade| trade.into()).collect(),
         Err(e) => {
             error!("Error fetching data: {e}");
@@ -166,7 +169,7 @@ async fn fetch_minted_burnt_by_attribute(
          group by attribute
          order by attribute")
         .bind(token_address)
-        .fetch_all(pool).await {
+        .fetch_all(pool).timed("fetch_minted_burnt_by_attribute").await {
         Ok(result) => {
             result
                 .into_iter()
@@ -192,6 +195,7 @@ async fn fetch_active_orders_by_attribute(
     )
     .bind(token_address)
     .fetch_all(pool)
+    .timed("fetch_active_orders_by_attribute")
     .await
     {
         Ok(result) => result
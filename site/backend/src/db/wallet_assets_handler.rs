@@ -0,0 +1,136 @@
+use crate::utils::image_utils;
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::asset::AssetContentData;
+use model::model::price::Price;
+use model::model::wallet::{WalletAssetData, WalletAssetSort, WalletAssetsData};
+use sqlx::types::Decimal;
+use sqlx::{query, query_as, FromRow, Pool, Postgres, Row};
+
+// The assets the wallets hold now, 50 per page, optionally of one collection and one attribute.
+// Floors are compared in USD at the latest coin prices, last sales at the coin prices of their day
+pub async fn get_wallet_assets(
+    pool: &Pool<Postgres>,
+    wallets: &Vec<String>,
+    collection: Option<&String>,
+    attribute: Option<&String>,
+    sort: WalletAssetSort,
+    page: i32,
+) -> Option<WalletAssetsData> {
+    let total: i64 = match query(
+        "select count(*) from asset
+         where current_owner = any($1)
+           and ($2::varchar is null or token_address = $2)
+           and ($3::varchar is null or attribute = $3)",
+    )
+    .bind(wallets)
+    .bind(collection)
+    .bind(attribute)
+    .fetch_one(pool)
+    .timed("get_wallet_assets")
+    .await
+    {
+        Ok(result) => result.get(0),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            0
+        }
+    };
+
+    let sort = match sort {
+        WalletAssetSort::Name => "name",
+        WalletAssetSort::Floor => "floor",
+        WalletAssetSort::LastSale => "last_sale",
+    };
+    return match query_as::<_, WalletAssetDb>(
+        "with held as (select token_address, token_id, coalesce(metadata->>'name', '') as name,
+                              coalesce(metadata->>'image_url', '') as image_url, attribute
+                       from asset
+                       where current_owner = any($1)
+                         and ($2::varchar is null or token_address = $2)
+                         and ($3::varchar is null or attribute = $3)),
+              latest_coin as (select distinct on (symbol) symbol, usd
+                              from coin_history
+                              order by symbol, datestamp desc),
+              floor as (select distinct on (f.token_address, f.attribute) f.token_address, f.attribute, f.buy_price,
+                               f.buy_currency, f.buy_price * lc.usd as usd
+                        from floor_data_mat_by_attribute_view f
+                                 join latest_coin lc on lc.symbol = f.buy_currency
+                        where (f.token_address, f.attribute) in (select token_address, attribute from held)
+                        order by f.token_address, f.attribute, usd)
+         select h.token_address, h.token_id, h.name, h.image_url, h.attribute, s.buy_price as sale_price,
+                s.buy_currency as sale_currency, f.buy_price as floor_price, f.buy_currency as floor_currency
+         from held h
+                  left join floor f on f.token_address = h.token_address and f.attribute = h.attribute
+                  left join lateral (select od.buy_price, od.buy_currency, od.buy_price * ch.usd as usd
+                                     from order_data od
+                                              left join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+                                     where od.status = 'filled'
+                                       and od.token_address = h.token_address
+                                       and od.token_id = h.token_id
+                                     order by od.updated_on desc
+                                     limit 1) s on true
+         order by case when $4 = 'floor' then f.usd end desc nulls last,
+                  case when $4 = 'last_sale' then s.usd end desc nulls last,
+                  h.name, h.token_address, h.token_id
+         limit 50 offset $5",
+    )
+    .bind(wallets)
+    .bind(collection)
+    .bind(attribute)
+    .bind(sort)
+    .bind((page - 1) * 50)
+    .fetch_all(pool)
+    .timed("get_wallet_assets")
+    .await
+    {
+        Ok(result) => Some(WalletAssetsData {
+            total,
+            assets: result.into_iter().map(|asset| asset.into()).collect(),
+        }),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+#[derive(FromRow)]
+struct WalletAssetDb {
+    token_address: String,
+    token_id: i32,
+    name: String,
+    image_url: String,
+    attribute: Option<String>,
+    sale_price: Option<Decimal>,
+    sale_currency: Option<String>,
+    floor_price: Option<Decimal>,
+    floor_currency: Option<String>,
+}
+
+fn price(price: Option<Decimal>, currency: Option<String>) -> Option<Price> {
+    match (price, currency) {
+        (Some(price), Some(currency)) => Some(Price {
+            price: f64::try_from(price).unwrap(),
+            currency,
+        }),
+        _ => None,
+    }
+}
+
+impl From<WalletAssetDb> for WalletAssetData {
+    fn from(asset: WalletAssetDb) -> Self {
+        Self {
+            content: AssetContentData {
+                thumbnail_url: image_utils::thumbnail_url(&asset.token_address, asset.token_id),
+                token_id: asset.token_id,
+                token_address: asset.token_address,
+                name: asset.name,
+                image_url: asset.image_url,
+            },
+            attribute: asset.attribute,
+            last_sale: price(asset.sale_price, asset.sale_currency),
+            floor: price(asset.floor_price, asset.floor_currency),
+        }
+    }
+}
@@ -1,46 +1,83 @@
-use crate::db::db_model::TransactionDataDb;
+use crate::db::db_model::{to_event_data, TransactionDataDb, EVENTS_PER_PAGE, MAX_COUNTED_EVENTS};
+use crate::db::event_filter;
+use crate::utils::cursor_utils::Cursor;
+use crate::utils::metrics::TimedQuery;
 use log::error;
-use model::model::transaction::EventData;
-use sqlx::{query, query_as, Pool, Postgres, Row};
+use model::model::transaction::{EventData, EventFilter};
+use sqlx::{query_as, Pool, Postgres};
 
+// The events of the wallets matching the filter, the page after the cursor or the first one.
+// Sent and received events are read apart so each side walks its own index, the counterparty is the other side.
+// transfers between the given wallets are left out, they are no activity of the group
 pub async fn get_wallet_events(
     pool: &Pool<Postgres>,
-    wallet: &String,
-    page: i32,
-    event: &String,
+    wallets: &Vec<String>,
+    cursor: Option<&Cursor>,
+    filter: &EventFilter,
 ) -> Option<EventData> {
-    let total: i64 = match query("select count(*) from events_view where (wallet_from=$1 or wallet_to=$1) and ($2 = 'All' or event = $2)")
-        .bind(wallet)
-        .bind(event)
-        .fetch_one(pool)
-        .await
+    // both sides take the same parameters
+    let (sent, limit) = event_filter::condition(filter, 2, Some("wallet_to"));
+    let (received, _) = event_filter::condition(filter, 2, Some("wallet_from"));
+    let count_sql = format!(
+        "select count(*) from (select id from (select id from event
+                                               where wallet_from = any($1)
+                                                 and not (event like 'Transfer%' and wallet_to = any($1)) and {sent}
+                                               limit ${limit}) sent
+                               union
+                               select id from (select id from event
+                                               where wallet_to = any($1)
+                                                 and not (event like 'Transfer%' and wallet_from = any($1)) and {received}
+                                               limit ${limit}) received
+                               limit ${limit}) e"
+    );
+    let counted: i64 = match event_filter::bind(
+        query_as::<_, (i64,)>(&count_sql).bind(wallets),
+        filter,
+        None,
+    )
+    .bind(MAX_COUNTED_EVENTS + 1)
+    .fetch_one(pool)
+    .timed("get_wallet_events")
+    .await
     {
-        Ok(result) => result.get(0),
+        Ok(result) => result.0,
         Err(e) => {
             error!("Error fetching data: {e}");
             0
         }
     };
 
-    return match query_as::<_, TransactionDataDb>(
-        "select emv.transaction_id, emv.wallet_from, emv.wallet_to, emv.event, emv.timestamp, emv.currency, emv.price, emv.usd_price,
-            emv.token_address, emv.token_id, a.metadata->>'name' as name, a.metadata->>'image_url' as image_url from events_view emv
-         join asset a on a.token_address = emv.token_address and a.token_id = emv.token_id
-         where (emv.wallet_from=$1 or emv.wallet_to=$1) and ($2 = 'All' or emv.event = $2)
-         order by emv.timestamp desc, emv.token_id
-         limit 50 offset $3")
-        .bind(wallet)
-        .bind(event)
-        .bind((page - 1) * 50)
-        .fetch_all(pool)
-        .await
+    let sql = format!(
+        "select e.id as event_id, e.transaction_id, e.wallet_from, e.wallet_to, e.event, e.timestamp, e.currency,
+                e.price, e.usd_price, e.token_address, e.token_id, coalesce(a.metadata->>'name', '') as name,
+                coalesce(a.metadata->>'image_url', '') as image_url
+         from (select * from (select * from event
+                              where wallet_from = any($1)
+                                and not (event like 'Transfer%' and wallet_to = any($1)) and {sent}
+                              order by timestamp {order}, id {order}
+                              limit ${limit}) sent
+               union
+               select * from (select * from event
+                              where wallet_to = any($1)
+                                and not (event like 'Transfer%' and wallet_from = any($1)) and {received}
+                              order by timestamp {order}, id {order}
+                              limit ${limit}) received) e
+                  left join asset a on a.token_address = e.token_address and a.token_id = e.token_id
+         order by e.timestamp {order}, e.id {order}
+         limit ${limit}",
+        order = event_filter::order(filter)
+    );
+    return match event_filter::bind(
+        query_as::<_, TransactionDataDb>(&sql).bind(wallets),
+        filter,
+        cursor,
+    )
+    .bind(EVENTS_PER_PAGE + 1)
+    .fetch_all(pool)
+    .timed("get_wallet_events")
+    .await
     {
-        Ok(result) => Some(
-            EventData {
-                total,
-                transactions: result.into_iter().map( | t| t.into()).collect()
-            }
-        ),
+        Ok(result) => Some(to_event_data(result, counted)),
         Err(e) => {
             error!("Error fetching data: {e}");
             None
@@ -1,16 +1,17 @@
+use crate::utils::metrics::TimedQuery;
 use log::error;
 use model::model::price::Price;
 use model::model::wallet::{TotalPerCollectionData, WalletData, WalletMoneyData};
 use sqlx::types::Decimal;
 use sqlx::{query_as, FromRow, Pool, Postgres};
 
-pub async fn get_wallet(pool: &Pool<Postgres>, wallet: &String) -> Option<WalletData> {
-    let money_data = get_money_data(pool, wallet).await;
-    let minted_per_collection_wallet = get_minted_per_collection_wallet(pool, wallet).await;
-    let owned_per_collection_wallet = get_owned_per_collection_wallet(pool, wallet).await;
+pub async fn get_wallet(pool: &Pool<Postgres>, wallets: &Vec<String>) -> Option<WalletData> {
+    let money_data = get_money_data(pool, wallets).await;
+    let minted_per_collection_wallet = get_minted_per_collection_wallet(pool, wallets).await;
+    let owned_per_collection_wallet = get_owned_per_collection_wallet(pool, wallets).await;
 
     return Some(WalletData {
-        wallet: wallet.clone(),
+        wallets: wallets.clone(),
         minted_per_collection_wallet,
         owned_per_collection_wallet,
         money_data,
@@ -19,7 +20,7 @@ pub async fn get_wallet(pool: &Pool<Postgres>, wallet: &String) -> Option<Wallet
 
 async fn get_minted_per_collection_wallet(
     pool: &Pool<Postgres>,
-    wallet: &String,
+    wallets: &Vec<String>,
 ) -> Vec<TotalPerCollectionData> {
     return match query_as::<_, TotalPerCollectionDb>(
         "SELECT
@@ -32,13 +33,14 @@ async fn get_minted_per_collection_wallet(
                 COUNT(*) AS count_mints
             FROM mint m
                      INNER JOIN collection c ON m.token_address = c.address
-            where m.wallet=$1
+            where m.wallet = any($1)
             GROUP BY c.name
         ) AS mint_counts ON c.name = mint_counts.name
         ORDER BY c.name",
     )
-    .bind(wallet)
+    .bind(wallets)
     .fetch_all(pool)
+    .timed("get_minted_per_collection_wallet")
     .await
     {
         Ok(result) => result.into_iter().map(|value| value.into()).collect(),
@@ -51,7 +53,7 @@ async fn get_minted_per_collection_wallet(
 
 async fn get_owned_per_collection_wallet(
     pool: &Pool<Postgres>,
-    wallet: &String,
+    wallets: &Vec<String>,
 ) -> Vec<TotalPerCollectionData> {
     return match query_as::<_, TotalPerCollectionDb>(
         "SELECT
@@ -64,13 +66,14 @@ async fn get_owned_per_collection_wallet(
                 COUNT(*) AS total
             FROM asset a
                      INNER JOIN collection c ON a.token_address = c.address
-            where a.current_owner=$1
+            where a.current_owner = any($1)
             GROUP BY c.name
         ) AS assets_counts ON c.name = assets_counts.name
         ORDER BY c.name",
     )
-    .bind(wallet)
+    .bind(wallets)
     .fetch_all(pool)
+    .timed("get_owned_per_collection_wallet")
     .await
     {
         Ok(result) => result.into_iter().map(|value| value.into()).collect(),
@@ -81,7 +84,7 @@ async fn get_owned_per_collection_wallet(
     };
 }
 
-async fn get_money_data(pool: &Pool<Postgres>, wallet: &String) -> WalletMoneyData {
+async fn get_money_data(pool: &Pool<Postgres>, wallets: &Vec<String>) -> WalletMoneyData {
     let zero_price = Price {
         price: 0_f64,
         currency: String::from("USD"),
@@ -89,11 +92,12 @@ async fn get_money_data(pool: &Pool<Postgres>, wallet: &String) -> WalletMoneyDa
     let mint_spend_usd = match query_as::<_, TotalDb>(
         "select sum(round((m.price * ch.usd), 2)) as total_usd, count(*) as total from mint m
              join coin_history ch on ch.datestamp = m.minted_on::date and ch.symbol = m.currency
-         where m.wallet=$1
+         where m.wallet = any($1)
          and m.price is not null",
     )
-    .bind(wallet)
+    .bind(wallets)
     .fetch_one(pool)
+    .timed("get_money_data")
     .await
     {
         Ok(result) => result.total_usd.map_or(zero_price.clone(), |value| Price {
@@ -113,12 +117,13 @@ async fn get_money_data(pool: &Pool<Postgres>, wallet: &String) -> WalletMoneyDa
             where status = 'filled'
         )
         select
-            sum(round((od.buy_price * ch.usd), 2)) filter ( where od.wallet_from=$1) as total_sell_usd,
-            sum(round((od.buy_price * ch.usd), 2)) filter ( where od.wallet_to=$1) as total_buy_usd
+            sum(round((od.buy_price * ch.usd), 2)) filter ( where od.wallet_from = any($1)) as total_sell_usd,
+            sum(round((od.buy_price * ch.usd), 2)) filter ( where od.wallet_to = any($1)) as total_buy_usd
         from filtered_order_data od
             join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency")
-        .bind(wallet)
+        .bind(wallets)
         .fetch_one(pool)
+        .timed("get_money_data")
         .await
     {
         Ok(result) => {
@@ -139,9 +144,10 @@ async fn get_money_data(pool: &Pool<Postgres>, wallet: &String) -> WalletMoneyDa
     let total_active = match query_as::<_, TotalDb>(
         "select sum(round((od.buy_price * ch.usd), 2)) as total_usd, count(*) as total
             from order_data od join coin_history ch on ch.datestamp = (select max(datestamp) from coin_history) and ch.symbol = od.buy_currency
-            where od.status='active' and od.wallet_from=$1")
-        .bind(wallet)
+            where od.status='active' and od.wallet_from = any($1)")
+        .bind(wallets)
         .fetch_one(pool)
+        .timed("get_money_data")
         .await
     {
         Ok(result) => result.total_usd.map_or((zero_price.clone(), 0), |value| (Price {
@@ -0,0 +1,99 @@
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::Decimal;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+
+// Every asset that went into or out of a group of wallets, in the order it happened, acquisitions first within the same
+// timestamp. Transfers and trades between the wallets of the group are left out. kind is mint, buy, sale, transfer,
+// deposit or withdrawal, burns are outgoing transfers.
+// usd is the price paid or, for sales, the proceeds after fees, at the coin price of that day
+#[derive(FromRow)]
+pub struct LedgerEntryDb {
+    pub kind: String,
+    pub incoming: bool,
+    pub token_address: String,
+    pub token_id: i32,
+    pub timestamp: NaiveDateTime,
+    pub transaction_id: Option<i32>,
+    pub usd: Option<Decimal>,
+    // the last sale of the same attribute before a transfer or deposit, only fetched with market_basis
+    pub market_usd: Option<Decimal>,
+    pub name: Option<String>,
+    pub attribute: Option<String>,
+}
+
+pub async fn get_ledger(
+    wallets: &Vec<String>,
+    market_basis: bool,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<LedgerEntryDb>> {
+    return match query_as::<_, LedgerEntryDb>(
+        "with ledger as (select 'mint' as kind, true as incoming, m.token_address, m.token_id, m.minted_on as timestamp,
+                                m.transaction_id, m.price * ch.usd as usd
+                         from mint m
+                                  left join coin_history ch on ch.datestamp = m.minted_on::date and ch.symbol = m.currency
+                         where m.wallet = any($1)
+                         union all
+                         select 'buy', true, od.token_address, od.token_id, od.updated_on, od.transaction_id,
+                                od.buy_price * ch.usd
+                         from order_data od
+                                  left join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+                         where od.status = 'filled' and od.wallet_to = any($1)
+                           and not coalesce(od.wallet_from = any($1), false)
+                         union all
+                         select 'sale', false, od.token_address, od.token_id, od.updated_on, od.transaction_id,
+                                (od.buy_price - coalesce(f.amount, 0)) * ch.usd
+                         from order_data od
+                                  left join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+                                  left join lateral (select sum(amount) as amount
+                                                     from order_fee
+                                                     where order_id = od.order_id and currency = od.buy_currency) f on true
+                         where od.status = 'filled' and od.wallet_from = any($1)
+                           and not coalesce(od.wallet_to = any($1), false)
+                         union all
+                         select 'transfer', wallet_to = any($1), token_address, token_id, created_on, transaction_id, null
+                         from transfer
+                         where status = 'success'
+                           and (wallet_to = any($1) or wallet_from = any($1))
+                           and not (wallet_to = any($1) and wallet_from = any($1))
+                         union all
+                         select 'deposit', true, token_address, token_id, created_on, transaction_id, null
+                         from deposit
+                         where status = 'success' and wallet = any($1)
+                         union all
+                         select 'withdrawal', false, token_address, token_id, created_on, transaction_id, null
+                         from withdrawal
+                         where status = 'success' and wallet = any($1))
+         select l.kind, l.incoming, l.token_address, l.token_id, l.timestamp, l.transaction_id, l.usd, mb.usd as market_usd,
+                a.metadata->>'name' as name, a.attribute
+         from ledger l
+                  left join asset a on a.token_address = l.token_address and a.token_id = l.token_id
+                  left join lateral (select od.buy_price * ch.usd as usd
+                                     from order_data od
+                                              join asset s on s.token_address = od.token_address and s.token_id = od.token_id
+                                              join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+                                     where $2
+                                       and l.incoming
+                                       and l.kind in ('transfer', 'deposit')
+                                       and od.status = 'filled'
+                                       and od.token_address = l.token_address
+                                       and s.attribute = a.attribute
+                                       and od.updated_on <= l.timestamp
+                                     order by od.updated_on desc
+                                     limit 1) mb on true
+         order by l.timestamp, l.incoming desc, l.transaction_id",
+    )
+    .bind(wallets)
+    .bind(market_basis)
+    .fetch_all(pool)
+    .timed("get_ledger")
+    .await
+    {
+        Ok(result) => Some(result),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
@@ -0,0 +1,340 @@
+use crate::db::wallet_ledger_handler;
+use crate::db::wallet_ledger_handler::LedgerEntryDb;
+use crate::utils::image_utils;
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::price::Price;
+use model::model::wallet::{
+    PnlMethod, TransferBasis, WalletPnlData, WalletPnlLot, WalletPnlPosition, WalletPnlSale,
+};
+use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::Decimal;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+use std::collections::HashMap;
+
+// Realized gains of the sales of a group of wallets and unrealized gains of what it still holds, against the current floors.
+// Prices without a coin price of their day count as zero
+pub async fn get_wallet_pnl(
+    wallets: &Vec<String>,
+    method: PnlMethod,
+    transfer_basis: TransferBasis,
+    pool: &Pool<Postgres>,
+) -> Option<WalletPnlData> {
+    let ledger =
+        wallet_ledger_handler::get_ledger(wallets, transfer_basis == TransferBasis::Market, pool)
+            .await?;
+    let (sales, lots) = match_lots(ledger, method, transfer_basis);
+
+    let token_addresses: Vec<String> = lots.iter().map(|lot| lot.token_address.clone()).collect();
+    let floors = get_floors_usd(&token_addresses, pool).await?;
+    let (mut cost_basis, mut market_value, mut unrealized_gain) =
+        (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+    let mut positions = vec![];
+    for lot in lots {
+        let floor_usd = lot
+            .attribute
+            .as_ref()
+            .and_then(|attribute| floors.get(&(lot.token_address.clone(), attribute.clone())))
+            .copied();
+        cost_basis += lot.cost_basis_usd;
+        // assets without a floor are left out of the market value, as they could not be sold right now
+        if let Some(floor_usd) = floor_usd {
+            market_value += floor_usd;
+            unrealized_gain += floor_usd - lot.cost_basis_usd;
+        }
+        positions.push(WalletPnlPosition {
+            floor_usd: floor_usd.map(usd),
+            unrealized_gain_usd: floor_usd.map(|floor_usd| usd(floor_usd - lot.cost_basis_usd)),
+            lot: lot.into(),
+        });
+    }
+
+    Some(WalletPnlData {
+        wallets: wallets.clone(),
+        proceeds_usd: usd(sales.iter().map(|sale| sale.proceeds_usd).sum()),
+        realized_gain_usd: usd(sales.iter().map(|sale| sale.gain_usd()).sum()),
+        cost_basis_usd: usd(cost_basis),
+        market_value_usd: usd(market_value),
+        unrealized_gain_usd: usd(unrealized_gain),
+        sales: sales.into_iter().map(|sale| sale.into()).collect(),
+        positions,
+    })
+}
+
+struct Lot {
+    token_address: String,
+    token_id: i32,
+    name: Option<String>,
+    attribute: Option<String>,
+    acquired_by: String,
+    acquired_on: NaiveDateTime,
+    cost_basis_usd: Decimal,
+}
+
+// lot is none if the wallet's acquisition of the sold asset is not known, its cost basis is zero then
+struct Sale {
+    entry: LedgerEntryDb,
+    proceeds_usd: Decimal,
+    lot: Option<Lot>,
+}
+
+impl Sale {
+    fn gain_usd(&self) -> Decimal {
+        self.proceeds_usd
+            - self
+                .lot
+                .as_ref()
+                .map_or(Decimal::ZERO, |lot| lot.cost_basis_usd)
+    }
+}
+
+// Every acquisition opens a lot, every disposal closes the one method picks, only sales realize a gain.
+// Returns the sales and the lots that are still open, oldest first
+fn match_lots(
+    ledger: Vec<LedgerEntryDb>,
+    method: PnlMethod,
+    transfer_basis: TransferBasis,
+) -> (Vec<Sale>, Vec<Lot>) {
+    let mut lots: Vec<Lot> = vec![];
+    let mut sales = vec![];
+    for entry in ledger {
+        if entry.incoming {
+            let cost_basis_usd = match (entry.kind.as_str(), transfer_basis) {
+                ("transfer" | "deposit", TransferBasis::Zero) => None,
+                ("transfer" | "deposit", TransferBasis::Market) => entry.market_usd,
+                _ => entry.usd,
+            };
+            lots.push(Lot {
+                token_address: entry.token_address,
+                token_id: entry.token_id,
+                name: entry.name,
+                attribute: entry.attribute,
+                acquired_by: entry.kind,
+                acquired_on: entry.timestamp,
+                cost_basis_usd: cost_basis_usd.unwrap_or_default(),
+            });
+            continue;
+        }
+
+        let position = lots.iter().position(|lot| {
+            lot.token_address == entry.token_address && lot.token_id == entry.token_id
+        });
+        // with fifo the sold asset takes the cost of the oldest lot of its attribute, the lots in between each take
+        // the cost of the next one, so the lots left stay those of the assets still held.
+        // Assets without an attribute can only be matched by themselves
+        if let (PnlMethod::Fifo, Some(attribute), Some(position)) =
+            (method, &entry.attribute, position)
+        {
+            let same_attribute: Vec<usize> = (0..=position)
+                .filter(|index| {
+                    lots[*index].token_address == entry.token_address
+                        && lots[*index].attribute.as_ref() == Some(attribute)
+                })
+                .collect();
+            for pair in same_attribute.windows(2) {
+                swap_cost(&mut lots, pair[0], pair[1]);
+            }
+        }
+        let lot = position.map(|position| lots.remove(position));
+        if entry.kind != "sale" {
+            continue;
+        }
+        sales.push(Sale {
+            proceeds_usd: entry.usd.unwrap_or_default(),
+            entry,
+            lot,
+        });
+    }
+    (sales, lots)
+}
+
+// swaps what the lots at first and second, first being before second, cost and when and how they were acquired
+fn swap_cost(lots: &mut [Lot], first: usize, second: usize) {
+    let (head, tail) = lots.split_at_mut(second);
+    let (first, second) = (&mut head[first], &mut tail[0]);
+    std::mem::swap(&mut first.cost_basis_usd, &mut second.cost_basis_usd);
+    std::mem::swap(&mut first.acquired_by, &mut second.acquired_by);
+    std::mem::swap(&mut first.acquired_on, &mut second.acquired_on);
+}
+
+// the lowest active listing per collection and attribute, in USD at the latest coin prices
+async fn get_floors_usd(
+    token_addresses: &Vec<String>,
+    pool: &Pool<Postgres>,
+) -> Option<HashMap<(String, String), Decimal>> {
+    return match query_as::<_, FloorUsdDb>(
+        "select f.token_address, f.attribute, min(f.buy_price * ch.usd) as floor_usd
+         from floor_data_mat_by_attribute_view f
+                  join coin_history ch on ch.symbol = f.buy_currency
+             and ch.datestamp = (select max(datestamp) from coin_history where symbol = f.buy_currency)
+         where f.token_address = any($1)
+         group by f.token_address, f.attribute",
+    )
+    .bind(token_addresses)
+    .fetch_all(pool)
+    .timed("get_floors_usd")
+    .await
+    {
+        Ok(result) => Some(
+            result
+                .into_iter()
+                .map(|floor| ((floor.token_address, floor.attribute), floor.floor_usd))
+                .collect(),
+        ),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+fn usd(value: Decimal) -> Price {
+    Price {
+        price: f64::try_from(value.round_dp(2)).unwrap(),
+        currency: String::from("USD"),
+    }
+}
+
+#[derive(FromRow)]
+struct FloorUsdDb {
+    token_address: String,
+    attribute: String,
+    floor_usd: Decimal,
+}
+
+impl From<Lot> for WalletPnlLot {
+    fn from(lot: Lot) -> Self {
+        Self {
+            thumbnail_url: image_utils::thumbnail_url(&lot.token_address, lot.token_id),
+            token_address: lot.token_address,
+            token_id: lot.token_id,
+            name: lot.name,
+            attribute: lot.attribute,
+            acquired_by: lot.acquired_by,
+            acquired_on: lot.acquired_on,
+            cost_basis_usd: usd(lot.cost_basis_usd),
+        }
+    }
+}
+
+impl From<Sale> for WalletPnlSale {
+    fn from(sale: Sale) -> Self {
+        Self {
+            gain_usd: usd(sale.gain_usd()),
+            token_address: sale.entry.token_address,
+            token_id: sale.entry.token_id,
+            name: sale.entry.name,
+            sold_on: sale.entry.timestamp,
+            transaction_id: sale.entry.transaction_id,
+            proceeds_usd: usd(sale.proceeds_usd),
+            lot: sale.lot.map(|lot| lot.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::types::chrono::NaiveDate;
+
+    fn entry(kind: &str, incoming: bool, token_id: i32, day: u32, usd: i64) -> LedgerEntryDb {
+        LedgerEntryDb {
+            kind: kind.to_owned(),
+            incoming,
+            token_address: String::from("0xa"),
+            token_id,
+            timestamp: on(day),
+            transaction_id: None,
+            usd: Some(Decimal::from(usd)),
+            market_usd: None,
+            name: None,
+            attribute: Some(String::from("Rare")),
+        }
+    }
+
+    fn on(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn held(lots: &[Lot]) -> Vec<(i32, Decimal)> {
+        lots.iter()
+            .map(|lot| (lot.token_id, lot.cost_basis_usd))
+            .collect()
+    }
+
+    #[test]
+    fn fifo_sale_of_newer_asset_takes_oldest_cost_and_keeps_older_asset() {
+        let ledger = vec![
+            entry("buy", true, 1, 1, 10),
+            entry("buy", true, 2, 2, 20),
+            entry("sale", false, 2, 3, 50),
+        ];
+        let (sales, lots) = match_lots(ledger, PnlMethod::Fifo, TransferBasis::Zero);
+
+        assert_eq!(sales.len(), 1);
+        let lot = sales[0].lot.as_ref().unwrap();
+        assert_eq!(lot.token_id, 2);
+        assert_eq!(lot.cost_basis_usd, Decimal::from(10));
+        assert_eq!(lot.acquired_on, on(1));
+        assert_eq!(sales[0].gain_usd(), Decimal::from(40));
+        assert_eq!(held(&lots), vec![(1, Decimal::from(20))]);
+        assert_eq!(lots[0].acquired_on, on(2));
+    }
+
+    #[test]
+    fn fifo_sale_of_oldest_asset_takes_its_own_cost() {
+        let ledger = vec![
+            entry("buy", true, 1, 1, 10),
+            entry("buy", true, 2, 2, 20),
+            entry("sale", false, 1, 3, 50),
+        ];
+        let (sales, lots) = match_lots(ledger, PnlMethod::Fifo, TransferBasis::Zero);
+
+        assert_eq!(sales[0].gain_usd(), Decimal::from(40));
+        assert_eq!(held(&lots), vec![(2, Decimal::from(20))]);
+    }
+
+    #[test]
+    fn fifo_costs_stay_in_order_over_several_sales() {
+        let ledger = vec![
+            entry("buy", true, 1, 1, 10),
+            entry("buy", true, 2, 2, 20),
+            entry("buy", true, 3, 3, 30),
+            entry("sale", false, 3, 4, 50),
+            entry("sale", false, 2, 5, 50),
+        ];
+        let (sales, lots) = match_lots(ledger, PnlMethod::Fifo, TransferBasis::Zero);
+
+        let costs: Vec<Decimal> = sales
+            .iter()
+            .map(|sale| sale.lot.as_ref().unwrap().cost_basis_usd)
+            .collect();
+        assert_eq!(costs, vec![Decimal::from(10), Decimal::from(20)]);
+        assert_eq!(held(&lots), vec![(1, Decimal::from(30))]);
+    }
+
+    #[test]
+    fn specific_id_sale_takes_the_cost_of_the_asset_itself() {
+        let ledger = vec![
+            entry("buy", true, 1, 1, 10),
+            entry("buy", true, 2, 2, 20),
+            entry("sale", false, 2, 3, 50),
+        ];
+        let (sales, lots) = match_lots(ledger, PnlMethod::SpecificId, TransferBasis::Zero);
+
+        assert_eq!(sales[0].gain_usd(), Decimal::from(30));
+        assert_eq!(held(&lots), vec![(1, Decimal::from(10))]);
+    }
+
+    #[test]
+    fn sale_of_asset_with_unknown_acquisition_has_no_lot() {
+        let ledger = vec![entry("buy", true, 1, 1, 10), entry("sale", false, 2, 3, 50)];
+        let (sales, lots) = match_lots(ledger, PnlMethod::Fifo, TransferBasis::Zero);
+
+        assert!(sales[0].lot.is_none());
+        assert_eq!(held(&lots), vec![(1, Decimal::from(10))]);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::db::wallet_ledger_handler;
+use crate::db::wallet_ledger_handler::LedgerEntryDb;
+use crate::utils::image_utils;
+use crate::utils::metrics::TimedQuery;
+use log::error;
+use model::model::price::Price;
+use model::model::wallet::{WalletPortfolioAsset, WalletPortfolioData, WalletPortfolioValue};
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime, Utc};
+use sqlx::types::Decimal;
+use sqlx::{query_as, FromRow, Pool, Postgres};
+use std::collections::{BTreeMap, HashMap};
+
+type AttributeKey = (String, String);
+
+pub async fn get_wallet_portfolio(
+    wallets: &Vec<String>,
+    pool: &Pool<Postgres>,
+) -> Option<WalletPortfolioData> {
+    let assets = get_valued_assets(wallets, pool).await?;
+    let ledger = wallet_ledger_handler::get_ledger(wallets, false, pool).await?;
+    let attributes = ledger
+        .iter()
+        .filter_map(|entry| {
+            let attribute = entry.attribute.clone()?;
+            Some((entry.token_address.clone(), attribute))
+        })
+        .collect::<Vec<AttributeKey>>();
+    let daily_prices = get_daily_prices(&attributes, pool).await?;
+    let history = get_history(ledger, &daily_prices);
+
+    let value_usd = assets
+        .iter()
+        .filter_map(|asset| asset.value().map(|v| v.0))
+        .sum();
+    let value_eth = assets
+        .iter()
+        .filter_map(|asset| asset.value().map(|v| v.1))
+        .sum();
+    Some(WalletPortfolioData {
+        wallets: wallets.clone(),
+        value_usd: price(value_usd, "USD"),
+        value_eth: price(value_eth, "ETH"),
+        assets: assets.into_iter().map(|asset| asset.into()).collect(),
+        history,
+    })
+}
+
+// the assets the wallets hold now with the floor and the last sale of their attribute
+async fn get_valued_assets(
+    wallets: &Vec<String>,
+    pool: &Pool<Postgres>,
+) -> Option<Vec<ValuedAssetDb>> {
+    return match query_as::<_, ValuedAssetDb>(
+        "with held as (select token_address, token_id, metadata->>'name' as name, attribute
+                       from asset
+                       where current_owner = any($1)),
+              attributes as (select distinct token_address, attribute from held where attribute is not null),
+              latest_coin as (select distinct on (symbol) symbol, usd, eth
+                              from coin_history
+                              order by symbol, datestamp desc),
+              floor as (select at.token_address, at.attribute, f.usd, f.eth, f.listed_on
+                        from attributes at
+                                 cross join lateral (select od.buy_price * lc.usd as usd, od.buy_price * lc.eth as eth,
+                                                            od.updated_on as listed_on
+                                                     from order_data od
+                                                              join asset s on s.token_address = od.token_address and s.token_id = od.token_id
+                                                              join latest_coin lc on lc.symbol = od.buy_currency
+                                                     where od.status = 'active'
+                                                       and s.token_address = at.token_address
+                                                       and s.attribute = at.attribute
+                                                     order by usd
+                                                     limit 1) f),
+              last_sale as (select at.token_address, at.attribute, s.usd, s.eth, s.sold_on
+                            from attributes at
+                                     cross join lateral (select od.buy_price * ch.usd as usd, od.buy_price * ch.eth as eth,
+                                                                od.updated_on as sold_on
+                                                         from order_data od
+                                                                  join asset s on s.token_address = od.token_address and s.token_id = od.token_id
+                                                                  join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+                                                         where od.status = 'filled'
+                                                           and s.token_address = at.token_address
+                                                           and s.attribute = at.attribute
+                                                         order by od.updated_on desc
+                                                         limit 1) s)
+         select h.token_address, h.token_id, h.name, h.attribute, f.usd as floor_usd, f.eth as floor_eth, f.listed_on,
+                s.usd as sale_usd, s.eth as sale_eth, s.sold_on
+         from held h
+                  left join floor f on f.token_address = h.token_address and f.attribute = h.attribute
+                  left join last_sale s on s.token_address = h.token_address and s.attribute = h.attribute
+         order by h.token_address, h.token_id",
+    )
+    .bind(wallets)
+    .fetch_all(pool)
+    .timed("get_valued_assets")
+    .await
+    {
+        Ok(result) => Some(result),
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+// The USD and ETH price per day of the given attributes, the market snapshot floor where there is one,
+// otherwise the last sale of the day. Days without either are not included
+async fn get_daily_prices(
+    attributes: &[AttributeKey],
+    pool: &Pool<Postgres>,
+) -> Option<HashMap<AttributeKey, BTreeMap<NaiveDate, (Decimal, Decimal)>>> {
+    let (token_addresses, attributes): (Vec<String>, Vec<String>) =
+        attributes.iter().cloned().unzip();
+    return match query_as::<_, DailyPriceDb>(
+        "with attributes as (select distinct * from unnest($1::varchar[], $2::varchar[]) as t(token_address, attribute))
+         select token_address, attribute, day, usd, eth
+         from (select a.token_address, a.attribute, od.updated_on::date as day,
+                      (array_agg(od.buy_price * ch.usd order by od.updated_on desc))[1] as usd,
+                      (array_agg(od.buy_price * ch.eth order by od.updated_on desc))[1] as eth,
+                      1 as priority
+               from order_data od
+                        join asset a on a.token_address = od.token_address and a.token_id = od.token_id
+                        join attributes at on at.token_address = a.token_address and at.attribute = a.attribute
+                        join coin_history ch on ch.datestamp = od.updated_on::date and ch.symbol = od.buy_currency
+               where od.status = 'filled' and ch.usd is not null and ch.eth is not null
+               group by a.token_address, a.attribute, od.updated_on::date
+               union all
+               select ms.token_address, ms.attribute, ms.snapshot_date, ms.floor_usd, ms.floor_price * ch.eth, 2
+               from market_snapshot ms
+                        join attributes at on at.token_address = ms.token_address and at.attribute = ms.attribute
+                        join coin_history ch on ch.datestamp = ms.snapshot_date and ch.symbol = ms.floor_currency
+               where ms.floor_usd is not null and ch.eth is not null) prices
+         order by priority",
+    )
+    .bind(token_addresses)
+    .bind(attributes)
+    .fetch_all(pool)
+    .timed("get_daily_prices")
+    .await
+    {
+        Ok(result) => {
+            let mut daily_prices: HashMap<AttributeKey, BTreeMap<NaiveDate, (Decimal, Decimal)>> =
+                HashMap::new();
+            // snapshots come last and replace the sale of the same day
+            for price in result {
+                daily_prices
+                    .entry((price.token_address, price.attribute))
+                    .or_default()
+                    .insert(price.day, (price.usd, price.eth));
+            }
+            Some(daily_prices)
+        }
+        Err(e) => {
+            error!("Error fetching data: {e}");
+            None
+        }
+    };
+}
+
+// Replays the ledger day by day from the first entry until today, assets without an attribute
+// or without any price until that day count as held but add nothing to the value
+fn get_history(
+    ledger: Vec<LedgerEntryDb>,
+    daily_prices: &HashMap<AttributeKey, BTreeMap<NaiveDate, (Decimal, Decimal)>>,
+) -> Vec<WalletPortfolioValue> {
+    let Some(first_day) = ledger.first().map(|entry| entry.timestamp.date()) else {
+        return vec![];
+    };
+    let today = Utc::now().date_naive();
+    let mut held: HashMap<(String, i32), Option<String>> = HashMap::new();
+    let mut held_per_attribute: HashMap<AttributeKey, i64> = HashMap::new();
+    let mut entries = ledger.into_iter().peekable();
+    let mut history = vec![];
+    for day in first_day.iter_days().take_while(|day| *day <= today) {
+        while let Some(entry) = entries.next_if(|entry| entry.timestamp.date() <= day) {
+            let key = (entry.token_address.clone(), entry.token_id);
+            let change = if entry.incoming {
+                match held.insert(key, entry.attribute.clone()) {
+                    Some(_) => None,
+                    None => entry.attribute.map(|attribute| (attribute, 1)),
+                }
+            } else {
+                held.remove(&key).flatten().map(|attribute| (attribute, -1))
+            };
+            if let Some((attribute, change)) = change {
+                *held_per_attribute
+                    .entry((entry.token_address, attribute))
+                    .or_default() += change;
+            }
+        }
+
+        let (mut value_usd, mut value_eth) = (Decimal::ZERO, Decimal::ZERO);
+        for (attribute, count) in &held_per_attribute {
+            let last_price = daily_prices
+                .get(attribute)
+                .and_then(|prices| prices.range(..=day).next_back());
+            if let Some((_, (usd, eth))) = last_price {
+                value_usd += usd * Decimal::from(*count);
+                value_eth += eth * Decimal::from(*count);
+            }
+        }
+        history.push(WalletPortfolioValue {
+            date: day,
+            assets: held.len() as i64,
+            value_usd: price(value_usd, "USD"),
+            value_eth: price(value_eth, "ETH"),
+        });
+    }
+    history
+}
+
+fn price(value: Decimal, currency: &str) -> Price {
+    let decimals = if currency == "ETH" { 6 } else { 2 };
+    Price {
+        price: f64::try_from(value.round_dp(decimals)).unwrap(),
+        currency: String::from(currency),
+    }
+}
+
+#[derive(FromRow)]
+struct ValuedAssetDb {
+    token_address: String,
+    token_id: i32,
+    name: Option<String>,
+    attribute: Option<String>,
+    floor_usd: Option<Decimal>,
+    floor_eth: Option<Decimal>,
+    listed_on: Option<NaiveDateTime>,
+    sale_usd: Option<Decimal>,
+    sale_eth: Option<Decimal>,
+    sold_on: Option<NaiveDateTime>,
+}
+
+impl ValuedAssetDb {
+    // (usd, eth, valued_by, valued_on) of the floor, or of the last sale if it is more recent than the floor listing
+    fn valuation(&self) -> Option<(Decimal, Decimal, &'static str, NaiveDateTime)> {
+        let floor = match (self.floor_usd, self.floor_eth, self.listed_on) {
+            (Some(usd), Some(eth), Some(on)) => Some((usd, eth, "floor", on)),
+            _ => None,
+        };
+        let sale = match (self.sale_usd, self.sale_eth, self.sold_on) {
+            (Some(usd), Some(eth), Some(on)) => Some((usd, eth, "sale", on)),
+            _ => None,
+        };
+        match (floor, sale) {
+            (Some(floor), Some(sale)) if sale.3 > floor.3 => Some(sale),
+            (Some(floor), _) => Some(floor),
+            (None, sale) => sale,
+        }
+    }
+
+    fn value(&self) -> Option<(Decimal, Decimal)> {
+        self.valuation().map(|(usd, eth, _, _)| (usd, eth))
+    }
+}
+
+impl From<ValuedAssetDb> for WalletPortfolioAsset {
+    fn from(asset: ValuedAssetDb) -> Self {
+        let valuation = asset.valuation();
+        Self {
+            thumbnail_url: image_utils::thumbnail_url(&asset.token_address, asset.token_id),
+            token_address: asset.token_address,
+            token_id: asset.token_id,
+            name: asset.name,
+            attribute: asset.attribute,
+            value_usd: valuation.map(|(usd, _, _, _)| price(usd, "USD")),
+            value_eth: valuation.map(|(_, eth, _, _)| price(eth, "ETH")),
+            valued_by: valuation.map(|(_, _, by, _)| String::from(by)),
+            valued_on: valuation.map(|(_, _, _, on)| on),
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct DailyPriceDb {
+    token_address: String,
+    attribute: String,
+    day: NaiveDate,
+    usd: Decimal,
+    eth: Decimal,
+}
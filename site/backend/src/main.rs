@@ -1,17 +1,29 @@
 use crate::controller::{
-    assets_controller::get_asset, assets_controller::get_events,
-    collection_controller::get_collection, collection_controller::get_collections,
-    mints_controller::get_mints, search_controller::get_search_results,
-    stats_controller::get_stats, vitals_controller::get_vitals, wallet_controller::get_wallet,
-    wallet_controller::get_wallet_events,
+    assets_controller::get_asset, assets_controller::get_events, assets_controller::get_history,
+    collection_controller::get_collection, collection_controller::get_collection_events,
+    collection_controller::get_collections, image_controller::get_image,
+    metrics_controller::get_metrics, mints_controller::get_mints,
+    search_controller::get_search_results, stats_controller::get_candles,
+    stats_controller::get_listing_durations, stats_controller::get_market_history,
+    stats_controller::get_marketplace_shares, stats_controller::get_reprices,
+    stats_controller::get_royalties, stats_controller::get_stats,
+    stats_controller::get_time_to_sell, status_controller::get_freshness,
+    vitals_controller::get_vitals, wallet_controller::get_portfolio, wallet_controller::get_wallet,
+    wallet_controller::get_wallet_assets, wallet_controller::get_wallet_events,
+    wallet_controller::get_wallet_pnl, wallet_controller::get_wallet_portfolio,
+    wallet_controller::save_portfolio,
 };
 use crate::db::db_handler;
-use crate::utils::env_utils;
+use crate::utils::{config, env_utils, metrics};
 use actix_cors::Cors;
+use actix_web::dev::Service;
+use actix_web::http::header;
 use actix_web::middleware::Compress;
 use actix_web::web::Data;
 use actix_web::{App, HttpServer};
 use dotenvy::dotenv;
+use log::error;
+use std::time::Instant;
 
 mod controller;
 mod db;
@@ -23,27 +35,80 @@ async fn main() -> std::io::Result<()> {
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "info"),
     );
     dotenv().expect(".env file should be present");
+    // fails fast instead of on the first asset request
+    config::get();
 
     let pool = db_handler::create_pool().await;
 
+    // /metrics is kept off the public address, it is only served on METRICS_PORT, locally unless METRICS_ENDPOINT is set
+    if let Some(port) = env_utils::as_optional::<u16>("METRICS_PORT") {
+        let pool = pool.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(pool.clone()))
+                .service(get_metrics)
+        })
+        .workers(1)
+        .bind((
+            env_utils::as_optional::<String>("METRICS_ENDPOINT")
+                .unwrap_or_else(|| String::from("127.0.0.1")),
+            port,
+        ))?
+        .run();
+        actix_web::rt::spawn(async move {
+            if let Err(e) = server.await {
+                error!("Metrics server stopped: {e}");
+            }
+        });
+    }
+
     HttpServer::new(move || {
         App::new()
             .app_data(Data::new(pool.clone()))
             .service(get_mints)
             .service(get_asset)
             .service(get_events)
+            .service(get_history)
             .service(get_collections)
+            .service(get_collection_events)
             .service(get_collection)
             .service(get_stats)
+            .service(get_listing_durations)
+            .service(get_reprices)
+            .service(get_time_to_sell)
+            .service(get_royalties)
+            .service(get_marketplace_shares)
+            .service(get_market_history)
+            .service(get_candles)
             .service(get_search_results)
             .service(get_vitals)
             .service(get_wallet)
             .service(get_wallet)
             .service(get_wallet_events)
+            .service(get_wallet_pnl)
+            .service(get_wallet_portfolio)
+            .service(get_wallet_assets)
+            .service(get_portfolio)
+            .service(save_portfolio)
+            .service(get_freshness)
+            .service(get_image)
+            .wrap_fn(|req, srv| {
+                let started = Instant::now();
+                let method = req.method().to_string();
+                let response = srv.call(req);
+                async move {
+                    let response = response.await?;
+                    let route = response.request().match_name().unwrap_or("unmatched");
+                    let status = response.status().as_u16();
+                    metrics::observe_request(route, &method, status, started.elapsed());
+                    Ok(response)
+                }
+            })
             .wrap(
                 Cors::default()
                     .allowed_origin(&env_utils::as_string("ALLOWED_ORIGIN"))
-                    .allowed_methods(vec!["GET"]),
+                    .allowed_methods(vec!["GET", "POST"])
+                    .allowed_header(header::CONTENT_TYPE),
             )
             .wrap(Compress::default())
     })
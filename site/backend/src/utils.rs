@@ -1 +1,5 @@
+pub mod config;
+pub mod cursor_utils;
 pub mod env_utils;
+pub mod image_utils;
+pub mod metrics;
@@ -0,0 +1,68 @@
+use serde::Deserialize;
+use std::env;
+use std::sync::LazyLock;
+
+static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    let path = env::var("CONFIG_FILE").unwrap_or(String::from("config.toml"));
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("config file {path} should be readable: {e}"));
+    let config: Config = toml::from_str(&content)
+        .unwrap_or_else(|e| panic!("config file {path} should be valid: {e}"));
+    // the asset links point at one of them
+    if config.images.thumbnail_sizes.is_empty() {
+        panic!("config file {path} should have at least one thumbnail size");
+    }
+    config
+});
+
+// The collections and images of config.toml, the sources are only read by the loader
+#[derive(Deserialize)]
+pub struct Config {
+    pub collections: Vec<CollectionConfig>,
+    pub images: ImagesConfig,
+}
+
+#[derive(Deserialize)]
+pub struct ImagesConfig {
+    // the content addressed store the loader mirrors the images to
+    pub dir: String,
+    pub thumbnail_sizes: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionConfig {
+    pub address: String,
+    pub kind: CollectionKind,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionKind {
+    Land,
+    D1sk,
+    Illuvitar,
+    Accessories,
+    Blueprints,
+    Events,
+}
+
+pub fn get() -> &'static Config {
+    &CONFIG
+}
+
+pub fn kind_of(token_address: &str) -> Option<CollectionKind> {
+    get()
+        .collections
+        .iter()
+        .find(|collection| collection.address.eq_ignore_ascii_case(token_address))
+        .map(|collection| collection.kind)
+}
+
+// None if no collection of the kind is configured, queries bound to it return no rows then
+pub fn address_of(kind: CollectionKind) -> Option<&'static str> {
+    get()
+        .collections
+        .iter()
+        .find(|collection| collection.kind == kind)
+        .map(|collection| collection.address.as_str())
+}
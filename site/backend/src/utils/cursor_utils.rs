@@ -0,0 +1,39 @@
+use actix_web::error::ErrorBadRequest;
+use sqlx::types::chrono::NaiveDateTime;
+use std::fmt::{Display, Formatter};
+
+// microseconds, the precision of the timestamps in the db, so a cursor points at exactly one event
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f";
+
+// The keyset cursor of an event feed, the timestamp and id of the last event of a page.
+// The next page starts right after it, feeds are ordered newest first
+pub struct Cursor {
+    pub timestamp: NaiveDateTime,
+    pub id: i64,
+}
+
+impl Cursor {
+    pub fn parse(value: &str) -> Option<Cursor> {
+        let (timestamp, id) = value.rsplit_once('_')?;
+        Some(Cursor {
+            timestamp: NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}_{}", self.timestamp.format(TIMESTAMP_FORMAT), self.id)
+    }
+}
+
+// the cursor of a query parameter, none for the first page
+pub fn from_param(value: &Option<String>) -> actix_web::Result<Option<Cursor>> {
+    match value {
+        None => Ok(None),
+        Some(value) => Cursor::parse(value)
+            .map(Some)
+            .ok_or_else(|| ErrorBadRequest(format!("invalid cursor {value}"))),
+    }
+}
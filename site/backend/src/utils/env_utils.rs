@@ -18,3 +18,14 @@ pub fn as_string(key: &str) -> String {
             .as_str(),
     )
 }
+
+pub fn as_optional<T: FromStr>(key: &str) -> Option<T>
+where
+    <T as FromStr>::Err: Debug,
+{
+    env::var(key).ok().map(|value| {
+        value
+            .parse::<T>()
+            .unwrap_or_else(|_| panic!("{} should be valid", key))
+    })
+}
@@ -0,0 +1,42 @@
+use crate::utils::config;
+use std::path::{Path, PathBuf};
+
+// the thumbnail size the asset grids and links are laid out for
+const GRID_THUMBNAIL_SIZE: u32 = 256;
+
+// relative to the backend host, served by image_controller
+pub fn thumbnail_url(token_address: &str, token_id: i32) -> String {
+    format!(
+        "/img/{token_address}/{token_id}?size={}",
+        grid_thumbnail_size()
+    )
+}
+
+// the smallest configured size that still fills a grid cell, or the largest one if all are smaller,
+// so the links keep working whatever sizes the loader writes. The config has at least one size
+fn grid_thumbnail_size() -> u32 {
+    let sizes = &config::get().images.thumbnail_sizes;
+    sizes
+        .iter()
+        .filter(|size| **size >= GRID_THUMBNAIL_SIZE)
+        .min()
+        .or_else(|| sizes.iter().max())
+        .copied()
+        .unwrap_or(GRID_THUMBNAIL_SIZE)
+}
+
+pub fn is_thumbnail_size(size: u32) -> bool {
+    config::get().images.thumbnail_sizes.contains(&size)
+}
+
+// the same layout the loader writes, <dir>/<first 2 chars of the hash>/<hash>/original and <hash>/<size>.webp
+pub fn get_path(content_hash: &str, size: Option<u32>) -> PathBuf {
+    let file = match size {
+        Some(size) => format!("{size}.webp"),
+        None => String::from("original"),
+    };
+    Path::new(&config::get().images.dir)
+        .join(&content_hash[..2])
+        .join(content_hash)
+        .join(file)
+}
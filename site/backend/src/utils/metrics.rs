@@ -0,0 +1,74 @@
+use prometheus::{
+    register_histogram_vec, register_int_gauge_vec, HistogramVec, IntGaugeVec, TextEncoder,
+};
+use sqlx::{Pool, Postgres};
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+static REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "Request latency per route",
+        &["route", "method", "status"]
+    )
+    .unwrap()
+});
+
+static QUERY_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "db_query_duration_seconds",
+        "Duration of sqlx queries, labeled by the handler function running them",
+        &["query"]
+    )
+    .unwrap()
+});
+
+static POOL_CONNECTIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "db_pool_connections",
+        "Connections of the DB pool by state, max is the configured pool size",
+        &["state"]
+    )
+    .unwrap()
+});
+
+// route is the name of the handler function, e.g. get_asset, or "unmatched" for unknown paths
+pub fn observe_request(route: &str, method: &str, status: u16, duration: Duration) {
+    REQUEST_DURATION
+        .with_label_values(&[route, method, status.to_string().as_str()])
+        .observe(duration.as_secs_f64());
+}
+
+// Renders all metrics in the Prometheus text format, the pool gauges are sampled on every scrape
+pub fn gather(pool: &Pool<Postgres>) -> String {
+    let size = pool.size() as i64;
+    let idle = pool.num_idle() as i64;
+    POOL_CONNECTIONS
+        .with_label_values(&["max"])
+        .set(pool.options().get_max_connections() as i64);
+    POOL_CONNECTIONS.with_label_values(&["open"]).set(size);
+    POOL_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+    POOL_CONNECTIONS
+        .with_label_values(&["in_use"])
+        .set(size - idle);
+    TextEncoder::new()
+        .encode_to_string(&prometheus::gather())
+        .unwrap_or_else(|e| format!("# metrics could not be encoded: {e}"))
+}
+
+pub trait TimedQuery: Future + Sized {
+    // Records how long the query future took, failed queries included
+    fn timed(self, query: &'static str) -> impl Future<Output = Self::Output> {
+        async move {
+            let started = Instant::now();
+            let result = self.await;
+            QUERY_DURATION
+                .with_label_values(&[query])
+                .observe(started.elapsed().as_secs_f64());
+            result
+        }
+    }
+}
+
+impl<F: Future> TimedQuery for F {}
@@ -4,6 +4,7 @@ pub mod mint;
 pub mod price;
 pub mod search;
 pub mod stats;
+pub mod status;
 pub mod transaction;
 pub mod vitals;
 pub mod wallet;
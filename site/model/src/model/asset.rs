@@ -1,5 +1,6 @@
 use crate::model::price::Price;
 use crate::model::transaction::SingleTransaction;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -43,6 +44,8 @@ pub struct AssetContentData {
     pub token_address: String,
     pub name: String,
     pub image_url: String,
+    // the mirrored thumbnail, relative to the backend host
+    pub thumbnail_url: String,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Clone)]
@@ -113,3 +116,18 @@ pub struct CommonOrderData {
     pub last_active_orders: Vec<SingleTransaction>,
     pub last_filled_orders: Vec<SingleTransaction>,
 }
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct AssetHistoryData {
+    pub attribute: Option<String>,
+    pub valid_from: NaiveDateTime,
+    pub valid_to: Option<NaiveDateTime>,
+    pub changes: Vec<MetadataChange>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct MetadataChange {
+    pub key: String,
+    pub previous_value: Option<String>,
+    pub value: Option<String>,
+}
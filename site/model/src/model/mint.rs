@@ -12,4 +12,6 @@ pub struct Mint {
     pub token_address: String,
     pub name: String,
     pub image_url: String,
+    // the mirrored thumbnail, relative to the backend host
+    pub thumbnail_url: String,
 }
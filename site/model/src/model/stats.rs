@@ -1,5 +1,6 @@
 use crate::model::price::Price;
 use crate::model::transaction::SingleTransaction;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -49,3 +50,102 @@ pub struct StatsDataMostEventForWallet {
     pub count: i64,
     pub wallet: String,
 }
+
+// durations are in hours, the listings are grouped by the state they ended in
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataListingDuration {
+    pub attribute: Option<String>,
+    pub status: String,
+    pub listings: i64,
+    pub avg_hours: f64,
+    pub median_hours: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataReprices {
+    pub attribute: Option<String>,
+    pub listings: i64,
+    pub reprices: i64,
+    pub avg_reprices_per_listing: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataTimeToSell {
+    pub attribute: Option<String>,
+    pub sales: i64,
+    pub avg_hours: f64,
+    pub median_hours: f64,
+}
+
+// royalties of filled orders only, the ones of other orders were never paid
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataRoyalties {
+    pub orders: i64,
+    pub total_in_buy_currency: Price,
+    pub total_usd: Option<Price>,
+}
+
+// the marketplace of a filled order is the recipient of its ecosystem fee, orders without one have none
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataMarketplaceShare {
+    pub recipient: Option<String>,
+    pub orders: i64,
+    pub share: f64,
+}
+
+// the market state of an attribute as of the last snapshot of a day, the floor is the active listing with the lowest
+// USD price
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataMarketSnapshot {
+    pub snapshot_date: NaiveDate,
+    pub attribute: String,
+    pub floor: Option<Price>,
+    pub floor_usd: Option<Price>,
+    pub listings: i64,
+    pub unique_holders: i64,
+    pub assets_minted: i64,
+    pub assets_burnt: i64,
+    pub trades_24h: i64,
+    pub volume_24h: Vec<Price>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum CandleInterval {
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    Day,
+    #[serde(rename = "1w")]
+    Week,
+}
+
+impl CandleInterval {
+    // the field of date_trunc, weeks start on Monday
+    pub fn unit(&self) -> &'static str {
+        match self {
+            CandleInterval::Hour => "hour",
+            CandleInterval::Day => "day",
+            CandleInterval::Week => "week",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CandleCurrency {
+    Native,
+    Usd,
+}
+
+// the filled orders of an interval, prices and volume are in currency
+#[derive(Serialize, Deserialize)]
+pub struct StatsDataCandle {
+    pub period_start: NaiveDateTime,
+    pub currency: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub trades: i64,
+}
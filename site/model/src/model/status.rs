@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FreshnessData {
+    pub step: String,
+    pub token_address: Option<String>,
+    pub collection_name: Option<String>,
+    pub last_synced_on: NaiveDateTime,
+    pub watermark: Option<NaiveDateTime>,
+}
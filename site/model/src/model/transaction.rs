@@ -1,6 +1,6 @@
 use crate::model::asset::AssetContentData;
 use crate::model::price::Price;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, PartialEq, Clone)]
@@ -14,9 +14,47 @@ pub struct SingleTransaction {
     pub transaction_id: Option<i32>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSort {
+    #[default]
+    Newest,
+    Oldest,
+}
+
+// The filters of an event feed, the query parameters of its endpoint and of the page showing it.
+// event is a comma separated list of event types, prices are in USD and dates are inclusive
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct EventFilter {
+    pub event: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub min_usd: Option<f64>,
+    pub max_usd: Option<f64>,
+    pub currency: Option<String>,
+    pub counterparty: Option<String>,
+    pub sort: Option<EventSort>,
+}
+
+impl EventFilter {
+    // the event types filtered on, none for all of them
+    pub fn events(&self) -> Vec<String> {
+        self.event
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty() && value != "All")
+            .collect()
+    }
+}
+
+// A page of an event feed. Counting stops at a cap, total_capped tells the feed has more events than total.
+// next_cursor is none on the last page
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct EventData {
     pub total: i64,
+    pub total_capped: bool,
+    pub next_cursor: Option<String>,
     pub transactions: Vec<TransactionData>,
 }
 
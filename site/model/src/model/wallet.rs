@@ -1,9 +1,13 @@
+use crate::model::asset::AssetContentData;
 use crate::model::price::Price;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
+// the data of a single wallet or of a group of them taken together, transfers between the wallets of a group
+// are not counted
 #[derive(Serialize, Deserialize)]
 pub struct WalletData {
-    pub wallet: String,
+    pub wallets: Vec<String>,
     pub minted_per_collection_wallet: Vec<TotalPerCollectionData>,
     pub owned_per_collection_wallet: Vec<TotalPerCollectionData>,
     pub money_data: WalletMoneyData,
@@ -23,3 +27,131 @@ pub struct WalletMoneyData {
     pub total_active_usd: Price,
     pub total_active: i64,
 }
+
+// how a disposal is matched with the assets the wallet acquired before, FIFO takes the oldest asset of the same
+// attribute, specific ID the sold asset itself
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PnlMethod {
+    Fifo,
+    SpecificId,
+}
+
+// the cost basis of assets received by a transfer or a deposit, market is the last sale of the same attribute
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferBasis {
+    Zero,
+    Market,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WalletPnlData {
+    pub wallets: Vec<String>,
+    pub proceeds_usd: Price,
+    pub realized_gain_usd: Price,
+    pub cost_basis_usd: Price,
+    pub market_value_usd: Price,
+    pub unrealized_gain_usd: Price,
+    pub sales: Vec<WalletPnlSale>,
+    pub positions: Vec<WalletPnlPosition>,
+}
+
+// an acquired asset, acquired_by is mint, buy, transfer or deposit
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WalletPnlLot {
+    pub token_address: String,
+    pub token_id: i32,
+    pub name: Option<String>,
+    pub attribute: Option<String>,
+    pub thumbnail_url: String,
+    pub acquired_by: String,
+    pub acquired_on: NaiveDateTime,
+    pub cost_basis_usd: Price,
+}
+
+// lot is none if the wallet's acquisition of the sold asset is not known
+#[derive(Serialize, Deserialize)]
+pub struct WalletPnlSale {
+    pub token_address: String,
+    pub token_id: i32,
+    pub name: Option<String>,
+    pub sold_on: NaiveDateTime,
+    pub transaction_id: Option<i32>,
+    pub proceeds_usd: Price,
+    pub lot: Option<WalletPnlLot>,
+    pub gain_usd: Price,
+}
+
+// floor_usd is none if no asset of the attribute is listed
+#[derive(Serialize, Deserialize)]
+pub struct WalletPnlPosition {
+    pub lot: WalletPnlLot,
+    pub floor_usd: Option<Price>,
+    pub unrealized_gain_usd: Option<Price>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WalletPortfolioData {
+    pub wallets: Vec<String>,
+    pub value_usd: Price,
+    pub value_eth: Price,
+    pub assets: Vec<WalletPortfolioAsset>,
+    pub history: Vec<WalletPortfolioValue>,
+}
+
+// an asset is valued at the floor of its attribute, or at the last sale of the attribute if that is more recent
+// than the floor listing, valued_by is floor or sale and none if neither exists
+#[derive(Serialize, Deserialize)]
+pub struct WalletPortfolioAsset {
+    pub token_address: String,
+    pub token_id: i32,
+    pub name: Option<String>,
+    pub attribute: Option<String>,
+    pub thumbnail_url: String,
+    pub value_usd: Option<Price>,
+    pub value_eth: Option<Price>,
+    pub valued_by: Option<String>,
+    pub valued_on: Option<NaiveDateTime>,
+}
+
+// the assets held at the end of a day, valued at the market snapshot floor of their attribute of that day
+// or the last sale of the attribute until then
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct WalletPortfolioValue {
+    pub date: NaiveDate,
+    pub assets: i64,
+    pub value_usd: Price,
+    pub value_eth: Price,
+}
+
+// a saved group of wallets, shared by its id
+#[derive(Serialize, Deserialize)]
+pub struct Portfolio {
+    pub id: String,
+    pub wallets: Vec<String>,
+}
+
+// how the assets held by a wallet are ordered, floor and last sale highest first and in USD
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletAssetSort {
+    Name,
+    Floor,
+    LastSale,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WalletAssetsData {
+    pub total: i64,
+    pub assets: Vec<WalletAssetData>,
+}
+
+// last_sale is the last filled order of the asset itself, floor the cheapest active listing of its attribute
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct WalletAssetData {
+    pub content: AssetContentData,
+    pub attribute: Option<String>,
+    pub last_sale: Option<Price>,
+    pub floor: Option<Price>,
+}
@@ -18,6 +18,10 @@ pub enum Route {
     CollectionStats { token_address: String },
     #[at("/wallet/:wallet")]
     Wallet { wallet: String },
+    #[at("/wallets/:wallets")]
+    Wallets { wallets: String },
+    #[at("/portfolio/:id")]
+    Portfolio { id: String },
     #[at("/about")]
     About,
     #[at("/")]
@@ -31,7 +35,9 @@ pub fn switch(route: Route) -> Html {
     match route {
         Route::Home => html! { <Home />},
         Route::About => html! { <About />},
-        Route::Wallet { wallet } => html! { <Wallet wallet={wallet}/>},
+        Route::Wallet { wallet } => html! { <Wallet query={format!("wallet={wallet}")}/>},
+        Route::Wallets { wallets } => html! { <Wallet query={format!("wallets={wallets}")}/>},
+        Route::Portfolio { id } => html! { <Wallet query={format!("portfolio={id}")}/>},
         Route::Collection { token_address } => html! {<Collection token_address={token_address}/>},
         Route::CollectionStats { token_address } => {
             html! {<CollectionStats token_address={token_address}/>}
@@ -1,5 +1,6 @@
 use crate::utils::env_utils;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 pub async fn fetch_single_api_response<T: DeserializeOwned>(endpoint: &str) -> reqwest::Result<T> {
     let result = reqwest::get(env_utils::get_api_endpoint() + endpoint)
@@ -8,3 +9,17 @@ pub async fn fetch_single_api_response<T: DeserializeOwned>(endpoint: &str) -> r
         .await?;
     return Ok(result);
 }
+
+pub async fn post_single_api_response<B: Serialize, T: DeserializeOwned>(
+    endpoint: &str,
+    body: &B,
+) -> reqwest::Result<T> {
+    let result = reqwest::Client::new()
+        .post(env_utils::get_api_endpoint() + endpoint)
+        .json(body)
+        .send()
+        .await?
+        .json::<T>()
+        .await?;
+    return Ok(result);
+}
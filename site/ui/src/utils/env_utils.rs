@@ -5,3 +5,9 @@ pub fn get_api_endpoint() -> String {
     let backend_url = dotenv!("BACKEND_ENDPOINT");
     String::from(backend_url)
 }
+
+// the mirrored images are served by the backend next to the API
+pub fn get_image_url(path: &String) -> String {
+    let api_endpoint = get_api_endpoint();
+    format!("{}{}", api_endpoint.trim_end_matches("/api"), path)
+}
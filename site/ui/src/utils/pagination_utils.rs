@@ -100,3 +100,64 @@ fn get_single_li_with_arrow(
                                 class={ class }><i class={String::from(arrow_class)}></i></button></li>
     )
 }
+
+// Pagination of a feed read by cursors, cursors holds the cursor of every page before the shown one.
+// Pages only go one newer or one older, the total is shown instead of page numbers
+pub fn cursor_pagination(
+    total: i64,
+    total_capped: bool,
+    cursors: &UseStateHandle<Vec<String>>,
+    next_cursor: &Option<String>,
+) -> Html {
+    let onclick_newer = {
+        let cursors = cursors.clone();
+        Callback::from(move |_| {
+            let mut value = (*cursors).clone();
+            value.pop();
+            cursors.set(value);
+        })
+    };
+    let onclick_older = {
+        let cursors = cursors.clone();
+        let next_cursor = next_cursor.clone();
+        Callback::from(move |_| {
+            if let Some(next_cursor) = &next_cursor {
+                let mut value = (*cursors).clone();
+                value.push(next_cursor.clone());
+                cursors.set(value);
+            }
+        })
+    };
+    let total_label = if total_capped {
+        format!("{total}+ events")
+    } else {
+        format!("{total} events")
+    };
+
+    html! {
+        <div class="row">
+          <div class="d-flex justify-content-center align-items-center mt-3">
+            <nav>
+              <ul class="pagination m-0">
+                { get_cursor_li(onclick_newer, cursors.is_empty(), "fas fa-angle-left") }
+                <li class="page-item"><span class="page-link bg-dark text-white">{ total_label }</span></li>
+                { get_cursor_li(onclick_older, next_cursor.is_none(), "fas fa-angle-right") }
+              </ul>
+            </nav>
+          </div>
+        </div>
+    }
+}
+
+fn get_cursor_li(onclick: Callback<MouseEvent>, disabled: bool, arrow_class: &str) -> Html {
+    let mut class = String::from("page-link text-white shadow-none");
+    if disabled {
+        class.push_str(" bg-secondary disabled");
+    } else {
+        class.push_str(" bg-dark")
+    }
+
+    html!(
+        <li class="page-item"><button {onclick} class={ class }><i class={String::from(arrow_class)}></i></button></li>
+    )
+}
@@ -1,4 +1,4 @@
-use crate::utils::formatting_utils;
+use crate::utils::{env_utils, formatting_utils};
 use crate::view::asset::order_data_view::AssetOrderData;
 use crate::view::asset::{image::AssetImage, title::AssetTitle};
 use model::model::asset::AccessoriesAssetData;
@@ -60,7 +60,7 @@ fn intro(asset: &AccessoriesAssetData) -> Html {
                                 <div class="col-md-3 text-center mb-2 mx-1 p-0 border border-muted rounded bg-dark">
                                    <p class="fs-5 text-white m-0 p-1">{&d1sk.name}</p>
                                    <div class="justify-content-center align-items-end py-2">
-                                     { formatting_utils::get_asset_link(&d1sk.token_address, d1sk.token_id, &d1sk.image_url) }
+                                     { formatting_utils::get_asset_link(&d1sk.token_address, d1sk.token_id, &env_utils::get_image_url(&d1sk.thumbnail_url)) }
                                    </div>
                                 </div>
                             </div>
@@ -73,7 +73,7 @@ fn intro(asset: &AccessoriesAssetData) -> Html {
                               <div class="col-md-3 text-center mb-2 mx-1 p-0 border border-muted rounded bg-dark">
                                   <p class="fs-5 text-white m-0 p-1">{&illuvitar.name}</p>
                                   <div class="justify-content-center align-items-end py-2">
-                                    { formatting_utils::get_asset_link(&illuvitar.token_address, illuvitar.token_id, &illuvitar.image_url) }
+                                    { formatting_utils::get_asset_link(&illuvitar.token_address, illuvitar.token_id, &env_utils::get_image_url(&illuvitar.thumbnail_url)) }
                                   </div>
                               </div>
                           </div>
@@ -1,4 +1,4 @@
-use crate::utils::formatting_utils;
+use crate::utils::{env_utils, formatting_utils};
 use crate::view::asset::order_data_view::AssetOrderData;
 use crate::view::asset::{image::AssetImage, title::AssetTitle};
 use model::model::asset::BlueprintAssetData;
@@ -58,7 +58,7 @@ fn intro(asset: &BlueprintAssetData) -> Html {
                                 <div class="col-md-3 text-center mb-2 mx-1 p-0 border border-muted rounded bg-dark">
                                    <p class="fs-5 text-white m-0 p-1">{&land.name}</p>
                                    <div class="justify-content-center align-items-end py-2">
-                                     { formatting_utils::get_asset_link(&land.token_address, land.token_id, &land.image_url) }
+                                     { formatting_utils::get_asset_link(&land.token_address, land.token_id, &env_utils::get_image_url(&land.thumbnail_url)) }
                                    </div>
                                 </div>
                             </div>
@@ -1,4 +1,4 @@
-use crate::utils::formatting_utils;
+use crate::utils::{env_utils, formatting_utils};
 use crate::view::asset::order_data_view::AssetOrderData;
 use crate::view::asset::{image::AssetImage, title::AssetTitle};
 use model::model::asset::D1skAssetData;
@@ -54,7 +54,7 @@ fn intro(asset: &D1skAssetData) -> Html {
                                     <div class="col-md-3 mb-2 mx-1 p-0 border border-muted rounded bg-dark d-flex flex-column">
                                         <p class="fs-5 text-white m-0 py-2">{&content.name}</p>
                                         <div class="d-flex justify-content-center align-items-end py-2 flex-grow-1">
-                                          { formatting_utils::get_asset_link(&content.token_address, content.token_id, &content.image_url) }
+                                          { formatting_utils::get_asset_link(&content.token_address, content.token_id, &env_utils::get_image_url(&content.thumbnail_url)) }
                                         </div>
                                     </div>
                                 )).collect::<Html>() }
@@ -1,4 +1,4 @@
-use crate::utils::formatting_utils;
+use crate::utils::{env_utils, formatting_utils};
 use crate::view::asset::order_data_view::AssetOrderData;
 use crate::view::asset::{image::AssetImage, title::AssetTitle};
 use model::model::asset::IlluvitarAssetData;
@@ -65,7 +65,7 @@ fn intro(asset: &IlluvitarAssetData) -> Html {
                                 <div class="col-md-3 text-center mb-2 mx-1 p-0 border border-muted rounded bg-dark">
                                    <p class="fs-5 text-white m-0 p-1">{&d1sk.name}</p>
                                    <div class="justify-content-center align-items-end py-2">
-                                     { formatting_utils::get_asset_link(&d1sk.token_address, d1sk.token_id, &d1sk.image_url) }
+                                     { formatting_utils::get_asset_link(&d1sk.token_address, d1sk.token_id, &env_utils::get_image_url(&d1sk.thumbnail_url)) }
                                    </div>
                                 </div>
                             </div>
@@ -78,7 +78,7 @@ fn intro(asset: &IlluvitarAssetData) -> Html {
                                 <div class="col-md-3 text-center mb-2 mx-1 p-0 border border-muted rounded bg-dark">
                                    <p class="fs-5 text-white m-0 p-1">{&origin_illuvitar.name}</p>
                                    <div class="justify-content-center align-items-end py-2">
-                                     { formatting_utils::get_asset_link(&origin_illuvitar.token_address, origin_illuvitar.token_id, &origin_illuvitar.image_url) }
+                                     { formatting_utils::get_asset_link(&origin_illuvitar.token_address, origin_illuvitar.token_id, &env_utils::get_image_url(&origin_illuvitar.thumbnail_url)) }
                                    </div>
                                 </div>
                             </div>
@@ -91,7 +91,7 @@ fn intro(asset: &IlluvitarAssetData) -> Html {
                                 <div class="col-md-3 text-center mb-2 mx-1 p-0 border border-muted rounded bg-dark">
                                    <p class="fs-5 text-white m-0 p-1">{&accessorised_illuvitar.name}</p>
                                    <div class="justify-content-center align-items-end py-2">
-                                     { formatting_utils::get_asset_link(&accessorised_illuvitar.token_address, accessorised_illuvitar.token_id, &accessorised_illuvitar.image_url) }
+                                     { formatting_utils::get_asset_link(&accessorised_illuvitar.token_address, accessorised_illuvitar.token_id, &env_utils::get_image_url(&accessorised_illuvitar.thumbnail_url)) }
                                    </div>
                                 </div>
                             </div>
@@ -106,7 +106,7 @@ fn intro(asset: &IlluvitarAssetData) -> Html {
                                     <div class="col-md-3 mb-2 mx-1 p-0 border border-muted rounded bg-dark d-flex flex-column">
                                         <p class="fs-5 text-white m-0 p-1">{&accessory.name}</p>
                                         <div class="d-flex justify-content-center align-items-end py-2 flex-grow-1">
-                                          { formatting_utils::get_asset_link(&accessory.token_address, accessory.token_id, &accessory.image_url) }
+                                          { formatting_utils::get_asset_link(&accessory.token_address, accessory.token_id, &env_utils::get_image_url(&accessory.thumbnail_url)) }
                                         </div>
                                     </div>
                                   )).collect::<Html>() }
@@ -1,3 +1,4 @@
+mod events;
 mod mint;
 pub mod page;
 mod vitals;
@@ -0,0 +1,18 @@
+use crate::view::common::events_table::EventsTable;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub token_address: String,
+}
+
+#[function_component(CollectionEvents)]
+pub fn collection_events_function_component(props: &Props) -> Html {
+    let url = format!("/collection/events?token_address={}", &props.token_address);
+
+    return html! {
+        <div class="container-fluid p-3 bg-dark">
+            <EventsTable {url} />
+        </div>
+    };
+}
@@ -1,4 +1,4 @@
-use crate::utils::{api_utils, pagination_utils};
+use crate::utils::{api_utils, env_utils, pagination_utils};
 use crate::view::loading::LoadingSpinnerDark;
 use log::error;
 use model::model::mint::MintData;
@@ -59,7 +59,7 @@ pub fn collection_mint_function_component(props: &Props) -> Html {
                                    {
                                        <div class="col-md mb-2 text-center">
                                            <Link<Route> to={Route::Asset {token_address: mint.token_address.to_string(), token_id: mint.token_id} } classes="img-fluid">
-                                               <img src={mint.image_url.clone()} class="img-fluid" width="175" height="175"
+                                               <img src={env_utils::get_image_url(&mint.thumbnail_url)} class="img-fluid" width="175" height="175"
                                                loading="lazy" alt={mint.name.clone()}/>
                                            </Link<Route>>
                                            <p class="text-white">{mint.name.clone()}</p>
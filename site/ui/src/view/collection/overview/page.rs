@@ -1,4 +1,6 @@
-use crate::view::collection::overview::{mint::CollectionMint, vitals::CollectionVitals};
+use crate::view::collection::overview::{
+    events::CollectionEvents, mint::CollectionMint, vitals::CollectionVitals,
+};
 use crate::view::collection::project::CollectionProject;
 use yew::prelude::*;
 
@@ -14,6 +16,7 @@ pub fn collection_function_component(props: &Props) -> Html {
             { html! {<CollectionProject token_address={props.token_address.clone()} />} }
             { html! {<CollectionVitals token_address={props.token_address.clone()} />} }
             { html! {<CollectionMint token_address={props.token_address.clone()} />} }
+            { html! {<CollectionEvents token_address={props.token_address.clone()} />} }
          </selection>
     }
 }
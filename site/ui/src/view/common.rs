@@ -1,4 +1,5 @@
 pub mod events_table;
+pub mod line_chart;
 pub mod no_data;
 pub mod transaction_view;
 pub mod transactions_view;
@@ -1,55 +1,82 @@
-use crate::utils::{api_utils, formatting_utils, pagination_utils};
+use crate::route::Route;
+use crate::utils::{api_utils, env_utils, formatting_utils, pagination_utils};
 use crate::view::loading::LoadingSpinnerDark;
 use log::error;
-use model::model::transaction::EventData;
 use model::model::transaction::TransactionData;
+use model::model::transaction::{EventData, EventFilter, EventSort};
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use yew_router::prelude::*;
+
+const EVENT_TYPES: [&str; 12] = [
+    "Burned",
+    "Burned pending",
+    "Deposit",
+    "Deposit pending",
+    "Mint",
+    "Trade active",
+    "Trade cancelled",
+    "Trade filled",
+    "Transfer",
+    "Transfer pending",
+    "Withdrawal",
+    "Withdrawal pending",
+];
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
     pub url: String,
 }
 
+// The filters are kept in the query of the page, so a filtered feed can be shared and survives a reload
 #[function_component(EventsTable)]
 pub fn events_table_function_component(props: &Props) -> Html {
-    let event_types = vec![
-        String::from("All"),
-        String::from("Burned"),
-        String::from("Deposit"),
-        String::from("Mint"),
-        String::from("Trade active"),
-        String::from("Trade cancelled"),
-        String::from("Trade filled"),
-        String::from("Transfer"),
-        String::from("Withdrawal"),
-    ];
-
-    let url = &props.url;
-    let page = use_state(|| 1);
-    let event_index = use_state(|| 0);
+    let location = use_location();
+    let filter = location
+        .as_ref()
+        .and_then(|location| location.query::<EventFilter>().ok())
+        .unwrap_or_default();
+    // only the filter goes to the API, the page may carry parameters of its own
+    let query = serde_urlencoded::to_string(&filter).unwrap_or_else(|e| {
+        error!("{e}");
+        String::new()
+    });
+    let url = format!("{}&{}", props.url, query);
+
+    return html! {
+        <div class="container-fluid p-3 bg-dark">
+            <div class="col-md-12">
+                <p class="text-white text-center fs-2 mb-4">{"Events"}</p>
+            </div>
+            <EventsFilter {filter} />
+            // a new filter starts over at the first page
+            <EventsFeed key={query} {url} />
+         </div>
+    };
+}
+
+#[derive(Properties, PartialEq)]
+struct FeedProps {
+    url: String,
+}
+
+#[function_component(EventsFeed)]
+fn events_feed_function_component(props: &FeedProps) -> Html {
+    // the cursors of the pages before the shown one, the first page has none
+    let cursors = use_state(Vec::<String>::new);
     let event_data = use_state(|| None);
     {
-        let url = url.clone();
+        let url = props.url.clone();
         let event_data = event_data.clone();
-        let page = page.clone();
-        let page_val = *page;
-        let event_index = event_index.clone();
-        let event_val = *event_index;
-        let event_types = event_types.clone();
-        use_effect_with((page_val, event_val, props.url.clone()), move |_| {
+        let cursor_val = (*cursors).last().cloned();
+        use_effect_with((cursor_val.clone(), props.url.clone()), move |_| {
             event_data.set(None);
+            let mut url = url;
+            if let Some(cursor) = cursor_val {
+                url.push_str(&format!("&cursor={}", cursor));
+            }
             wasm_bindgen_futures::spawn_local(async move {
-                match api_utils::fetch_single_api_response::<EventData>(
-                    format!(
-                        "{}&page={}&event={}",
-                        url,
-                        page_val,
-                        &event_types.get(event_val).unwrap_or(&String::from("All"))
-                    )
-                    .as_str(),
-                )
-                .await
-                {
+                match api_utils::fetch_single_api_response::<EventData>(url.as_str()).await {
                     Ok(fetched_data) => {
                         event_data.set(Some(fetched_data));
                     }
@@ -62,18 +89,14 @@ pub fn events_table_function_component(props: &Props) -> Html {
     }
 
     return html! {
-        <div class="container-fluid p-3 bg-dark">
-            <div class="col-md-12">
-                <p class="text-white text-center fs-2 mb-4">{"Events"}</p>
-            </div>
-            { events_filter(&event_types, event_index, &page) }
+        <>
              if let Some(event_data) = (*event_data).as_ref() {
-                { pagination_utils::pagination(event_data.total, &page) }
+                { pagination_utils::cursor_pagination(event_data.total, event_data.total_capped, &cursors, &event_data.next_cursor) }
                 { render_table(&event_data.transactions) }
              } else {
                 <LoadingSpinnerDark />
              }
-         </div>
+        </>
     };
 }
 
@@ -104,7 +127,7 @@ fn render_table(transaction_data: &Vec<TransactionData>) -> Html {
                             {
                                 transaction_data.iter().map(|transaction| {
                                     html!{
-                                        <tr key={transaction.updated_on.to_string()}>
+                                        <tr key={format!("{}{}{}", transaction.updated_on, transaction.event, transaction.id.unwrap_or_default())}>
                                             if {transaction.id == Option::None} {
                                                 <td scope="row" class="align-middle">
                                                     <span class="d-block mb-1">{ transaction.event.clone() }</span>
@@ -120,7 +143,7 @@ fn render_table(transaction_data: &Vec<TransactionData>) -> Html {
                                             <td class="align-middle">{ formatting_utils::format_wallet_link(&transaction.wallet_to) }</td>
                                             if let Some(asset_content) = &transaction.asset_content {
                                                 <td scope="row" class="align-middle">
-                                                    <span class="d-block mb-1 w-50 mx-auto">{ formatting_utils::get_asset_link(&asset_content.token_address, asset_content.token_id, &asset_content.image_url) }</span>
+                                                    <span class="d-block mb-1 w-50 mx-auto">{ formatting_utils::get_asset_link(&asset_content.token_address, asset_content.token_id, &env_utils::get_image_url(&asset_content.thumbnail_url)) }</span>
                                                     <span class="d-block text-white"> { &asset_content.name }</span>
                                                 </td>
                                             }
@@ -147,42 +170,155 @@ fn render_table(transaction_data: &Vec<TransactionData>) -> Html {
     )
 }
 
-fn events_filter(
-    event_types: &Vec<String>,
-    event_handler: UseStateHandle<usize>,
-    page_handler: &UseStateHandle<i32>,
-) -> Html {
-    let event_type_label = &String::from("Event Type");
-    let empty_label = &String::new();
-    let label = if *event_handler == 0 {
-        event_type_label
-    } else {
-        event_types.get(*event_handler).unwrap_or(empty_label)
+// Replaces the filter in the query of the page, keeping its path
+#[derive(Clone)]
+struct FilterTarget {
+    navigator: Option<Navigator>,
+    route: Option<Route>,
+    filter: EventFilter,
+}
+
+impl FilterTarget {
+    fn set(&self, update: impl FnOnce(&mut EventFilter)) {
+        let mut filter = self.filter.clone();
+        update(&mut filter);
+        if let (Some(navigator), Some(route)) = (&self.navigator, &self.route) {
+            if let Err(e) = navigator.replace_with_query(route, &filter) {
+                error!("{e}")
+            }
+        }
+    }
+
+    // an input applied on enter or when it loses focus, not on every key, empty clears it
+    fn on_input(&self, apply: fn(&mut EventFilter, Option<String>)) -> Callback<Event> {
+        let target = self.clone();
+        Callback::from(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            let value = Some(value.trim().to_owned()).filter(|value| !value.is_empty());
+            target.set(|filter| apply(filter, value));
+        })
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct FilterProps {
+    filter: EventFilter,
+}
+
+#[function_component(EventsFilter)]
+fn events_filter_function_component(props: &FilterProps) -> Html {
+    let target = FilterTarget {
+        navigator: use_navigator(),
+        route: use_route::<Route>(),
+        filter: props.filter.clone(),
+    };
+    let filter = &props.filter;
+    let events = filter.events();
+    let event_label = match events.as_slice() {
+        [] => String::from("Event Type"),
+        [event] => event.clone(),
+        _ => format!("{} event types", events.len()),
+    };
+    let sort = filter.sort.unwrap_or_default();
+    let sort_label = match sort {
+        EventSort::Newest => "Newest first",
+        EventSort::Oldest => "Oldest first",
+    };
+    let format_number =
+        |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+
+    let onclick_clear = {
+        let target = target.clone();
+        Callback::from(move |_| target.set(|filter| *filter = EventFilter::default()))
     };
 
     return html!(
-        <div class="row">
-            <div class="col-md text-center">
+        <div class="row justify-content-center text-center g-2">
+            <div class="col-md-2">
+                <div class="dropdown">
+                  <button class="btn btn-secondary dropdown-toggle w-100" type="button" data-bs-toggle="dropdown"
+                          data-bs-auto-close="outside" aria-expanded="false">
+                    { event_label }
+                  </button>
+                  <ul class="dropdown-menu">
+                  {EVENT_TYPES.into_iter().map(|event| {
+                      let selected = events.iter().any(|value| value == event);
+                      let target = target.clone();
+                      let events = events.clone();
+                      html!(
+                        <li><button class="dropdown-item" onclick={move |_| {
+                              let mut events = events.clone();
+                              if selected {
+                                  events.retain(|value| value != event);
+                              } else {
+                                  events.push(event.to_string());
+                              }
+                              target.set(|filter| filter.event = Some(events.join(",")).filter(|value| !value.is_empty()));
+                          }}>
+                            if selected {
+                                <i class="fas fa-check me-2"></i>
+                            }
+                            { event }
+                        </button></li>
+                      )
+                  }).collect::<Html>()}
+                  </ul>
+                </div>
+            </div>
+            <div class="col-md-2">
+                <input type="date" class="form-control" title="From"
+                       value={filter.from.map(|value| value.to_string()).unwrap_or_default()}
+                       onchange={target.on_input(|filter, value| filter.from = value.and_then(|value| value.parse().ok()))} />
+            </div>
+            <div class="col-md-2">
+                <input type="date" class="form-control" title="To"
+                       value={filter.to.map(|value| value.to_string()).unwrap_or_default()}
+                       onchange={target.on_input(|filter, value| filter.to = value.and_then(|value| value.parse().ok()))} />
+            </div>
+            <div class="col-md-1">
+                <input type="number" min="0" class="form-control" placeholder="Min $"
+                       value={format_number(filter.min_usd)}
+                       onchange={target.on_input(|filter, value| filter.min_usd = value.and_then(|value| value.parse().ok()))} />
+            </div>
+            <div class="col-md-1">
+                <input type="number" min="0" class="form-control" placeholder="Max $"
+                       value={format_number(filter.max_usd)}
+                       onchange={target.on_input(|filter, value| filter.max_usd = value.and_then(|value| value.parse().ok()))} />
+            </div>
+            <div class="col-md-1">
+                <input type="text" class="form-control" placeholder="Currency"
+                       value={filter.currency.clone().unwrap_or_default()}
+                       onchange={target.on_input(|filter, value| filter.currency = value.map(|value| value.to_uppercase()))} />
+            </div>
+            <div class="col-md-3">
+                <input type="text" class="form-control" placeholder="Counterparty wallet"
+                       value={filter.counterparty.clone().unwrap_or_default()}
+                       onchange={target.on_input(|filter, value| filter.counterparty = value.map(|value| value.to_lowercase()))} />
+            </div>
+            <div class="col-md-2">
                 <div class="dropdown">
-                  <button class="btn btn-secondary dropdown-toggle" type="button" data-bs-toggle="dropdown" aria-expanded="false">
-                    { label }
+                  <button class="btn btn-secondary dropdown-toggle w-100" type="button" data-bs-toggle="dropdown" aria-expanded="false">
+                    { sort_label }
                   </button>
                   <ul class="dropdown-menu">
-                  {event_types.iter().enumerate().map(|(index, event)| {
-                      let event_handler = event_handler.clone();
-                      let page_handler = page_handler.clone();
+                  {[(EventSort::Newest, "Newest first"), (EventSort::Oldest, "Oldest first")].into_iter().map(|(value, label)| {
+                      let target = target.clone();
                       html!(
                         <li><button class="dropdown-item" onclick={move |_| {
-                              if *event_handler != index {
-                                  event_handler.set(index);
-                                  page_handler.set(1);
+                              if sort != value {
+                                  target.set(|filter| filter.sort = Some(value).filter(|value| *value != EventSort::default()));
                               }
-                          }}>{ event }</button></li>
+                          }}>{ label }</button></li>
                       )
                   }).collect::<Html>()}
                   </ul>
                 </div>
             </div>
+            if *filter != EventFilter::default() {
+                <div class="col-md-1">
+                    <button class="btn btn-outline-secondary w-100" onclick={onclick_clear}>{"Clear"}</button>
+                </div>
+            }
         </div>
     );
 }
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+use yew::prelude::*;
+
+const WIDTH: f64 = 1000.0;
+const HEIGHT: f64 = 300.0;
+const PADDING: f64 = 10.0;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub points: Vec<(NaiveDate, f64)>,
+    pub label: String,
+}
+
+// A plain SVG line over the dates of the points, scaled from zero to the highest value
+#[function_component(LineChart)]
+pub fn line_chart_function_component(props: &Props) -> Html {
+    let (Some(first), Some(last)) = (props.points.first(), props.points.last()) else {
+        return html!();
+    };
+    let max_value = props
+        .points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(0.0, f64::max);
+    let step = WIDTH / (props.points.len().max(2) - 1) as f64;
+    let y = |value: f64| {
+        if max_value == 0.0 {
+            HEIGHT - PADDING
+        } else {
+            HEIGHT - PADDING - value / max_value * (HEIGHT - 2.0 * PADDING)
+        }
+    };
+    let line = props
+        .points
+        .iter()
+        .enumerate()
+        .map(|(index, (_, value))| format!("{:.1},{:.1}", index as f64 * step, y(*value)))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    html! {
+        <div class="text-white">
+            <div class="d-flex justify-content-between text-muted small">
+                <span>{ &props.label }</span>
+                <span>{ format!("max {:.2}", max_value) }</span>
+            </div>
+            <svg viewBox={format!("0 0 {WIDTH} {HEIGHT}")} preserveAspectRatio="none" class="w-100" style="height: 300px;">
+                <polyline points={line} fill="none" stroke="#ab54f4" stroke-width="2" vector-effect="non-scaling-stroke" />
+            </svg>
+            <div class="d-flex justify-content-between text-muted small">
+                <span>{ first.0.to_string() }</span>
+                <span>{ last.0.to_string() }</span>
+            </div>
+        </div>
+    }
+}
@@ -1,12 +1,39 @@
+use crate::utils::api_utils;
 use chrono::Datelike;
 use chrono::Utc;
+use log::error;
+use model::model::status::FreshnessData;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
 use crate::route::Route;
 
+// the loader step shown per collection in the footer
+const FRESHNESS_STEP: &str = "orders";
+
 #[function_component(Footer)]
 pub fn footer() -> Html {
+    let freshness = use_state(|| vec![]);
+    {
+        let freshness = freshness.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_utils::fetch_single_api_response::<Vec<FreshnessData>>(
+                    "/status/freshness",
+                )
+                .await
+                {
+                    Ok(fetched_data) => {
+                        freshness.set(fetched_data);
+                    }
+                    Err(e) => {
+                        error!("{e}")
+                    }
+                }
+            });
+        });
+    }
+
     html! {
         <footer class="bg-dark text-muted border-top mt-auto">
           <div class="container py-2">
@@ -23,6 +50,19 @@ pub fn footer() -> Html {
                 </Link<Route>>
               </div>
             </div>
+            <div class="row">
+              <div class="d-flex flex-wrap justify-content-center justify-content-md-start">
+                {
+                    freshness.iter()
+                        .filter(|data| data.step == FRESHNESS_STEP)
+                        .filter_map(|data| data.collection_name.as_ref().map(|name| (name, data)))
+                        .map(|(name, data)| html! {
+                            <small class="me-3">{format!("{}: orders last synced {}", name, get_time_ago(data))}</small>
+                        })
+                        .collect::<Html>()
+                }
+              </div>
+            </div>
           </div>
         </footer>
     }
@@ -33,3 +73,13 @@ fn get_current_year() -> i32 {
     let year = now.year();
     return year;
 }
+
+fn get_time_ago(data: &FreshnessData) -> String {
+    let minutes = (Utc::now().naive_utc() - data.last_synced_on).num_minutes();
+    return match minutes {
+        m if m < 1 => String::from("just now"),
+        m if m < 60 => format!("{} min ago", m),
+        m if m < 24 * 60 => format!("{} h ago", m / 60),
+        m => format!("{} d ago", m / (24 * 60)),
+    };
+}
@@ -1,4 +1,4 @@
-use crate::utils::{api_utils, formatting_utils};
+use crate::utils::{api_utils, env_utils, formatting_utils};
 use crate::view::loading::LoadingSpinnerGrayNoVh;
 use gloo_timers::callback::Timeout;
 use log::error;
@@ -112,7 +112,7 @@ pub fn search() -> Html {
                     html!(
                         <li>
                             <div class="align-items-center text-center text-white m-3" {onclick}>
-                                {formatting_utils::get_asset_link(&asset.token_address, asset.token_id, &asset.image_url)}
+                                {formatting_utils::get_asset_link(&asset.token_address, asset.token_id, &env_utils::get_image_url(&asset.thumbnail_url))}
                                 <p class="m-0">{asset.name.clone()}</p>
                             </div>
                         </li>
@@ -1,3 +1,7 @@
 mod events;
+mod group_view;
+mod holdings_view;
 pub mod page;
+mod pnl_view;
+mod portfolio_view;
 mod wallet_data_view;
@@ -3,12 +3,12 @@ use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub wallet: String,
+    pub query: String,
 }
 
 #[function_component(WalletEvents)]
 pub fn wallet_events_function_component(props: &Props) -> Html {
-    let url = format!("/wallet/events?wallet={}", props.wallet);
+    let url = format!("/wallet/events?{}", props.query);
 
     return html! {
         <div class="container-fluid p-3 bg-dark">
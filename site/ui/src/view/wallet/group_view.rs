@@ -0,0 +1,87 @@
+use crate::route::Route;
+use crate::utils::{api_utils, formatting_utils};
+use log::error;
+use model::model::wallet::Portfolio;
+use serde::Serialize;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub wallets: Vec<String>,
+    // whether the wallets are shown by the id of a saved portfolio
+    pub saved: bool,
+}
+
+#[derive(Serialize)]
+struct SavePortfolio {
+    wallets: Vec<String>,
+}
+
+// The wallets shown together, another wallet can be added to them and a group saved as a portfolio to share its link
+#[function_component(WalletGroupView)]
+pub fn wallet_group_view_function_component(props: &Props) -> Html {
+    let navigator = use_navigator().unwrap();
+    let input_ref = use_node_ref();
+
+    let onsubmit = {
+        let navigator = navigator.clone();
+        let input_ref = input_ref.clone();
+        let wallets = props.wallets.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                let wallet = input.value().trim().to_lowercase();
+                if !wallet.is_empty() && !wallets.contains(&wallet) {
+                    let mut wallets = wallets.clone();
+                    wallets.push(wallet);
+                    input.set_value("");
+                    navigator.push(&Route::Wallets {
+                        wallets: wallets.join(","),
+                    });
+                }
+            }
+        })
+    };
+
+    let onsave = {
+        let wallets = props.wallets.clone();
+        Callback::from(move |_| {
+            let navigator = navigator.clone();
+            let body = SavePortfolio {
+                wallets: wallets.clone(),
+            };
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_utils::post_single_api_response::<_, Portfolio>("/portfolio", &body).await
+                {
+                    Ok(portfolio) => navigator.push(&Route::Portfolio { id: portfolio.id }),
+                    Err(e) => {
+                        error!("{e}")
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="row align-items-center">
+            <div class="col-md-8 my-2">
+                if let [wallet] = props.wallets.as_slice() {
+                    <p class="text-white text-break fs-2 m-0">{ wallet.clone() }</p>
+                } else {
+                    { props.wallets.iter().map(formatting_utils::format_wallet_link).collect::<Html>() }
+                }
+            </div>
+            <div class="col-md-4 my-2">
+                <form class="input-group" {onsubmit}>
+                    <input ref={input_ref} type="text" class="form-control" placeholder="Add a wallet" />
+                    <button type="submit" class="btn btn-secondary">{"Add"}</button>
+                    if props.wallets.len() > 1 && !props.saved {
+                        <button type="button" class="btn btn-primary" onclick={onsave}>{"Save"}</button>
+                    }
+                </form>
+            </div>
+        </div>
+    }
+}
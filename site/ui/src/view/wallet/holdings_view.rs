@@ -0,0 +1,220 @@
+use crate::utils::{api_utils, env_utils, formatting_utils, pagination_utils};
+use crate::view::loading::LoadingSpinnerGrayNoVh;
+use log::error;
+use model::model::collection::CollectionData;
+use model::model::wallet::{WalletAssetData, WalletAssetSort, WalletAssetsData};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    // the wallet query of the API, a wallet, a list of wallets or a portfolio
+    pub query: String,
+}
+
+#[function_component(WalletHoldingsView)]
+pub fn wallet_holdings_view_function_component(props: &Props) -> Html {
+    let collections = use_state(|| vec![]);
+    let collection = use_state(|| None::<String>);
+    let attribute = use_state(|| None::<String>);
+    let sort = use_state(|| WalletAssetSort::Name);
+    let page = use_state(|| 1);
+    let assets_data = use_state(|| None);
+    {
+        let collections = collections.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_utils::fetch_single_api_response::<Vec<CollectionData>>(
+                    "/collection/collections",
+                )
+                .await
+                {
+                    Ok(fetched_collections) => {
+                        collections.set(fetched_collections);
+                    }
+                    Err(e) => {
+                        error!("{e}")
+                    }
+                }
+            });
+        });
+    }
+    {
+        let query = props.query.clone();
+        let assets_data = assets_data.clone();
+        let collection_val = (*collection).clone();
+        let attribute_val = (*attribute).clone();
+        let sort_val = *sort;
+        let page_val = *page;
+        use_effect_with(
+            (
+                props.query.clone(),
+                collection_val.clone(),
+                attribute_val.clone(),
+                sort_val,
+                page_val,
+            ),
+            move |_| {
+                assets_data.set(None);
+                let mut url = format!(
+                    "/wallet/assets?{}&sort={}&page={}",
+                    query,
+                    sort_param(sort_val),
+                    page_val
+                );
+                if let Some(collection) = collection_val {
+                    url.push_str(&format!("&collection={}", collection));
+                }
+                if let Some(attribute) = attribute_val {
+                    url.push_str(&format!("&attribute={}", attribute));
+                }
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_utils::fetch_single_api_response::<WalletAssetsData>(url.as_str())
+                        .await
+                    {
+                        Ok(fetched_data) => {
+                            assets_data.set(Some(fetched_data));
+                        }
+                        Err(e) => {
+                            error!("{e}")
+                        }
+                    }
+                });
+            },
+        );
+    }
+
+    html! {
+        <div class="container-fluid p-3 bg-gray">
+            <div class="container animate__animated animate__fadeIn animate__faster">
+                <p class="text-white text-center fs-2 mb-4">{"Holdings"}</p>
+                { holdings_filter(&collections, &collection, &attribute, &sort, &page) }
+                if let Some(data) = (*assets_data).as_ref() {
+                    { pagination_utils::pagination(data.total, &page) }
+                    { holdings_grid(&data.assets) }
+                } else {
+                    <LoadingSpinnerGrayNoVh />
+                }
+            </div>
+        </div>
+    }
+}
+
+fn sort_param(sort: WalletAssetSort) -> &'static str {
+    match sort {
+        WalletAssetSort::Name => "name",
+        WalletAssetSort::Floor => "floor",
+        WalletAssetSort::LastSale => "last_sale",
+    }
+}
+
+fn holdings_filter(
+    collections: &UseStateHandle<Vec<CollectionData>>,
+    collection: &UseStateHandle<Option<String>>,
+    attribute: &UseStateHandle<Option<String>>,
+    sort: &UseStateHandle<WalletAssetSort>,
+    page: &UseStateHandle<i32>,
+) -> Html {
+    let collection_label = (**collection)
+        .as_ref()
+        .and_then(|address| collections.iter().find(|value| &value.address == address))
+        .map_or(String::from("All collections"), |value| value.name.clone());
+    let mut collection_options = vec![(None, String::from("All collections"))];
+    collection_options.extend(
+        collections
+            .iter()
+            .map(|value| (Some(value.address.clone()), value.name.clone())),
+    );
+    let sorts = [
+        (WalletAssetSort::Name, String::from("Name")),
+        (WalletAssetSort::Floor, String::from("Floor")),
+        (WalletAssetSort::LastSale, String::from("Last sale")),
+    ];
+    let sort_label = sorts
+        .iter()
+        .find(|(value, _)| value == &**sort)
+        .map_or(String::new(), |(_, label)| label.clone());
+
+    // the attribute is applied on enter or when the input loses focus, not on every key
+    let onchange_attribute = {
+        let attribute = attribute.clone();
+        let page = page.clone();
+        Callback::from(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            attribute.set(Some(value.trim().to_owned()).filter(|value| !value.is_empty()));
+            page.set(1);
+        })
+    };
+
+    html! {
+        <div class="row justify-content-center text-center">
+            <div class="col-md-3 my-1">
+                { dropdown(&collection_label, &collection_options, collection, page) }
+            </div>
+            <div class="col-md-3 my-1">
+                <input type="text" class="form-control" placeholder="Attribute" onchange={onchange_attribute} />
+            </div>
+            <div class="col-md-3 my-1">
+                { dropdown(&sort_label, &sorts, sort, page) }
+            </div>
+        </div>
+    }
+}
+
+fn dropdown<T: Clone + PartialEq + 'static>(
+    label: &String,
+    options: &[(T, String)],
+    handler: &UseStateHandle<T>,
+    page_handler: &UseStateHandle<i32>,
+) -> Html {
+    html! {
+        <div class="dropdown">
+            <button class="btn btn-secondary dropdown-toggle" type="button" data-bs-toggle="dropdown" aria-expanded="false">
+                { label }
+            </button>
+            <ul class="dropdown-menu">
+            { options.iter().map(|(value, option_label)| {
+                let value = value.clone();
+                let handler = handler.clone();
+                let page_handler = page_handler.clone();
+                html!(
+                    <li><button class="dropdown-item" onclick={move |_| {
+                        if *handler != value {
+                            handler.set(value.clone());
+                            page_handler.set(1);
+                        }
+                    }}>{ option_label }</button></li>
+                )
+            }).collect::<Html>() }
+            </ul>
+        </div>
+    }
+}
+
+fn holdings_grid(assets: &Vec<WalletAssetData>) -> Html {
+    if assets.is_empty() {
+        return html! {
+            <p class="text-muted text-center">{"None"}</p>
+        };
+    }
+
+    html! {
+        <div class="row row-cols-2 row-cols-md-4 row-cols-lg-5 g-3 text-center">
+        { assets.iter().map(|asset| html! {
+            <div class="col" key={format!("{}{}", asset.content.token_address, asset.content.token_id)}>
+                <div class="border rounded bg-dark p-2 h-100">
+                    { formatting_utils::get_asset_link(&asset.content.token_address, asset.content.token_id, &env_utils::get_image_url(&asset.content.thumbnail_url)) }
+                    <span class="d-block text-white mt-2">{ asset.content.name.clone() }</span>
+                    <span class="d-block text-muted">{ asset.attribute.clone().unwrap_or_default() }</span>
+                    if let Some(floor) = &asset.floor {
+                        <span class="d-block text-white">{"Floor "}{ formatting_utils::format_price(floor) }</span>
+                    }
+                    if let Some(last_sale) = &asset.last_sale {
+                        <span class="d-block text-muted">{"Last sale "}{ formatting_utils::format_price(last_sale) }</span>
+                    }
+                </div>
+            </div>
+        }).collect::<Html>() }
+        </div>
+    }
+}
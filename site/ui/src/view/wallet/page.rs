@@ -1,18 +1,65 @@
 use crate::view::wallet::events::WalletEvents;
+use crate::view::wallet::holdings_view::WalletHoldingsView;
+use crate::view::wallet::pnl_view::WalletPnlView;
+use crate::view::wallet::portfolio_view::WalletPortfolioView;
 use crate::view::wallet::wallet_data_view::WalletDataView;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub wallet: String,
+    pub query: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tab {
+    Overview,
+    Holdings,
+    Pnl,
 }
 
 #[function_component(Wallet)]
 pub fn wallet_function_component(props: &Props) -> Html {
+    let tab = use_state(|| Tab::Overview);
+
     html! {
          <selection>
-            { html! {<WalletDataView wallet={props.wallet.clone()} />} }
-            { html! {<WalletEvents wallet={props.wallet.clone()} />} }
+            { wallet_tabs(&tab) }
+            if *tab == Tab::Overview {
+                { html! {<WalletDataView query={props.query.clone()} />} }
+                { html! {<WalletPortfolioView query={props.query.clone()} />} }
+                { html! {<WalletEvents query={props.query.clone()} />} }
+            } else if *tab == Tab::Holdings {
+                { html! {<WalletHoldingsView query={props.query.clone()} />} }
+            } else {
+                { html! {<WalletPnlView query={props.query.clone()} />} }
+            }
          </selection>
     }
 }
+
+fn wallet_tabs(tab: &UseStateHandle<Tab>) -> Html {
+    let tabs = [
+        (Tab::Overview, "Overview"),
+        (Tab::Holdings, "Holdings"),
+        (Tab::Pnl, "P&L"),
+    ];
+
+    html! {
+        <div class="container-fluid pt-3 bg-gray">
+            <div class="container">
+                <ul class="nav nav-tabs border-secondary">
+                { tabs.iter().map(|(value, label)| {
+                    let value = *value;
+                    let tab = tab.clone();
+                    let classes = if *tab == value { "nav-link active" } else { "nav-link text-white" };
+                    html!(
+                        <li class="nav-item">
+                            <button class={classes} onclick={move |_| tab.set(value)}>{ *label }</button>
+                        </li>
+                    )
+                }).collect::<Html>() }
+                </ul>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,230 @@
+use crate::utils::{api_utils, env_utils, formatting_utils};
+use crate::view::loading::LoadingSpinnerGray;
+use log::error;
+use model::model::price::Price;
+use model::model::wallet::{
+    PnlMethod, TransferBasis, WalletPnlData, WalletPnlLot, WalletPnlPosition, WalletPnlSale,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub query: String,
+}
+
+#[function_component(WalletPnlView)]
+pub fn wallet_pnl_view_function_component(props: &Props) -> Html {
+    let method = use_state(|| PnlMethod::Fifo);
+    let transfer_basis = use_state(|| TransferBasis::Zero);
+    let pnl_data = use_state(|| None);
+    {
+        let query = props.query.clone();
+        let pnl_data = pnl_data.clone();
+        let method_val = *method;
+        let transfer_basis_val = *transfer_basis;
+        use_effect_with(
+            (props.query.clone(), method_val, transfer_basis_val),
+            move |_| {
+                pnl_data.set(None);
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_utils::fetch_single_api_response::<WalletPnlData>(
+                        format!(
+                            "/wallet/pnl?{}&method={}&transfer_basis={}",
+                            query,
+                            method_param(method_val),
+                            transfer_basis_param(transfer_basis_val)
+                        )
+                        .as_str(),
+                    )
+                    .await
+                    {
+                        Ok(fetched_data) => {
+                            pnl_data.set(Some(fetched_data));
+                        }
+                        Err(e) => {
+                            error!("{e}")
+                        }
+                    }
+                });
+            },
+        );
+    }
+
+    html! {
+        <div class="container-fluid p-3 bg-gray">
+            <div class="container animate__animated animate__fadeIn animate__faster">
+                <div class="row justify-content-center text-center mb-3">
+                    <div class="col-auto">
+                        { option_buttons(&method, &[(PnlMethod::Fifo, "FIFO"), (PnlMethod::SpecificId, "Specific ID")]) }
+                    </div>
+                    <div class="col-auto">
+                        { option_buttons(&transfer_basis, &[(TransferBasis::Zero, "Transfers at zero"), (TransferBasis::Market, "Transfers at market")]) }
+                    </div>
+                </div>
+                if let Some(data) = (*pnl_data).as_ref() {
+                    { pnl_summary(data) }
+                    { positions_table(&data.positions) }
+                    { sales_table(&data.sales) }
+                } else {
+                    <LoadingSpinnerGray />
+                }
+            </div>
+        </div>
+    }
+}
+
+fn method_param(method: PnlMethod) -> &'static str {
+    match method {
+        PnlMethod::Fifo => "fifo",
+        PnlMethod::SpecificId => "specific_id",
+    }
+}
+
+fn transfer_basis_param(transfer_basis: TransferBasis) -> &'static str {
+    match transfer_basis {
+        TransferBasis::Zero => "zero",
+        TransferBasis::Market => "market",
+    }
+}
+
+fn option_buttons<T: Copy + PartialEq + 'static>(
+    handler: &UseStateHandle<T>,
+    options: &[(T, &'static str)],
+) -> Html {
+    html! {
+        <div class="btn-group" role="group">
+        { options.iter().map(|(value, label)| {
+            let value = *value;
+            let handler = handler.clone();
+            let classes = if *handler == value { "btn btn-primary" } else { "btn btn-secondary" };
+            html!(
+                <button type="button" class={classes} onclick={move |_| handler.set(value)}>{ *label }</button>
+            )
+        }).collect::<Html>() }
+        </div>
+    }
+}
+
+fn pnl_summary(data: &WalletPnlData) -> Html {
+    html! {
+        <div class="row justify-content-center text-center">
+            <div class="col-md-4 p-0 m-2 border rounded bg-dark">
+                <ul class="list-group list-group-flush p-2">
+                    { formatting_utils::get_li_with_span_and_price(&String::from("Sold for"), &data.proceeds_usd) }
+                    { formatting_utils::get_li_with_span_and_price(&String::from("Realized gain"), &data.realized_gain_usd) }
+                </ul>
+            </div>
+            <div class="col-md-4 p-0 m-2 border rounded bg-dark">
+                <ul class="list-group list-group-flush p-2">
+                    { formatting_utils::get_li_with_span_and_price(&String::from("Cost basis"), &data.cost_basis_usd) }
+                    { formatting_utils::get_li_with_span_and_price(&String::from("Value at floor"), &data.market_value_usd) }
+                    { formatting_utils::get_li_with_span_and_price(&String::from("Unrealized gain"), &data.unrealized_gain_usd) }
+                </ul>
+            </div>
+        </div>
+    }
+}
+
+fn positions_table(positions: &Vec<WalletPnlPosition>) -> Html {
+    html! {
+        <div class="row mt-4">
+            <p class="text-white text-center fs-3 mb-3">{"Holdings"}</p>
+            if positions.is_empty() {
+                <p class="text-muted text-center">{"None"}</p>
+            } else {
+                <div class="table-responsive">
+                    <table class="table text-white border-secondary text-center">
+                        <thead>
+                            <tr>
+                                <th scope="col">{"Token"}</th>
+                                <th scope="col">{"Acquired"}</th>
+                                <th scope="col">{"Cost basis"}</th>
+                                <th scope="col">{"Floor"}</th>
+                                <th scope="col">{"Unrealized gain"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                        { positions.iter().map(|position| html! {
+                            <tr key={format!("{}{}", position.lot.token_address, position.lot.token_id)}>
+                                { lot_cells(&position.lot) }
+                                <td class="align-middle">{ optional_price(&position.floor_usd) }</td>
+                                <td class="align-middle">{ optional_price(&position.unrealized_gain_usd) }</td>
+                            </tr>
+                        }).collect::<Html>() }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </div>
+    }
+}
+
+fn sales_table(sales: &Vec<WalletPnlSale>) -> Html {
+    html! {
+        <div class="row mt-4">
+            <p class="text-white text-center fs-3 mb-3">{"Sales"}</p>
+            if sales.is_empty() {
+                <p class="text-muted text-center">{"None"}</p>
+            } else {
+                <div class="table-responsive">
+                    <table class="table text-white border-secondary text-center">
+                        <thead>
+                            <tr>
+                                <th scope="col">{"Sold"}</th>
+                                <th scope="col">{"Matched with"}</th>
+                                <th scope="col">{"Acquired"}</th>
+                                <th scope="col">{"Cost basis"}</th>
+                                <th scope="col">{"Proceeds"}</th>
+                                <th scope="col">{"Gain"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                        { sales.iter().map(|sale| html! {
+                            <tr key={format!("{}{}{}", sale.token_address, sale.token_id, sale.sold_on)}>
+                                <td class="align-middle">
+                                    <span class="d-block mb-1">{ sale.name.clone().unwrap_or_default() }</span>
+                                    <span class="d-block text-muted">{ formatting_utils::format_date(sale.sold_on) }</span>
+                                </td>
+                                if let Some(lot) = &sale.lot {
+                                    { lot_cells(lot) }
+                                } else {
+                                    <td class="align-middle text-muted">{"Unknown"}</td>
+                                    <td></td>
+                                    <td></td>
+                                }
+                                <td class="align-middle">{ formatting_utils::format_price(&sale.proceeds_usd) }</td>
+                                <td class="align-middle">{ formatting_utils::format_price(&sale.gain_usd) }</td>
+                            </tr>
+                        }).collect::<Html>() }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </div>
+    }
+}
+
+// token, acquisition and cost basis
+fn lot_cells(lot: &WalletPnlLot) -> Html {
+    html! {
+        <>
+            <td class="align-middle">
+                <span class="d-block mb-1 w-50 mx-auto">{ formatting_utils::get_asset_link(&lot.token_address, lot.token_id, &env_utils::get_image_url(&lot.thumbnail_url)) }</span>
+                <span class="d-block">{ lot.name.clone().unwrap_or_default() }</span>
+                <span class="d-block text-muted">{ lot.attribute.clone().unwrap_or_default() }</span>
+            </td>
+            <td class="align-middle">
+                <span class="d-block mb-1">{ formatting_utils::capitalize_label(&lot.acquired_by) }</span>
+                <span class="d-block text-muted">{ formatting_utils::format_date(lot.acquired_on) }</span>
+            </td>
+            <td class="align-middle">{ formatting_utils::format_price(&lot.cost_basis_usd) }</td>
+        </>
+    }
+}
+
+fn optional_price(price: &Option<Price>) -> Html {
+    match price {
+        Some(price) => formatting_utils::format_price(price),
+        None => html!(),
+    }
+}
@@ -0,0 +1,101 @@
+use crate::utils::{api_utils, formatting_utils};
+use crate::view::common::line_chart::LineChart;
+use crate::view::loading::LoadingSpinnerGrayNoVh;
+use log::error;
+use model::model::wallet::WalletPortfolioData;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    // the wallet query of the API, a wallet, a list of wallets or a portfolio
+    pub query: String,
+}
+
+#[function_component(WalletPortfolioView)]
+pub fn wallet_portfolio_view_function_component(props: &Props) -> Html {
+    let in_eth = use_state(|| false);
+    let portfolio_data = use_state(|| None);
+    {
+        let query = props.query.clone();
+        let portfolio_data = portfolio_data.clone();
+        use_effect_with(props.query.clone(), move |_| {
+            portfolio_data.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_utils::fetch_single_api_response::<WalletPortfolioData>(
+                    format!("/wallet/portfolio?{}", query).as_str(),
+                )
+                .await
+                {
+                    Ok(fetched_data) => {
+                        portfolio_data.set(Some(fetched_data));
+                    }
+                    Err(e) => {
+                        error!("{e}")
+                    }
+                }
+            });
+        });
+    }
+
+    return match (*portfolio_data).as_ref() {
+        Some(data) => portfolio_view(data, in_eth),
+        None => {
+            html! {
+                <LoadingSpinnerGrayNoVh />
+            }
+        }
+    };
+}
+
+fn portfolio_view(data: &WalletPortfolioData, in_eth: UseStateHandle<bool>) -> Html {
+    let points = data
+        .history
+        .iter()
+        .map(|value| {
+            let price = if *in_eth {
+                &value.value_eth
+            } else {
+                &value.value_usd
+            };
+            (value.date, price.price)
+        })
+        .collect::<Vec<_>>();
+    let currency_buttons = [(false, "USD"), (true, "ETH")]
+        .into_iter()
+        .map(|(value, label)| {
+            let in_eth = in_eth.clone();
+            let classes = if *in_eth == value {
+                "btn btn-primary"
+            } else {
+                "btn btn-secondary"
+            };
+            html!(
+                <button type="button" class={classes} onclick={move |_| in_eth.set(value)}>{ label }</button>
+            )
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="container-fluid p-3 bg-gray">
+            <div class="container animate__animated animate__fadeIn animate__faster">
+                <p class="text-white text-center fs-2 mb-3">{"Portfolio value"}</p>
+                <div class="row justify-content-center text-center">
+                    <div class="col-md-4 p-0 m-2 border rounded bg-dark">
+                        <ul class="list-group list-group-flush p-2">
+                            { formatting_utils::get_li_with_span_and_price(&String::from("Value in USD"), &data.value_usd) }
+                            { formatting_utils::get_li_with_span_and_price(&String::from("Value in ETH"), &data.value_eth) }
+                        </ul>
+                    </div>
+                </div>
+                if !points.is_empty() {
+                    <div class="row justify-content-center mt-3">
+                        <div class="col-auto btn-group" role="group">{ currency_buttons }</div>
+                    </div>
+                    <div class="row mt-3">
+                        <LineChart points={points} label={String::from("Daily value of the assets held")} />
+                    </div>
+                }
+            </div>
+        </div>
+    }
+}
@@ -1,26 +1,28 @@
 use crate::utils::{api_utils, formatting_utils, navigation_utils};
 use crate::view::loading::LoadingSpinnerGray;
+use crate::view::wallet::group_view::WalletGroupView;
 use log::error;
 use model::model::wallet::{TotalPerCollectionData, WalletData, WalletMoneyData};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
-    pub wallet: String,
+    // the wallet query of the API, a wallet, a list of wallets or a portfolio
+    pub query: String,
 }
 
 #[function_component(WalletDataView)]
 pub fn wallet_data_view_function_component(props: &Props) -> Html {
     let wallet_data = use_state(|| None);
     {
-        let wallet = props.wallet.clone();
+        let query = props.query.clone();
         let wallet_data = wallet_data.clone();
-        use_effect_with(props.wallet.clone(), move |_| {
+        use_effect_with(props.query.clone(), move |_| {
             wallet_data.set(None);
             navigation_utils::scroll_to_top();
             wasm_bindgen_futures::spawn_local(async move {
                 match api_utils::fetch_single_api_response::<WalletData>(
-                    format!("/wallet/wallet?wallet={}", wallet).as_str(),
+                    format!("/wallet/wallet?{}", query).as_str(),
                 )
                 .await
                 {
@@ -35,10 +37,11 @@ pub fn wallet_data_view_function_component(props: &Props) -> Html {
         });
     }
 
+    let saved = props.query.starts_with("portfolio=");
     return match (*wallet_data).as_ref() {
         Some(data) => {
             return html! {
-                { wallet_view(data) }
+                { wallet_view(data, saved) }
             };
         }
         None => {
@@ -49,7 +52,7 @@ pub fn wallet_data_view_function_component(props: &Props) -> Html {
     };
 }
 
-fn wallet_view(data: &WalletData) -> Html {
+fn wallet_view(data: &WalletData, saved: bool) -> Html {
     let minted_per_collection_wallet = &data.minted_per_collection_wallet;
     let owned_per_collection_wallet = &data.owned_per_collection_wallet;
     let money_data = &data.money_data;
@@ -57,9 +60,7 @@ fn wallet_view(data: &WalletData) -> Html {
     html! {
         <div class="container-fluid p-3 bg-gray">
             <div class="container animate__animated animate__fadeIn animate__faster">
-                <div class="row">
-                    <p class="text-white text-break fs-2 my-2">{data.wallet.clone()}</p>
-                </div>
+                <WalletGroupView wallets={data.wallets.clone()} {saved} />
                 <div class="row justify-content-center text-center">
                     {html! {wallet_mint_view(minted_per_collection_wallet)}}
                     {html! {wallet_asset_view(owned_per_collection_wallet)}}